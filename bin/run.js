@@ -7,12 +7,21 @@ const process = require('process');
 const assert = require('assert');
 const path = require('path');
 
+// With --virtual-clock, time does not pass while the program runs. Instead,
+// it jumps to the time that the next timer is due, so that tests run timers
+// quickly and in a deterministic order.
 let build = "debug";
-if (process.argv.length === 4 && process.argv[3] === "--release") {
-    build = "release";
-} else {
-    assert(process.argv.length === 3);
+let virtualClock = false;
+for (const flag of process.argv.slice(3)) {
+    if (flag === "--release") {
+        build = "release";
+    } else if (flag === "--virtual-clock") {
+        virtualClock = true;
+    } else {
+        assert(false, `unknown flag ${flag}`);
+    }
 }
+assert(process.argv.length >= 3);
 
 // Allows us to call this script from any directory.
 const runtimePath = path.normalize(path.join(path.dirname(process.argv[1]),
@@ -20,6 +29,9 @@ const runtimePath = path.normalize(path.join(path.dirname(process.argv[1]),
 
 // keep a WebAssembly memory reference for `readString`
 let memory;
+// the runtime calls getters and setters, and creates errors, through the
+// program, which is instantiated after the runtime
+let programExports;
 
 function c_string(startOffset) {
     let view = new Uint8Array(memory.buffer, startOffset);
@@ -41,6 +53,15 @@ const imports = {
         jankscripten_error: function(startOffset) {
             let view = c_string(startOffset);
             console.error((new TextDecoder()).decode(view));
+        },
+        jankscripten_call_getter: function(getter, self) {
+            return programExports.jnks_call_getter(getter, self);
+        },
+        jankscripten_call_setter: function(setter, self, value) {
+            return programExports.jnks_call_setter(setter, self, value);
+        },
+        jankscripten_new_error: function(name, message) {
+            return programExports.jnks_new_error(name, message);
         }
     },
 };
@@ -57,15 +78,33 @@ async function main(filename) {
     const programInstance = await WebAssembly.instantiate(programModule, {
         runtime: runtimeInstance.exports
     });
+    programExports = programInstance.exports;
     const exports = runtimeInstance.exports;
     memory = exports.memory;
     const startTime = Date.now();
     const result = programInstance.exports.main();
     const endTime = Date.now();
     console.error(`Running time: ${endTime - startTime}ms`);
+    await runTimers(programInstance.exports.jnks_run_timers, startTime);
     return result;
 }
 
+// Runs the timers that the program scheduled until there are none left. The
+// program's clock starts at 0 when main starts.
+async function runTimers(jnks_run_timers, startTime) {
+    let now = virtualClock ? 0 : Date.now() - startTime;
+    let next = jnks_run_timers(now);
+    while (next >= 0) {
+        if (virtualClock) {
+            now = next;
+        } else {
+            await new Promise(resolve => setTimeout(resolve, next - now));
+            now = Date.now() - startTime;
+        }
+        next = jnks_run_timers(now);
+    }
+}
+
 
 main(process.argv[2])
     .catch(err => {
@@ -0,0 +1,9 @@
+var o = { b: 1 };
+var k = 0;
+o[k] = 3;
+o[1] = 4;
+console.log(o.b);
+var x = {};
+for (var i = 0; i < 20; i++) { x[i] = i; }
+console.log(x[19]);
+console.log(x["19"]);
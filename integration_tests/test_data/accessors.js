@@ -0,0 +1,85 @@
+var point = {
+    x: 3,
+    y: 4,
+    get norm() {
+        return this.x * this.x + this.y * this.y;
+    },
+    set both(v) {
+        this.x = v;
+        this.y = v;
+    }
+};
+console.log(point.norm);
+point.both = 1;
+console.log(point.x);
+console.log(point.norm);
+
+// getters and setters are inherited
+var child = Object.create(point);
+child.both = 5;
+console.log(child.norm);
+console.log(point.x);
+
+var counter = { count: 0 };
+Object.defineProperty(counter, "next", {
+    get: function() {
+        this.count = this.count + 1;
+        return this.count;
+    }
+});
+console.log(counter.next);
+console.log(counter.next);
+// not enumerable, since defineProperty defaults to false
+for (var k in counter) {
+    console.log(k);
+}
+
+var constant = {};
+Object.defineProperty(constant, "answer", { value: 42, enumerable: true });
+constant.answer = 0;
+console.log(constant.answer);
+console.log(delete constant.answer);
+try {
+    Object.defineProperty(constant, "answer", { value: 1 });
+} catch (e) {
+    console.log(e.name);
+}
+
+var desc = Object.getOwnPropertyDescriptor(constant, "answer");
+console.log(desc.value);
+console.log(desc.writable);
+console.log(desc.enumerable);
+console.log(desc.configurable);
+console.log(Object.getOwnPropertyDescriptor(constant, "missing"));
+var accessorDesc = Object.getOwnPropertyDescriptor(point, "norm");
+console.log(accessorDesc.set);
+console.log(typeof accessorDesc.get);
+
+var frozen = Object.freeze({ a: 1 });
+frozen.a = 2;
+frozen.b = 3;
+console.log(frozen.a);
+console.log(frozen.b);
+console.log(delete frozen.a);
+
+// a getter that throws
+var bad = {
+    get oops() {
+        throw "oops";
+    }
+};
+try {
+    console.log(bad.oops);
+} catch (e) {
+    console.log(e);
+}
+
+// a read whose value is unused still runs the getter
+var noisy = {
+  get v() {
+    console.log("got v");
+    return 1;
+  },
+};
+noisy.v;
+noisy["v"];
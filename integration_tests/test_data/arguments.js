@@ -0,0 +1,71 @@
+function sum() {
+  var total = 0;
+  for (var i = 0; i < arguments.length; i++) {
+    total = total + arguments[i];
+  }
+  return total;
+}
+console.log(sum());
+console.log(sum(1, 2, 3, 4));
+
+function first(x, y) {
+  console.log(arguments.length);
+  console.log(arguments[2]);
+  return x + y;
+}
+console.log(first(1, 2, 30));
+console.log(first(5, 6));
+
+function outer(a) {
+  var inner = () => arguments[0] + a;
+  function nested() {
+    return arguments.length;
+  }
+  console.log(nested(1, 2, 3));
+  return inner();
+}
+console.log(outer(10));
+
+function withRest(a, ...rest) {
+  return arguments.length + rest.length;
+}
+console.log(withRest(1, 2, 3));
+
+function describe(greeting) {
+  return greeting + " " + this.name + " " + arguments.length;
+}
+var person = { name: "Ada" };
+console.log(describe.call(person, "hi", "extra"));
+console.log(describe.apply(person, ["hello"]));
+
+function count(a, b) {
+  return arguments.length;
+}
+console.log(count(1));
+console.log(count());
+
+function each(f) {
+  return f(1, 2, 3);
+}
+console.log(each(count));
+console.log(each(function () {
+  return arguments[1] + arguments.length;
+}));
+
+var h = count;
+h = 3;
+h = count;
+console.log(h(1, 2, 3, 4));
+var obj = { count: count };
+console.log(obj.count(1));
+
+var k = 0;
+function next() {
+  k = k + 1;
+  return k;
+}
+function pick(a, b) {
+  return arguments.length * 10 + a;
+}
+console.log(pick(next(), next(), next()));
+console.log(k);
@@ -0,0 +1,57 @@
+// functions that are called through an any may be given more or fewer
+// arguments than they take
+function two(a, b) {
+  return a + "," + b;
+}
+var o = { f: two };
+console.log(o.f(1));
+console.log(o.f(1, 2, 3));
+console.log(o.f());
+
+function callWithThree(f) {
+  return f(1, 2, 3);
+}
+function callWithNone(f) {
+  return f();
+}
+console.log(callWithThree(function (x) { return x * 10; }));
+console.log(callWithThree(function () { return "none"; }));
+console.log(callWithNone(function (x, y) { return typeof x + " " + typeof y; }));
+console.log(callWithNone(two));
+
+var fs = [two, function (x) { return x; }];
+console.log(fs[0].apply(null, [5]));
+console.log(fs[1].apply(null, [6, 7, 8]));
+console.log(fs[0].call(null, 9));
+
+var counter = {
+  n: 0,
+  add: function (k) {
+    this.n = this.n + (k === undefined ? 1 : k);
+    return this.n;
+  }
+};
+counter.add();
+counter.add(5, "ignored");
+console.log(counter.n);
+
+Promise.resolve(1).then(function () {
+  console.log("then without a parameter");
+});
+new Promise(function (resolve) {
+  resolve("resolved");
+}).then(function (v) {
+  console.log(v);
+});
+setTimeout(function () {
+  console.log("timeout");
+});
+
+// and there is no limit on the number of arguments
+function callWithNine(f) {
+  return f(1, 2, 3, 4, 5, 6, 7, 8, 9);
+}
+console.log(callWithNine(function (a, b) { return a + b; }));
+console.log(callWithNine(function (a, b, c, d, e, f, g, h, i, j) {
+  return "" + a + b + c + d + e + f + g + h + i + j;
+}));
@@ -0,0 +1,20 @@
+function makeAdder(x) {
+    return y => x + y;
+}
+log_any(makeAdder(4)(7));
+
+var twice = (f, x) => { return f(f(x)); };
+log_any(twice(y => y * 3, 2));
+
+function Counter() {
+    this.count = 0;
+    this.incr = () => {
+        this.count = this.count + 1;
+        return this.count;
+    };
+}
+var c = new Counter();
+c.incr();
+var incr = c.incr;
+log_any(incr());
+log_any(c.count);
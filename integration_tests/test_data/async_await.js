@@ -0,0 +1,68 @@
+function delay(v) {
+    return new Promise(function (resolve, reject) { resolve(v); });
+}
+async function add(a, b) {
+    var x = await delay(a);
+    var y = await b;
+    return x + y;
+}
+async function fails(msg) {
+    await 1;
+    throw Error(msg);
+}
+async function main() {
+    console.log("main");
+    var s = await add(1, 2);
+    console.log(s);
+    try {
+        await fails("boom");
+    } catch (e) {
+        console.log(e.message);
+    }
+    return "done";
+}
+main().then(function (v) { console.log(v); }, undefined);
+console.log("sync");
+class Counter {
+    constructor() { this.n = 0; }
+    async incr(by) {
+        await null;
+        this.n = this.n + by;
+        return this.n;
+    }
+}
+var c = new Counter();
+var twice = async (x) => {
+    var a = await c.incr(x);
+    var b = await c.incr(x);
+    return a + b;
+};
+var obj = {
+    async get(k) { return k + "!"; }
+};
+twice(5).then(function (v) { console.log(v); }, undefined);
+obj.get("key").then(function (v) { console.log(v); }, undefined);
+async function loop(n) {
+    var total = 0;
+    for (var i = 0; i < n; i++) {
+        total = total + await i;
+    }
+    return total;
+}
+loop(5).then(function (v) { console.log(v); }, undefined);
+async function order(name, n) {
+    for (var i = 0; i < n; i++) {
+        console.log(name + i);
+        await undefined;
+    }
+}
+order("x", 3);
+order("y", 2);
+async function finallyAwaits() {
+    try {
+        return "returned";
+    } finally {
+        console.log("finally " + await "awaited");
+    }
+}
+finallyAwaits().then((v) => { console.log(v); });
@@ -0,0 +1,71 @@
+// BigInt literals and arithmetic, which are exact beyond 2^53
+var a = 12345678901234567890n;
+var b = 98765432109876543210n;
+console.log(a + b);
+console.log(a - b);
+console.log(a * b);
+console.log(b / a);
+console.log(b % a);
+console.log(-7n / 2n);
+console.log(-7n % 2n);
+console.log(-a);
+console.log(a < b);
+console.log(a >= b);
+console.log(a * 2n === a + a);
+console.log(typeof a);
+console.log("a = " + a);
+console.log(String(b));
+
+// FNV-1 over bytes, modulo 2^64
+function fnv1(bytes) {
+    var hash = 14695981039346656037n;
+    var prime = 1099511628211n;
+    var modulus = 18446744073709551616n;
+    for (var i = 0; i < bytes.length; i++) {
+        hash = (hash * prime + BigInt(bytes[i])) % modulus;
+    }
+    return hash;
+}
+console.log(fnv1([106, 97, 110, 107]));
+
+// Modular exponentiation
+function modpow(base, exp, m) {
+    var result = 1n;
+    base = base % m;
+    while (exp > 0n) {
+        if (exp % 2n === 1n) {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp = exp / 2n;
+    }
+    return result;
+}
+console.log(modpow(2n, 127n, 170141183460469231731687303715884105727n));
+console.log(modpow(3n, 1000n, 1000000007n));
+
+// BigInts in untyped positions
+var xs = [1n, 2n, 3n];
+var total = 0n;
+for (var i = 0; i < xs.length; i++) {
+    total = total + xs[i];
+}
+console.log(total);
+console.log(xs[0] === 1n);
+console.log(BigInt(42) + BigInt("100"));
+
+try {
+    console.log(a + 1);
+} catch (e) {
+    console.log(e.name + ": " + e.message);
+}
+try {
+    console.log(a / 0n);
+} catch (e) {
+    console.log(e.name + ": " + e.message);
+}
+try {
+    BigInt(1.5);
+} catch (e) {
+    console.log(e.name);
+}
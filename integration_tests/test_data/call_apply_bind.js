@@ -0,0 +1,107 @@
+function greet(greeting, punct) {
+  return greeting + ", " + this.name + punct;
+}
+var alice = { name: "Alice" };
+var bob = { name: "Bob" };
+
+// call and apply supply `this`
+console.log(greet.call(alice, "Hello", "!"));
+console.log(greet.call(bob, "Hi"));
+console.log(greet.apply(alice, ["Hey", "?"]));
+console.log(greet.apply(bob, ["Yo"]));
+
+// a method that is read from an object can be called on another object
+var counter = {
+  count: 10,
+  add: function (n) {
+    return this.count + n;
+  },
+};
+var add = counter.add;
+console.log(add.call({ count: 1 }, 2));
+console.log(counter.add.apply({ count: 5 }, [5]));
+
+// ES5-style inheritance calls the parent constructor and methods with call
+function Shape(name) {
+  this.name = name;
+}
+Shape.prototype.describe = function (suffix) {
+  return "a " + this.name + suffix;
+};
+function Square(side) {
+  Shape.call(this, "square");
+  this.side = side;
+}
+Square.prototype = Object.create(Shape.prototype);
+Square.prototype.describe = function (suffix) {
+  return Shape.prototype.describe.call(this, " of side " + this.side) + suffix;
+};
+var sq = new Square(3);
+console.log(sq.describe("!"));
+
+// bind carries the receiver and any prefix arguments
+var hi = greet.bind(alice, "Hi");
+console.log(hi("."));
+var hello = greet.bind(bob);
+console.log(hello("Hello", "..."));
+var bound = [];
+for (var i = 0; i < 3; i++) {
+  bound.push(greet.bind({ name: "n" + i }, "#" + i));
+}
+console.log(bound[0]("") + " " + bound[2](""));
+
+// rest parameters
+function total(label, ...nums) {
+  var sum = 0;
+  for (var j = 0; j < nums.length; j++) {
+    sum = sum + nums[j];
+  }
+  return label + this.unit + sum;
+}
+var money = { unit: "$" };
+console.log(total.call(money, "total: ", 1, 2, 3));
+console.log(total.apply(money, ["total: ", 4, 5]));
+var withLabel = total.bind(money, "bound: ", 10);
+console.log(withLabel(20, 30));
+
+// an object may have its own methods called call, apply and bind
+var own = {
+  call: function (x) {
+    return "own call " + x;
+  },
+  bind: function (x) {
+    return "own bind " + x;
+  },
+};
+console.log(own.call(1));
+console.log(own.bind(2));
+
+// functions inherit call, apply and bind from Function.prototype
+console.log(typeof greet.call);
+console.log(typeof Function.prototype.bind);
+console.log(greet.hasOwnProperty("call"));
+console.log(Function.prototype.apply === greet.apply);
+
+// `new` on a bound function constructs with the target and the bound
+// arguments, and ignores the bound `this`
+function Pt(x, y) {
+  this.x = x;
+  this.y = y;
+}
+var BoundPt = Pt.bind({}, 8);
+var bp = new BoundPt(9);
+console.log(bp.x + bp.y);
+console.log(bp instanceof Pt);
+var NullPt = Pt.bind(null, 1, 2);
+console.log(new NullPt().y);
+
+// through Function.prototype.bind itself
+var bindFn = Pt.bind;
+var Late = bindFn.call(Pt, null, 3);
+var late = new Late(4);
+console.log(late.x * 10 + late.y);
+function sum3(a, b, c) {
+  return this.k + a + b + c;
+}
+var bound3 = bindFn.call(sum3, { k: 1 }, 2);
+console.log(bound3(3, 4));
@@ -0,0 +1,99 @@
+class Animal {
+    constructor(name) {
+        this.name = name;
+    }
+    speak() {
+        return this.name + " makes a sound";
+    }
+    static create(name) {
+        return new Animal(name);
+    }
+}
+
+class Dog extends Animal {
+    constructor(name, breed) {
+        super(name);
+        this.breed = breed;
+    }
+    speak() {
+        return super.speak() + " (woof)";
+    }
+}
+
+class Puppy extends Dog {
+    constructor(name) {
+        super(name, "pug");
+    }
+    speak() {
+        return "small " + super.speak();
+    }
+}
+
+var a = Animal.create("cat");
+console.log(a.speak());
+var d = new Dog("rex", "lab");
+console.log(d.speak());
+console.log(d.breed);
+var p = new Puppy("bit");
+console.log(p.breed);
+console.log(p.speak());
+log_any(p instanceof Puppy);
+log_any(p instanceof Dog);
+log_any(p instanceof Animal);
+log_any(d instanceof Puppy);
+
+var Point = class {
+    constructor(x, y) {
+        this.x = x;
+        this.y = y;
+    }
+    norm1() {
+        return this.x + this.y;
+    }
+};
+var pt = new Point(3, 4);
+log_any(pt.norm1());
+var made = Dog.create("inherited static");
+console.log(made.speak());
+
+class Base {
+    constructor() {
+        this.ready = true;
+    }
+}
+class Derived extends Base {
+    check() {
+        return this.ready;
+    }
+}
+log_any(new Derived().check());
+
+var Counter = class Count {
+    static down(n) {
+        return n <= 0 ? 0 : 1 + Count.down(n - 1);
+    }
+};
+log_any(Counter.down(3));
+for (var key in p) {
+    console.log(key);
+}
+
+// a class may refer to itself in its constructor
+class Self {
+    constructor() {
+        this.cls = Self;
+        this.target = new.target === Self;
+    }
+}
+var self = new Self();
+console.log(self.cls === Self);
+console.log(self.target);
+
+// a class can only be called with new
+class Plain {}
+try {
+    Plain();
+    console.log("no error");
+} catch (e) {
+    console.log(e.name);
+}
@@ -0,0 +1,177 @@
+function Point(x, y) {
+  this.x = x;
+  this.y = y;
+}
+var p = new Point(1, 2);
+console.log(p.x + p.y);
+
+// a constructor that returns an object replaces `this`
+function Replaced() {
+  this.a = 1;
+  return { b: 2 };
+}
+var r = new Replaced();
+console.log(r.a === undefined);
+console.log(r.b);
+
+// a constructor that returns a primitive does not
+function Primitive() {
+  this.a = 3;
+  return 4;
+}
+console.log(new Primitive().a);
+
+function Target() {
+  return new.target === Target;
+}
+console.log(Target());
+console.log(new Target() instanceof Target);
+
+function Arrow() {
+  var f = () => new.target;
+  this.same = f() === Arrow;
+}
+console.log(new Arrow().same);
+
+class Base {
+  constructor() {
+    this.target = new.target;
+  }
+}
+class Derived extends Base {
+  constructor() {
+    super();
+  }
+}
+console.log(new Derived().target === Derived);
+console.log(new Base().target === Base);
+
+function Outer() {
+  this.inner = new Inner();
+  this.target = new.target;
+}
+function Inner() {
+  this.target = new.target;
+}
+var o = new Outer();
+console.log(o.target === Outer);
+console.log(o.inner.target === Inner);
+
+var notConstructors = [5, "f", undefined, { x: 1 }];
+for (var i = 0; i < notConstructors.length; i++) {
+  try {
+    new notConstructors[i]();
+    console.log("no error");
+  } catch (e) {
+    console.log(e.name);
+  }
+}
+
+function Throws() {
+  throw "thrown";
+}
+try {
+  new Throws();
+} catch (e) {
+  console.log(e);
+}
+console.log(new Target() instanceof Target);
+
+// new.target belongs to the call, not to `this`
+function Helper() {
+  return new.target;
+}
+function WithHelper() {
+  this.t = Helper.call(this);
+}
+console.log(new WithHelper().t === undefined);
+
+var leaked;
+function Leaky() {
+  leaked = this;
+  throw "leaky";
+}
+try {
+  new Leaky();
+} catch (e) {
+  console.log(e);
+}
+console.log(Helper.call(leaked) === undefined);
+
+// through an any, and with its arguments
+function Sum(a, b) {
+  this.sum = a + b;
+  this.target = new.target;
+}
+var make = Sum;
+make = 3;
+make = Sum;
+var s = new make(1, 2, 3);
+console.log(s.sum);
+console.log(s.target === Sum);
+console.log(Helper(4, 5) === undefined);
+
+class Shape {
+  constructor(sides) {
+    this.sides = sides;
+    this.kind = new.target;
+  }
+}
+class Square extends Shape {
+  constructor(len) {
+    super(4);
+    this.len = len;
+  }
+}
+class Plain extends Shape {}
+var sq = new Square(3);
+console.log(sq.sides + sq.len);
+console.log(sq.kind === Square);
+var pl = new Plain(5);
+console.log(pl.sides);
+console.log(pl.kind === Plain);
+
+// arrows and methods are not constructors
+var arrow = () => 1;
+var obj = {
+  m() {
+    return 2;
+  },
+};
+class HasMethod {
+  m() {
+    return 3;
+  }
+}
+var methods = [arrow, obj.m, new HasMethod().m];
+for (var j = 0; j < methods.length; j++) {
+  try {
+    new methods[j]();
+    console.log("no error");
+  } catch (e) {
+    console.log(e.name);
+  }
+}
+console.log(arrow() + obj.m() + new HasMethod().m());
+
+// generator and async functions, and the functions of Math, are not
+// constructors either
+function* gen() {
+  yield 1;
+}
+async function later() {
+  return 1;
+}
+var genExpr = function* () {
+  yield 2;
+};
+var others = [gen, later, genExpr, Math.max];
+for (var k = 0; k < others.length; k++) {
+  try {
+    new others[k]();
+    console.log("no error");
+  } catch (e) {
+    console.log(e.name);
+  }
+}
+console.log(gen().next().value + genExpr().next().value + Math.max(1, 2));
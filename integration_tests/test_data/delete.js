@@ -0,0 +1,65 @@
+function getX(o) {
+    return o.x;
+}
+function setX(o, v) {
+    o.x = v;
+}
+
+var a = { x: 1, y: 2, z: 3 };
+var b = { x: 1, y: 2, z: 3 };
+log_any(getX(a));
+log_any(delete a.x);
+log_any(getX(a));
+log_any(getX(b));
+log_any(a.y + a.z);
+
+// re-adding a deleted field puts it at the end
+setX(a, 4);
+setX(b, 5);
+log_any(getX(a));
+log_any(getX(b));
+var order = "";
+for (var k in a) {
+    order = order + k;
+}
+console.log(order);
+
+// objects that delete the same field still agree on where the others are
+var c = { x: 1, y: 2, z: 3 };
+delete c.y;
+delete b.y;
+log_any(b.z + c.z);
+
+var field = "z";
+delete c[field];
+log_any(c.z);
+log_any(delete c.missing);
+
+// deleting a field that shadows the prototype exposes it again
+var proto = { greeting: "hello" };
+var obj = Object.create(proto);
+obj.greeting = "hi";
+console.log(obj.greeting);
+delete obj.greeting;
+console.log(obj.greeting);
+delete obj.greeting;
+console.log(obj.greeting);
+
+// arrays get holes
+var xs = [1, 2, 3];
+log_any(delete xs[1]);
+log_any(xs.length);
+log_any(xs[1]);
+log_any(xs[2]);
+log_any(1 in xs);
+var indices = "";
+for (var i in xs) {
+    indices = indices + i;
+}
+console.log(indices);
+var [first, second] = xs;
+log_any(second);
+xs[1] = 5;
+log_any(1 in xs);
+
+log_any(delete 23);
@@ -0,0 +1,56 @@
+var [a, b] = [1, 2];
+log_any(a + b);
+
+var [first, , third, ...others] = [10, 20, 30, 40, 50];
+log_any(first);
+log_any(third);
+log_any(others.length);
+log_any(others[1]);
+
+var { x, y: why, z = 7 } = { x: 3, y: 4 };
+log_any(x);
+log_any(why);
+log_any(z);
+
+var { p: { q: [deep] } } = { p: { q: [99] } };
+log_any(deep);
+
+var { kept, ...restObj } = { kept: 1, r1: 2, r2: 3 };
+log_any(kept);
+log_any(restObj.r1 + restObj.r2);
+log_any(restObj.kept);
+
+// swap with a destructuring assignment
+var m = 5, n = 6;
+[m, n] = [n, m];
+log_any(m * 10 + n);
+
+var obj = {};
+({ a: obj.fieldA, b: obj.fieldB } = { a: "A", b: "B" });
+console.log(obj.fieldA + obj.fieldB);
+
+function sumPair([l, r]) {
+    return l + r;
+}
+log_any(sumPair([8, 9]));
+
+function describe({ name, age = 30 }) {
+    return name + " is " + age;
+}
+console.log(describe({ name: "Ann" }));
+console.log(describe({ name: "Bob", age: 4 }));
+
+var pairs = [[1, 2], [3, 4]];
+var total = 0;
+for (var pair of pairs) {
+    var [u, v] = pair;
+    total = total + u * v;
+}
+log_any(total);
+
+var [d1 = 1, d2 = 2] = [undefined, 20];
+log_any(d1 + d2);
+
+var mapped = [[1, 2], [5, 6]].length;
+var add = ([s, t]) => s + t;
+log_any(add([mapped, 40]));
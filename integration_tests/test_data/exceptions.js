@@ -0,0 +1,129 @@
+function fail(msg) {
+    throw Error(msg);
+}
+
+function depth(n) {
+    var arr = [n];
+    if (n === 0) {
+        fail("bottom");
+    }
+    return depth(n - 1) + arr[0];
+}
+
+try {
+    depth(5);
+    console.log("unreachable");
+} catch (e) {
+    console.log(e.message);
+}
+
+// values of any type can be thrown
+try {
+    throw 42;
+} catch (e) {
+    console.log(e + 1);
+}
+
+function withFinally(x) {
+    try {
+        if (x > 0) {
+            return "positive";
+        }
+        fail("negative");
+    } finally {
+        console.log("finally " + x);
+    }
+    return "unreachable";
+}
+
+console.log(withFinally(1));
+try {
+    withFinally(-1);
+} catch (e) {
+    console.log("caught " + e.message);
+}
+
+var log = "";
+for (var i = 0; i < 5; i++) {
+    try {
+        if (i === 1) {
+            continue;
+        }
+        if (i === 3) {
+            break;
+        }
+        log += i;
+    } finally {
+        log += "f";
+    }
+}
+console.log(log);
+
+function rethrow() {
+    try {
+        fail("inner");
+    } catch (e) {
+        throw Error("outer after " + e.message);
+    } finally {
+        console.log("rethrow finally");
+    }
+}
+
+try {
+    rethrow();
+} catch (e) {
+    console.log(e.message);
+}
+
+function finallyOverrides() {
+    try {
+        return 1;
+    } finally {
+        return 2;
+    }
+}
+console.log(finallyOverrides());
+
+function nested() {
+    var r = "";
+    try {
+        try {
+            throw "a";
+        } finally {
+            r += "1";
+        }
+    } catch (e) {
+        r += e;
+    } finally {
+        r += "2";
+    }
+    return r;
+}
+console.log(nested());
+
+// exceptions thrown from callbacks unwind through the caller
+var obj = {
+    m: function (f) {
+        var before = "before";
+        f();
+        return before;
+    },
+};
+try {
+    obj.m(function () {
+        throw "from callback";
+    });
+} catch (e) {
+    console.log(e);
+}
+
+// the shadow stack is intact after unwinding, so allocation still works
+var total = 0;
+for (var j = 0; j < 2000; j++) {
+    try {
+        depth(3);
+    } catch (e) {
+        total += e.message.length;
+    }
+}
+console.log(total);
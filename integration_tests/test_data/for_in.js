@@ -0,0 +1,89 @@
+var point = { x: 1, y: 2 };
+point.z = 3;
+var names = "";
+for (var k in point) {
+    names = names + k;
+}
+console.log(names);
+
+var base = { a: 1, shared: 2 };
+var derived = Object.create(base);
+derived.b = 3;
+derived.shared = 4;
+var seen = "";
+for (let key in derived) {
+    seen = seen + key + "=" + derived[key] + ";";
+}
+console.log(seen);
+
+var indices = "";
+for (var i in [10, 20, 30]) {
+    indices = indices + i;
+}
+console.log(indices);
+
+var chars = "";
+for (var c in "hey") {
+    chars = chars + c;
+}
+console.log(chars);
+
+var fns = [];
+for (let f in { p: 1, q: 2 }) {
+    fns.push(function () {
+        return f;
+    });
+}
+console.log(fns[0]() + fns[1]());
+
+var count = 0;
+for (var m in { skip: 1, one: 2, stop: 3, never: 4 }) {
+    if (m == "skip") {
+        continue;
+    }
+    if (m == "stop") {
+        break;
+    }
+    count = count + 1;
+}
+log_any(count);
+
+var nothing = 0;
+var u = undefined;
+for (var n in u) {
+    nothing = nothing + 1;
+}
+var nl = null;
+for (var n in nl) {
+    nothing = nothing + 1;
+}
+log_any(nothing);
+
+function* keys(o) {
+    for (var key in o) {
+        yield key;
+    }
+}
+var gen = "";
+for (var g of keys({ first: 1, second: 2 })) {
+    gen = gen + g + ",";
+}
+console.log(gen);
+
+// a key that is deleted before the loop reaches it is skipped
+var deleted = { p: 1, r: 2, s: 3 };
+var visited = "";
+for (var d in deleted) {
+    visited = visited + d;
+    if (d === "p") {
+        delete deleted.r;
+    }
+}
+console.log(visited);
+var holes = [1, 2, 3];
+var indices = "";
+for (var h in holes) {
+    indices = indices + h;
+    delete holes[2];
+}
+console.log(indices);
@@ -0,0 +1,39 @@
+var sum = 0;
+for (var x of [1, 2, 3, 4]) {
+    sum = sum + x;
+}
+log_any(sum);
+
+var chars = "";
+for (var c of "abc") {
+    chars = c + chars;
+}
+console.log(chars);
+
+function count(xs) {
+    var n = 0;
+    var y;
+    for (y of xs) {
+        if (y == 2) {
+            continue;
+        }
+        if (y == 5) {
+            break;
+        }
+        n = n + 1;
+    }
+    return n;
+}
+log_any(count([1, 2, 3, 5, 6]));
+var anything = [10, 20];
+anything = "hi";
+log_any(count(anything));
+
+outer: for (var a of [1, 2]) {
+    for (var b of [1, 2]) {
+        if (b == 2) {
+            continue outer;
+        }
+        log_any(a * 10 + b);
+    }
+}
@@ -0,0 +1,135 @@
+function* count(n) {
+  for (var i = 0; i < n; i++) {
+    yield i;
+  }
+  return "end";
+}
+var it = count(3);
+var r = it.next();
+console.log(r.value + " " + r.done);
+r = it.next(); console.log(r.value + " " + r.done);
+r = it.next(); console.log(r.value + " " + r.done);
+r = it.next(); console.log(r.value + " " + r.done);
+r = it.next(); console.log(r.value + " " + r.done);
+for (var x of count(4)) console.log(x);
+console.log([...count(5)].length);
+var [a, b] = count(10);
+console.log(a + " " + b);
+function* fib() {
+  var a = 0, b = 1;
+  while (true) {
+    yield a;
+    var t = a + b;
+    a = b;
+    b = t;
+  }
+}
+var f = fib();
+var fibs = "";
+for (var k = 0; k < 10; k++) { fibs = fibs + f.next().value + " "; }
+console.log(fibs);
+function* guarded() {
+  try {
+    yield 1;
+    yield 2;
+  } catch (err) {
+    console.log("caught " + err);
+    yield 3;
+  } finally {
+    console.log("cleanup");
+  }
+}
+var g = guarded();
+console.log(g.next().value);
+console.log(g.throw("boom").value);
+console.log(g.next().done);
+var h = guarded();
+h.next();
+var hr = h.return(42);
+console.log(hr.value + " " + hr.done);
+function* outer() { yield 0; yield* count(2); yield* [7, 8]; yield 9; }
+var all = [...outer()];
+var joined = "";
+for (var j = 0; j < all.length; j++) { joined = joined + all[j] + " "; }
+console.log(joined);
+class Tree {
+  constructor(l, v, r) { this.l = l; this.v = v; this.r = r; }
+  *[Symbol.iterator]() {
+    if (this.l) yield* this.l;
+    yield this.v;
+    if (this.r) yield* this.r;
+  }
+}
+var t = new Tree(new Tree(null, 1, null), 2, new Tree(null, 3, null));
+for (const v of t) console.log(v);
+var obj = {
+  base: 10,
+  *items(n = 2) {
+    for (let i = 0; i < n; i++) yield this.base + i;
+  }
+};
+for (const v of obj.items(undefined)) console.log(v);
+for (const v of obj.items(3)) console.log(v);
+var gen = function* (xs) {
+  outer: for (var i = 0; i < xs.length; i++) {
+    for (var j = 0; j < xs[i].length; j++) {
+      if (xs[i][j] < 0) break outer;
+      if (xs[i][j] === 0) continue outer;
+      yield xs[i][j];
+    }
+  }
+  return -1;
+};
+console.log([...gen([[1, 2], [0, 9], [3, -1, 4], [5]])].length);
+for (var v of gen([[1, 2], [0, 9], [3, -1, 4], [5]])) console.log(v);
+function* nested() {
+  function* inner(k) { yield k; yield k * 2; }
+  var e = "outer e";
+  try {
+    yield* inner(1);
+    throw "bad";
+  } catch (e) {
+    yield e;
+    try { throw "inner"; } catch (e) { console.log(e); }
+    yield e;
+  }
+  yield e;
+}
+for (var v of nested()) console.log(v);
+function* withFinally() {
+  try {
+    yield 1;
+    return 2;
+  } finally {
+    console.log("finally ran");
+  }
+}
+var w = withFinally();
+console.log(w.next().value);
+var wr = w.next();
+console.log(wr.value + " " + wr.done);
+function* early() { console.log("started"); yield 1; }
+var ea = early();
+var er = ea.return(5);
+console.log(er.value + " " + er.done);
+console.log(ea.next().done);
+try { early().throw("never started"); } catch (x) { console.log("thrown " + x); }
+function* thrower() { yield 1; throw "oops"; }
+var th = thrower();
+th.next();
+try { th.next(); } catch (x) { console.log("caught " + x); }
+console.log(th.next().done);
+function* lazy(xs) { for (const x of xs) { if (x > 2) { yield x * 10; } } }
+const [p, q] = lazy([1, 3, 2, 5, 7]);
+console.log(p + q);
+function* echo() {
+  var got = yield "first";
+  while (true) {
+    got = yield "got " + got;
+  }
+}
+var ec = echo();
+console.log(ec.next("ignored").value);
+console.log(ec.next("a").value);
+console.log(ec.next().value);
+console.log(ec.next(1, 2).value);
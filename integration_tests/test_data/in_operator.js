@@ -0,0 +1,47 @@
+var opts = { verbose: true, level: 0 };
+log_any("verbose" in opts);
+log_any("quiet" in opts);
+if ("level" in opts) {
+    console.log("has level");
+}
+
+// `in` searches the prototype chain, but hasOwnProperty does not
+var base = { inherited: 1 };
+var derived = Object.create(base);
+derived.own = 2;
+log_any("inherited" in derived);
+log_any("own" in derived);
+log_any(derived.hasOwnProperty("own"));
+log_any(derived.hasOwnProperty("inherited"));
+log_any(opts.hasOwnProperty("toString"));
+log_any("toString" in opts);
+
+// undefined is still a value
+var o = { u: undefined };
+log_any("u" in o);
+log_any(o.hasOwnProperty("u"));
+delete o.u;
+log_any("u" in o);
+
+var key = 1;
+var numbered = { 1: "one" };
+log_any(key in numbered);
+
+var xs = [10, 20, 30];
+log_any(0 in xs);
+log_any(2 in xs);
+log_any(3 in xs);
+log_any("1" in xs);
+log_any("length" in xs);
+
+function f() {}
+f.extra = 1;
+log_any("extra" in f);
+log_any("prototype" in f);
+
+// the right-hand side must be an object
+try {
+    log_any("x" in 5);
+} catch (e) {
+    console.log(e.name);
+}
@@ -0,0 +1,41 @@
+var a = [1, 2, 3];
+for (var x of a) { console.log(x); }
+var b = [...a, ...'hi'];
+console.log(b.length);
+var [p, q, ...r] = a;
+console.log(p + q + r.length);
+function range(n) {
+  var obj = {};
+  obj[Symbol.iterator] = function () {
+    var i = 0;
+    return { next: function () { i = i + 1; return { value: i, done: i > n }; } };
+  };
+  return obj;
+}
+for (var x of range(3)) { console.log(x); }
+var arr = [...range(4), 10];
+console.log(arr.length);
+var [c, d] = range(100);
+console.log(c + d);
+var it = { [Symbol.iterator]() { return { n: 0, next() { this.n++; return { value: this.n * 2, done: this.n > 2 }; } }; } };
+for (const v of it) console.log(v);
+try { for (var z of 5) {} } catch (e) { console.log(e.name + ": " + e.message); }
+function closing(n) {
+  var i = 0;
+  var iterator = {
+    next: function () { i = i + 1; return { value: i, done: i > n }; },
+    return: function () { console.log("closed at " + i); return { done: true }; }
+  };
+  var obj = {};
+  obj[Symbol.iterator] = function () { return iterator; };
+  return obj;
+}
+for (var x of closing(2)) { }
+for (var x of closing(5)) { if (x === 3) break; }
+function firstOf(v) { for (var x of v) { return x; } }
+console.log(firstOf(closing(5)));
+try { for (var x of closing(5)) { throw "thrown at " + x; } } catch (e) { console.log(e); }
+outer: for (var x of closing(2)) { for (var y of closing(5)) { continue outer; } }
+var [e1, e2] = closing(5);
+var [e3, ...others] = closing(2);
+console.log(e1 + e2 + others.length);
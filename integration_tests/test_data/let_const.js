@@ -0,0 +1,83 @@
+// each iteration of a loop has its own binding
+var fs = [];
+for (let i = 0; i < 3; i++) {
+    fs.push(function() { return i; });
+}
+console.log(fs[0]() + fs[1]() + fs[2]());
+
+// shadowing in a nested block does not affect the outer variable
+let x = 1;
+{
+    let x = 2;
+    console.log(x);
+    {
+        const x = 3;
+        console.log(x);
+    }
+}
+console.log(x);
+
+function shadow(x) {
+    if (x > 0) {
+        let x = "inner";
+        return x;
+    }
+    return x;
+}
+console.log(shadow(1));
+console.log(shadow(0));
+
+// changes to the loop variable in the body carry over to the next
+// iteration, and continue still runs the update
+var gs = [];
+for (let j = 0; j < 6; j++) {
+    if (j == 1) {
+        continue;
+    }
+    let k = j * 10;
+    gs.push(function() { return j + k; });
+    j++;
+}
+for (var n = 0; n < gs.length; n++) {
+    console.log(gs[n]());
+}
+
+var hs = [];
+for (const s of ["a", "b", "c"]) {
+    hs.push(() => s);
+}
+console.log(hs[0]() + hs[1]() + hs[2]());
+
+var ws = [];
+var w = 0;
+while (w < 3) {
+    let v = w * w;
+    ws.push(() => v);
+    w++;
+}
+console.log(ws[0]() + ws[1]() + ws[2]());
+
+outer: for (let a = 0; a < 3; a++) {
+    for (let b = 0; b < 3; b++) {
+        if (b == 1) {
+            continue outer;
+        }
+        ws.push(() => a * 10 + b);
+    }
+}
+console.log(ws[3]() + ws[4]() + ws[5]());
+
+// a use that may run before the declaration throws when it does
+if (false) {
+  console.log(late);
+}
+function readLate() {
+  return late;
+}
+try {
+  readLate();
+} catch (e) {
+  console.log(e.name);
+}
+let late = "late";
+console.log(readLate());
@@ -0,0 +1,27 @@
+// The entry module of a program with several modules, which are in the
+// modules directory.
+import { count, increment } from "./modules/counter.js";
+import describe, { name as shapeName, Square } from "./modules/shapes.js";
+import * as geometry from "./modules/geometry.js";
+import { twice } from "./modules/../modules/util.js";
+import { isEven } from "./modules/even.js";
+
+// modules.js has its own helper, which does not collide with the helpers in
+// util.js and shapes.js
+function helper(x) {
+    return "main helper " + x;
+}
+
+console.log(count);
+increment();
+increment();
+console.log(count);
+console.log(describe(new Square(3)));
+console.log(shapeName);
+console.log(geometry.area(new Square(2)));
+console.log(geometry.twice(21));
+console.log(geometry.origin);
+console.log(twice(5));
+console.log(helper(1));
+console.log(isEven(10));
+console.log(isEven(7));
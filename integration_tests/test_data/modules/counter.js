@@ -0,0 +1,8 @@
+// Imports are live bindings, so importers see the new count
+export let count = 0;
+
+export function increment() {
+    count = count + 1;
+}
+
+console.log("counter.js runs once");
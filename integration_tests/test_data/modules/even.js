@@ -0,0 +1,10 @@
+// even.js and odd.js import each other. Function declarations are hoisted,
+// so the cycle works.
+import { isOdd } from "./odd.js";
+
+export function isEven(n) {
+    if (n === 0) {
+        return true;
+    }
+    return isOdd(n - 1);
+}
@@ -0,0 +1,10 @@
+// Re-exports from other modules
+export * from "./util.js";
+export { Square } from "./shapes.js";
+
+export function area(square) {
+    return square.side * square.side;
+}
+
+export default 42;
+export const origin = 0;
@@ -0,0 +1,18 @@
+import { twice } from "./util.js";
+
+function helper(shape) {
+    return "a square with sides " + shape.side;
+}
+
+export class Square {
+    constructor(side) {
+        this.side = side;
+    }
+}
+
+const shapeName = "square";
+export { shapeName as name };
+
+export default function (shape) {
+    return helper(shape) + " and perimeter " + twice(twice(shape.side));
+}
@@ -0,0 +1,9 @@
+function helper(x) {
+    return x * 2;
+}
+
+export function twice(x) {
+    return helper(x);
+}
+
+console.log("util.js runs once");
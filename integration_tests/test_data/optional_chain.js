@@ -0,0 +1,54 @@
+var count = 0;
+function next() {
+    count = count + 1;
+    return count;
+}
+function get(o) {
+    count = count + 1;
+    return o;
+}
+
+var o = { a: { b: 10 }, n: null, name: "o" };
+o.hello = function(x) {
+    return this.name + " says " + x;
+};
+var nothing = undefined;
+
+console.log(o?.a.b);
+console.log(o.n?.b);
+console.log(nothing?.a.b.c);
+console.log(o.missing?.b);
+console.log(o?.["a"]?.b);
+console.log(o.hello?.("hi"));
+console.log(o.goodbye?.("hi"));
+console.log(o?.hello("there"));
+var k = "hello";
+console.log(o[k]?.("you"));
+
+var f = function(x) { return x + 1; };
+console.log(f?.(1));
+var g = null;
+console.log(g?.(next()));
+console.log(count);
+
+// the object is evaluated once
+console.log(get(o)?.a.b);
+console.log(count);
+console.log(get(null)?.a.b);
+console.log(count);
+
+console.log(0 ?? 5);
+console.log("" ?? "default");
+console.log(false ?? true);
+console.log(null ?? "null");
+console.log(undefined ?? "undefined");
+console.log(o.n ?? o.a.b);
+console.log(nothing ?? next());
+console.log(count);
+
+// the right-hand sides are not evaluated
+console.log(1 ?? next());
+console.log(false && next());
+console.log(true || next());
+console.log(true ? 1 : next());
+console.log(count);
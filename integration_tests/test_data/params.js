@@ -0,0 +1,67 @@
+function f(a, b = 2, ...rest) {
+    return a + b + rest.length;
+}
+console.log(f(1));
+console.log(f(1, 5));
+console.log(f(1, 5, 7, 8));
+var xs = [10, 20, 30];
+console.log(f(...xs));
+var sum = (...ns) => {
+    var t = 0;
+    for (var i = 0; i < ns.length; i++) {
+        t = t + ns[i];
+    }
+    return t;
+};
+console.log(sum());
+console.log(sum(1, 2, 3));
+class B {
+    constructor(x, y) {
+        this.x = x;
+        this.y = y;
+    }
+}
+class D extends B {}
+var d = new D(3);
+console.log(d.x);
+console.log(d.y);
+var d2 = new D(3, 4);
+console.log(d2.y);
+function g({ p, q } = { p: 1, q: 2 }) {
+    return p + q;
+}
+console.log(g());
+console.log(g({ p: 5, q: 6 }));
+class Logger {
+    constructor(prefix, ...parts) {
+        this.line = prefix + parts.length;
+    }
+}
+class Child extends Logger {
+    constructor(...parts) {
+        super("child", ...parts);
+    }
+}
+console.log(new Logger("a", 1, 2).line);
+console.log(new Child(1, 2, 3).line);
+// a rest parameter collects the arguments of any call, including calls of
+// methods and callbacks
+var o = { f: f, sum: sum };
+console.log(o.f(1, 2, 3, 4, 5));
+console.log(o.sum(4, 5));
+function callWith(g) {
+    return g(1, 2, 3);
+}
+console.log(callWith(sum));
+console.log(callWith(f));
+// extra arguments are evaluated, even when they are dropped
+var n = 0;
+function tick() {
+    n = n + 1;
+    return n;
+}
+function one(x) {
+    return x;
+}
+console.log(one(tick(), tick(), tick()));
+console.log(n);
@@ -0,0 +1,32 @@
+async function a() {
+    console.log("a1");
+    await undefined;
+    console.log("a2");
+    await undefined;
+    console.log("a3");
+}
+async function b() {
+    console.log("b1");
+    await undefined;
+    console.log("b2");
+    return Promise.resolve("b-result");
+}
+var p1 = Promise.resolve(1);
+p1.then(function (x) { console.log("then1"); return x; }, undefined)
+  .then(function (x) { console.log("then2"); }, undefined);
+a();
+b().then(function (v) { console.log(v); }, undefined);
+Promise.reject(Error("no")).catch(function (e) { console.log("caught " + e.message); });
+Promise.all([1, delay(2), Promise.resolve(3)]).then(function (xs) { console.log(xs[0] + xs[1] + xs[2]); }, undefined);
+Promise.all([Promise.reject(Error("first")), 2]).then(undefined, function (e) { console.log("all " + e.message); });
+function delay(v) {
+    return new Promise(function (resolve, reject) { resolve(v); });
+}
+var thenable = { then: function (resolve, reject) { resolve(42); } };
+Promise.resolve(thenable).then(function (v) { console.log("thenable " + v); }, undefined);
+new Promise(function (resolve, reject) { throw Error("in executor"); }).catch(function (e) { console.log(e.message); });
+console.log("sync");
+new Promise((resolve) => { resolve("one param"); }).then((v) => { console.log(v); });
+Promise.resolve().then(() => { console.log("no params"); });
+var resolved = Promise.resolve(7);
+console.log(Promise.resolve(resolved) === resolved);
@@ -0,0 +1,38 @@
+// tick returns an int, and is precisely typed where it is called directly,
+// but it escapes into an array, where it is wrapped in a proxy
+var count = 0;
+function tick() {
+  count = count + 1;
+  return count;
+}
+var fs = [tick, tick];
+console.log(fs[0]() + fs[1]());
+console.log(tick() * 2);
+// every proxy is the same function as the original
+console.log(fs[0] === fs[1]);
+console.log(fs[0] === tick);
+// and shares its properties
+tick.label = "ticker";
+console.log(fs[1].label);
+
+// a callback that produces a string
+function shout(s) {
+  return s + "!";
+}
+function apply(f, x) {
+  return f(x);
+}
+console.log(apply(shout, "hi"));
+var o = { f: shout };
+console.log(o.f("hey"));
+
+// a function that returns a function
+function adder(n) {
+  return function (x) {
+    return x + n;
+  };
+}
+var add2 = adder(2);
+console.log(add2(40));
+var adders = [adder];
+console.log(adders[0](1)(2));
@@ -0,0 +1,65 @@
+function show(arr) {
+    if (arr === null) {
+        console.log("null");
+        return;
+    }
+    var i = 0;
+    while (i < arr.length) {
+        console.log(arr[i]);
+        i = i + 1;
+    }
+}
+
+var date = /(\d{4})-(\d\d)-(\d\d)/;
+console.log(date.test("on 2021-03-14"));
+console.log(date.test("no date here"));
+show(date.exec("due 1999-12-31!"));
+show(date.exec("nothing"));
+
+var word = /o+/g;
+show(word.exec("foo boo"));
+show(word.exec("foo boo"));
+show(word.exec("foo boo"));
+
+var csv = "a, b,c ,  d";
+show(csv.split(/\s*,\s*/));
+show("one two  three".split(" "));
+show("x-y_z".split(/([-_])/));
+
+var s = "The Quick Brown Fox";
+show(s.match(/quick/i));
+show(s.match(/[A-Z]/g));
+show(s.match(/zebra/));
+console.log(s.replace(/(\w+) (\w+)/, "$2 $1"));
+console.log(s.replace(/o/g, "0"));
+console.log(s.replace("Quick", "[$&]"));
+
+var lines = "first\nsecond\nthird";
+show(lines.match(/^\w+$/gm));
+console.log(/^second$/.test(lines));
+console.log(/a.c/.test("a\nc"));
+console.log(typeof /x/);
+
+function words(text, re) {
+    return text.split(re);
+}
+show(words("a1b22c", /\d+/));
+show(words(csv, ","));
+var things = [/b+/, "abbbc"];
+show(things[1].match(things[0]));
+var counter = /a/g;
+var n = 0;
+while (counter.test("banana")) {
+    n = n + 1;
+}
+console.log(n);
+console.log("2020-01-05".replace(/(?<y>\d+)-(?<m>\d+)-(?<d>\d+)/, "$<d>/$<m>/$<y>"));
+var pairs = /b(c)/g;
+var found = pairs.exec("abcbc");
+console.log(found.index);
+console.log(found.input);
+console.log(pairs.lastIndex);
+console.log(pairs.source);
+console.log(pairs.global);
+pairs.lastIndex = 0;
+console.log(pairs.exec("abcbc").index);
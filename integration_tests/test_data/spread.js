@@ -0,0 +1,66 @@
+function add3(a, b, c) {
+    return a + b + c;
+}
+
+var args = [1, 2, 3];
+console.log(add3(...args));
+console.log(add3(10, ...[20, 30]));
+
+var counter = {
+    count: 5,
+    add: function (a, b) {
+        return this.count + a + b;
+    }
+};
+var pair = [1, 2];
+console.log(counter.add(...pair));
+
+var a = [1, 2];
+var b = [3, 4];
+var c = [...a, ...b];
+console.log(c.length);
+console.log(c[0] + c[3]);
+var d = [0, ...a, 5, ...b];
+console.log(d.length);
+console.log(d[3]);
+// spreading copies the array
+a[0] = 100;
+console.log(c[0]);
+
+var o = { x: 1, y: 2 };
+var p = { ...o, y: 20, z: 3 };
+console.log(p.x);
+console.log(p.y);
+console.log(p.z);
+var q = { y: 30, ...o };
+console.log(q.y);
+o.x = 50;
+console.log(p.x);
+
+class Point {
+    constructor(x, y) {
+        this.x = x;
+        this.y = y;
+    }
+    sum() {
+        return this.x + this.y;
+    }
+}
+
+class Named extends Point {
+    constructor(name, coords) {
+        super(...coords);
+        this.name = name;
+    }
+}
+
+var n = new Named("n", [7, 8]);
+console.log(n.sum());
+
+// there is no limit on the number of arguments of a function that is applied
+function eight(a, b, c, d, e, f, g, h) {
+  return "" + a + b + c + d + e + f + g + h;
+}
+var digits = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+console.log(eight(...digits));
+console.log(eight.apply(null, [9, 8, 7, 6]));
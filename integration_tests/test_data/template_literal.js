@@ -0,0 +1,30 @@
+var name = "world";
+var n = 40;
+console.log(`hello, ${name}!`);
+console.log(`${n + 2} is ${n + 2 > 41 ? "big" : "small"}`);
+console.log(`${1.5}${true}${undefined}`);
+var o = { toString: function() { return "an object"; } };
+console.log(`this is ${o}`);
+var s = `x=${n}`;
+log_any(s.length);
+
+function tag(strings, a, b) {
+    return strings[0] + a + strings[1] + b + strings[2];
+}
+console.log(tag`<${1}|${2}>`);
+
+// the strings are frozen, hold their raw forms, and are the same each time
+function frozen(strings, a, b) {
+    strings[0] = "changed";
+    return strings[0] + a + strings[1] + b + strings[2] + strings.length + strings.raw[1];
+}
+console.log(frozen`<${1}\n${2}>`);
+var seen = [];
+function keep(strings) {
+    seen.push(strings);
+    return strings.raw.length;
+}
+for (var i = 0; i < 2; i++) {
+    keep`a${i}b`;
+}
+console.log(seen[0] === seen[1]);
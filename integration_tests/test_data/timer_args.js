@@ -0,0 +1,14 @@
+// Arguments after the delay are passed to the callback, and extra arguments
+// to the other library functions are evaluated and ignored.
+setTimeout(function (a, b) { console.log("timeout " + a + " " + b); }, 20, "x", 2);
+var n = 0;
+var t = setInterval(function (step) {
+    n = n + step;
+    console.log("interval " + n);
+    if (n >= 6) {
+        clearInterval(t, "ignored");
+    }
+}, 1, 3);
+function effect() { console.log("evaluated"); return 1; }
+clearTimeout(undefined, effect());
+console.log(parseInt("12", 10, effect()));
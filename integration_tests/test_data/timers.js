@@ -0,0 +1,38 @@
+// Timers run after main and its microtasks, ordered by when they are due and
+// then by when they were scheduled.
+console.log("start");
+setTimeout(function () { console.log("timeout 10"); }, 10);
+setTimeout(function () { console.log("timeout 0"); });
+setTimeout(function () { console.log("timeout 1"); }, 1);
+var n = 0;
+var interval = setInterval(function () {
+    n = n + 1;
+    console.log("interval " + n);
+    if (n === 3) {
+        clearInterval(interval);
+    }
+}, 4);
+var cancelled = setTimeout(function () { console.log("cancelled"); }, 5);
+clearTimeout(cancelled);
+Promise.resolve(1).then(function (v) {
+    console.log("microtask");
+    setTimeout(function () {
+        console.log("nested timeout");
+        Promise.resolve(2).then(function (w) { console.log("microtask in timer"); });
+    }, 2);
+});
+
+function delay(ms) {
+    return new Promise(function (resolve, reject) {
+        setTimeout(function () { resolve(ms); }, ms);
+    });
+}
+
+async function sleeper() {
+    var a = await delay(7);
+    console.log("slept " + a);
+    var b = await delay(20);
+    console.log("slept " + b);
+}
+sleeper();
+console.log("end");
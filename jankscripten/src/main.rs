@@ -8,6 +8,7 @@ use std::process;
 struct Compile {
     #[clap(short, long)]
     output: Option<String>,
+    /// A NotWasm file, or the entry module of a JavaScript program
     input: String,
     #[clap(short, long)]
     jankyscript_dump: bool,
@@ -121,11 +122,10 @@ fn compile(opts: Compile) {
             compile_notwasm(opts, &input, output_path.as_path());
         }
         "js" => {
-            let js_code = read_file(input_path);
             let (wasm_bin, interned_strings) = libjankscripten::javascript_to_wasm(
                 opts.libjankscripten_opts(),
-                &opts.input,
-                &js_code,
+                input_path,
+                |path| fs::read_to_string(path),
                 |janky| {
                     if opts.jankyscript_dump {
                         eprintln!("{}", janky);
@@ -151,46 +151,43 @@ fn compile(opts: Compile) {
     }
 }
 
-fn read_javascript(raw_path: &String) -> String {
+fn link_javascript(
+    raw_path: &String,
+    name_gen: &mut libjankscripten::javascript::NameGen,
+) -> libjankscripten::javascript::Stmt {
     let input_path = Path::new(raw_path);
     let ext = expect_extension(input_path);
-    match ext {
-        "js" => read_file(input_path),
-        _ => {
-            eprintln!("Unsupported extension: .{}", ext);
-            process::exit(1);
-        }
+    if ext != "js" {
+        eprintln!("Unsupported extension: .{}", ext);
+        process::exit(1);
     }
-}
-
-fn parse_javascript(
-    src_name: &str,
-    input: &String,
-    input_path: &String,
-) -> libjankscripten::javascript::Stmt {
-    match libjankscripten::javascript::parse(src_name, &input) {
+    let load = |path: &Path| fs::read_to_string(path);
+    match libjankscripten::javascript::link_modules(input_path, load, name_gen) {
         Ok(stmt) => stmt,
         Err(err) => {
-            eprintln!("{}:\n{}", input_path, err);
+            eprintln!("{}", err);
             process::exit(1);
         }
     }
 }
 
-fn desugar_javascript(
-    stmt: &mut libjankscripten::javascript::Stmt,
-) -> &libjankscripten::javascript::Stmt {
-    let mut name_gen = libjankscripten::javascript::NameGen::default();
-    libjankscripten::javascript::desugar(stmt, &mut name_gen);
+fn desugar_javascript<'a>(
+    stmt: &'a mut libjankscripten::javascript::Stmt,
+    name_gen: &mut libjankscripten::javascript::NameGen,
+) -> &'a libjankscripten::javascript::Stmt {
+    if let Err(err) = libjankscripten::javascript::desugar(stmt, name_gen) {
+        eprintln!("{}", err);
+        process::exit(1);
+    }
     stmt
 }
 
 fn parse(opts: Parse) {
     ///// Source Code -> JavaScript
-    let src_javascript = read_javascript(&opts.input);
-    let mut parsed_javascript = parse_javascript(&opts.input, &src_javascript, &opts.input);
+    let mut name_gen = libjankscripten::javascript::NameGen::default();
+    let mut parsed_javascript = link_javascript(&opts.input, &mut name_gen);
     // desugaring mutates the stmt in place
-    desugar_javascript(&mut parsed_javascript);
+    desugar_javascript(&mut parsed_javascript, &mut name_gen);
     let _desugared_javascript = parsed_javascript;
 }
 
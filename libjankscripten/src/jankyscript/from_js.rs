@@ -19,7 +19,7 @@ fn lvalue(lval: Js::LValue) -> LValue {
 }
 
 fn expr(e: Js::Expr) -> Expr {
-    use Js::{BinOp, Expr as E};
+    use Js::{BinOp, Expr as E, UnaryOp};
     match e {
         E::Lit(lit, s) => Expr::Lit(lit, s),
         E::Array(es, s) => Expr::Array(es.into_iter().map(|e| expr(e)).collect(), s),
@@ -34,6 +34,16 @@ fn expr(e: Js::Expr) -> Expr {
             s,
         ),
         E::New(_, _, _) => unexpected(e),
+        // `delete` needs the object and the field, rather than the value of
+        // its operand. deleting anything else evaluates it and produces `true`
+        E::Unary(UnaryOp::Delete, e, s) => {
+            let args = match *e {
+                E::Dot(obj, x, s) => vec![expr(*obj), Expr::Lit(Lit::String(x.into_name()), s)],
+                E::Bracket(obj, field, _) => vec![expr(*obj), expr(*field)],
+                e => vec![expr(e), Expr::Lit(Lit::Undefined, s.clone())],
+            };
+            Expr::JsOp(JsOp::Unary(UnaryOp::Delete), args, Default::default(), s)
+        }
         E::Unary(op, e, s) => Expr::JsOp(JsOp::Unary(op), vec![expr(*e)], Default::default(), s),
         E::Binary(BinOp::BinaryOp(op), e1, e2, s) => Expr::JsOp(
             JsOp::Binary(op),
@@ -59,13 +69,21 @@ fn expr(e: Js::Expr) -> Expr {
             s,
         ),
         E::Length(obj, s) => Expr::Length(Box::new(expr(*obj)), Type::Missing, s),
-        E::Func(_, args, body, s) => func(
+        E::Func(_, args, _, body, s) => func(
             args.into_iter().map(|x| (x, Type::Missing)).collect(),
             Type::Missing,
             stmt(*body),
             s,
         ),
-        E::Seq(_, _) => unexpected(e),
+        E::Arrow(..)
+        | E::Class(..)
+        | E::Super
+        | E::NewTarget
+        | E::CallWithThis(..)
+        | E::Apply(..)
+        | E::Destructure(..) => unexpected(e),
+        E::Seq(_, _) | E::OptChain(..) => unexpected(e),
+        E::Generator(..) | E::Yield(..) | E::Async(..) | E::Await(..) => unexpected(e),
     }
 }
 
@@ -82,10 +100,7 @@ fn stmt(s: Js::Stmt) -> Stmt {
             Box::new(stmt(*e)),
             s,
         ),
-        S::ForIn(is_var, bind, container, body, s) => {
-            assert!(!is_var, "for..in was not desugared to not use var");
-            for_in_(bind, expr(*container), stmt(*body), s)
-        }
+        S::ForIn(.., s) | S::ForOf(.., s) => unexpected(&s),
         S::Label(x, st, s) => Label(x, Box::new(stmt(*st)), s),
         S::Break(x, s) => Break(x.unwrap(), s),
         S::Continue(_, s) => unexpected(&s),
@@ -115,7 +130,7 @@ fn stmt(s: Js::Stmt) -> Stmt {
             }
             vardecl(decls.remove(0), s)
         }
-        S::Func(_, _, _, s) => unexpected(&s),
+        S::Func(.., s) => unexpected(&s),
         S::Return(e, s) => Return(Box::new(expr(*e)), s),
     }
 }
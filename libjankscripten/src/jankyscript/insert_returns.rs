@@ -18,7 +18,7 @@ fn returns(stmt: &Stmt) -> bool {
         Block(stmts, _) => stmts.iter().any(returns),
         If(_, true_part, false_part, _) => returns(true_part) && returns(false_part),
         // This is conservative. We could analyze the conditions and breaks etc...
-        Loop(..) | ForIn(..) => false,
+        Loop(..) => false,
         // This is conservative. We could analyze if/when the body throws,
         // but instead we assume the body may or may not throw at any time, so no
         // return can be guaranteed to be reachable
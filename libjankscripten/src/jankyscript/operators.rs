@@ -14,6 +14,7 @@ macro_rules! typ {
     (any) => (Type::Any);
     (dynobject) => (Type::DynObject);
     (array) => (Type::Array);
+    (bigint) => (Type::BigInt);
     (fun($( $arg:tt ),*) -> $ret:tt) =>
         (Type::Function(vec![ $( typ!($arg) ),* ], Box::new(typ!($ret))));
     (fun_vec($($args:tt)*) -> $($ret:tt)*) =>
@@ -145,20 +146,26 @@ lazy_static! {
         let mut table = OverloadTable::default();
         table.add(Plus, typ!(fun(int, int) -> int), I32Add);
         table.add(Plus, typ!(fun(string, string) -> string), RTSFunction::Method("concat".to_string(), typ!(fun(string, string) -> string)));
+        table.add(Plus, typ!(fun(bigint, bigint) -> bigint), RTSFunction::BigIntPlus);
         table.add(Plus, typ!(fun(any, any) -> any), RTSFunction::Plus);
 
 
         table.add(Minus, typ!(fun(float, float) -> float), F64Sub);
         table.add(Minus, typ!(fun(int, int) -> int), I32Sub);
+        table.add(Minus, typ!(fun(bigint, bigint) -> bigint), RTSFunction::BigIntMinus);
         table.add(Minus, typ!(fun(any, any) -> any), RTSFunction::Minus);
 
         table.add(Times, typ!(fun(float, float) -> float), F64Mul);
         table.add(Times, typ!(fun(int, int) -> int), I32Mul);
+        table.add(Times, typ!(fun(bigint, bigint) -> bigint), RTSFunction::BigIntTimes);
         table.add(Times, typ!(fun(any, any) -> any), RTSFunction::Times);
         table.add(Over, typ!(fun(float, float) -> float), F64Div);
-        table.add(Over, typ!(fun(any, any) -> float), RTSFunction::Over);
+        table.add(Over, typ!(fun(bigint, bigint) -> bigint), RTSFunction::BigIntOver);
+        // The quotient of two BigInts is a BigInt, so this is not a float
+        table.add(Over, typ!(fun(any, any) -> any), RTSFunction::Over);
         table.add(Mod, typ!(fun(int, int) -> int), I32Rem);
         table.add(Mod, typ!(fun(float, float) -> float), RTSFunction::ModF64);
+        table.add(Mod, typ!(fun(bigint, bigint) -> bigint), RTSFunction::BigIntMod);
         table.add(Mod, typ!(fun(any, any) -> any), RTSFunction::Mod);
         table.add(Equal, typ!(fun(int, int) -> bool), I32Eq);
         table.add(Equal, typ!(fun(float, float) -> bool), F64Eq);
@@ -168,21 +175,26 @@ lazy_static! {
         table.add(NotEqual, typ!(fun(any, any) -> bool), RTSFunction::NotEqual);
         table.add(StrictEqual, typ!(fun(int, int) -> bool), I32Eq);
         table.add(StrictEqual, typ!(fun(float, float) -> bool), F64Eq);
+        table.add(StrictEqual, typ!(fun(bigint, bigint) -> bool), RTSFunction::BigIntStrictEqual);
         table.add(StrictEqual, typ!(fun(any, any) -> bool), RTSFunction::StrictEqual);
         table.add(StrictNotEqual, typ!(fun(int, int) -> bool), I32Ne);
         table.add(StrictNotEqual, typ!(fun(float, float) -> bool), F64Ne);
         table.add(StrictNotEqual, typ!(fun(any, any) -> bool), RTSFunction::StrictNotEqual);
         table.add(LessThan, typ!(fun(int, int) -> bool), I32LT);
+        table.add(LessThan, typ!(fun(bigint, bigint) -> bool), RTSFunction::BigIntLessThan);
         // It's always safe to coerce to float because < only operates on
         // numbers and all numbers can be represented as floats. TODO(luna): This
         // isn't actually true: string ordering is a thing. However, this hack
         // brings us to parity with the old coercion insertion
         table.add_coercible(LessThan, typ!(fun(float, float) -> bool), F64LT);
         table.add(LessThanEqual, typ!(fun(int, int) -> bool), I32Le);
+        table.add(LessThanEqual, typ!(fun(bigint, bigint) -> bool), RTSFunction::BigIntLessThanEqual);
         table.add_coercible(LessThanEqual, typ!(fun(float, float) -> bool), F64Le);
         table.add(GreaterThan, typ!(fun(int, int) -> bool), I32GT);
+        table.add(GreaterThan, typ!(fun(bigint, bigint) -> bool), RTSFunction::BigIntGreaterThan);
         table.add_coercible(GreaterThan, typ!(fun(float, float) -> bool), F64GT);
         table.add(GreaterThanEqual, typ!(fun(int, int) -> bool), I32Ge);
+        table.add(GreaterThanEqual, typ!(fun(bigint, bigint) -> bool), RTSFunction::BigIntGreaterThanEqual);
         table.add_coercible(GreaterThanEqual, typ!(fun(float, float) -> bool), F64Ge);
         table.add_coercible(LeftShift, typ!(fun(int, int) -> int), I32Shl);
         table.add_coercible(RightShift, typ!(fun(int, int) -> int), I32Shr);
@@ -200,10 +212,11 @@ lazy_static! {
         table.add_coercible(JUO::Plus, typ!(fun(float) -> float), UnaryOp::Nop);
         table.add(JUO::Minus, typ!(fun(int) -> int), UnaryOp::I32Neg);
         table.add(JUO::Minus, typ!(fun(float) -> float), UnaryOp::F64Neg);
+        table.add(JUO::Minus, typ!(fun(bigint) -> bigint), RTSFunction::BigIntNeg);
         table.add(JUO::Minus, typ!(fun(any) -> any), RTSFunction::Neg);
         table.add(JUO::TypeOf, typ!(fun(any) -> string), RTSFunction::Typeof);
         table.add(JUO::Void, typ!(fun(any) -> any), RTSFunction::Void);
-        table.add(JUO::Delete, typ!(fun(any) -> any), RTSFunction::Delete);
+        table.add(JUO::Delete, typ!(fun(any, any) -> bool), RTSFunction::Delete);
 
         table.add_coercible(JUO::Not, typ!(fun(bool) -> bool), UnaryOp::Eqz);
         table.add_coercible(JUO::Tilde, typ!(fun(int) -> int), UnaryOp::I32Not);
@@ -17,6 +17,7 @@ impl Pretty for Type {
             Type::String => pp.text("string"),
             Type::Array => pp.text("array"),
             Type::Bool => pp.text("bool"),
+            Type::BigInt => pp.text("bigint"),
             Type::DynObject => pp.text("DynObject"),
             Type::Function(args, ret) => pp.concat(vec![
                 pp.intersperse(
@@ -103,7 +104,8 @@ impl Pretty for Lit {
     {
         match self {
             Lit::String(s) => pp.text(s).double_quotes(), // TODO(michael) escaping?
-            Lit::Regex(_s1, _s2) => todo!("regex literal pretty printing"),
+            Lit::Regex(pattern, flags) => pp.text(format!("/{}/{}", pattern, flags)),
+            Lit::BigInt(digits) => pp.text(format!("{}n", digits)),
             Lit::Bool(b) => pp.text(if *b { "true" } else { "false" }),
             Lit::Null => pp.text("null"),
             Lit::Num(Num::Int(i)) => pp.as_string(i),
@@ -346,14 +348,6 @@ impl Pretty for Stmt {
                 pp.hardline(),
             ),
             Stmt::Loop(s, _) => pp.concat(vec![pp.text("loop"), pp.hardline(), s.pretty(pp)]),
-            Stmt::ForIn(bind, container, body, _) => pp
-                .text("for (")
-                .append(pp.as_string(bind))
-                .append(pp.text(" in "))
-                .append(container.pretty(pp))
-                .append(pp.text(")"))
-                .append(pp.line())
-                .append(body.pretty(pp)),
             Stmt::Label(lbl, s, _) => pp.concat(vec![
                 pp.as_string(lbl),
                 pp.text(":"),
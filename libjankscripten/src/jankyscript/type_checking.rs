@@ -20,6 +20,8 @@ pub enum TypeCheckingError {
     ExpectedIndexer(String, Type, Pos),
     #[error("`{0}` expected an expression to have a function type, but received `{1}` at `{2}`")]
     ExpectedFunction(String, Type, Pos),
+    #[error("a function of type `{0}` takes {1} arguments, but received {2} at `{3}`")]
+    ArityMismatch(Type, usize, usize, Pos),
     #[error("tried to tag a value of type `{0}`, but received `{1}` at `{2}`")]
     TagTypeMismatch(Type, Type, Pos),
     #[error("`{0}` expected a ground type but got `{1}` at `{2}`")]
@@ -170,19 +172,6 @@ fn type_check_stmt(stmt: &Stmt, env: Env, ret_ty: &Option<Type>) -> TypeChecking
 
             Ok(env)
         }
-        Stmt::ForIn(bind, container, body, s) => {
-            ensure_indexable(
-                "for..in",
-                type_check_expr(container, env.clone())?,
-                s.clone(),
-            )?;
-            // type check body in a new scope
-            // TODO(luna): like in jankyscript::fv, this is a guess, and ForIn
-            // should really have a type
-            let for_in_env = env.clone().update(bind.clone(), Type::Any);
-            type_check_stmt(&body, for_in_env, ret_ty)?;
-            Ok(env)
-        }
         Stmt::Throw(e, _) => {
             // expression we're throwing should be well-typed
             type_check_expr(&e, env.clone())?;
@@ -267,8 +256,23 @@ fn type_check_fun_call(
 ) -> TypeCheckingResult<Type> {
     // ensure that `fun_type` is a function type.
     // get its expected argument types.
-    let (expected_arg_types, return_type) =
-        ensure_function("expected function for function call", fun_type, s.clone())?;
+    let (expected_arg_types, return_type) = ensure_function(
+        "expected function for function call",
+        fun_type.clone(),
+        s.clone(),
+    )?;
+
+    // a function of unknown type may be called with any number of
+    // arguments, but a function of known type must be called with exactly as
+    // many arguments as it takes
+    if expected_arg_types.len() != actual_args.len() {
+        return Err(TypeCheckingError::ArityMismatch(
+            fun_type,
+            expected_arg_types.len(),
+            actual_args.len(),
+            s,
+        ));
+    }
 
     // derive types for the actual arguments.
     let actual_arg_types: Vec<TypeCheckingResult<Type>> = actual_args
@@ -550,11 +554,13 @@ fn type_check_coercion(c: &Coercion, s: Pos) -> TypeCheckingResult<(Type, Type)>
             // get the original and new return type
             let (ret_from, ret_to) = type_check_coercion(ret_to_type, s)?;
 
-            // construct "from" function type
-            let from = Type::Function(args_from, Box::new(ret_from));
+            // construct "from" function type. the arguments are coerced from
+            // the arguments of the "to" function to the arguments of the
+            // "from" function
+            let from = Type::Function(args_to, Box::new(ret_from));
 
             // construct "to" function type
-            let to = Type::Function(args_to, Box::new(ret_to));
+            let to = Type::Function(args_from, Box::new(ret_to));
 
             // put them together
             Ok((from, to))
@@ -580,5 +586,6 @@ fn type_check_lit(l: &Lit) -> Type {
         Lit::Num(Num::Int(_)) => Type::Int,
         Lit::Undefined => Type::Any,
         Lit::Regex(_, _) => Type::Any,
+        Lit::BigInt(_) => Type::BigInt,
     }
 }
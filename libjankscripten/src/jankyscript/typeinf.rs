@@ -42,12 +42,15 @@ z3_datatype! {
     (str)
     (array)
     (dynobject)
-    // The must_ground field is a trick we use to ground function types. When
-    // `e` is a `fun and `(must_ground e)` is `true`, then the arguments are
-    // return type are constrained to be `any`. This is accomplished by
-    // auxilliary constraints that we define when we construct the type of
-    // `Expr::Fun` in `zfun`.
-    (fun (must_ground bool) (args (datatype Z3TypList)) (ret (datatype Z3Typ)))
+    (bigint)
+    // The escapes field is a trick we use to let functions flow into `any`.
+    // When `e` is a `fun` and `(escapes e)` is `true`, then the arguments are
+    // constrained to be `any`, and the return type must be able to escape
+    // too. A function of such a type is coerced to `any` with a proxy that
+    // tags its result, which cannot fail. This is accomplished by auxilliary
+    // constraints that we define when we construct the type of `Expr::Fun`
+    // in `zfun`.
+    (fun (escapes bool) (args (datatype Z3TypList)) (ret (datatype Z3Typ)))
 }
 
 z3_datatype! {
@@ -77,6 +80,7 @@ fn typ_lit(lit: &Lit) -> Type {
         Lit::String(_) => Type::String,
         Lit::Bool(_) => Type::Bool,
         Lit::Regex(..) => Type::Any,
+        Lit::BigInt(..) => Type::BigInt,
         Lit::Undefined => Type::Any,
         Lit::Null => Type::Any,
     }
@@ -143,28 +147,29 @@ impl<'a> Typeinf<'a> {
             Type::Bool => self.z.make_bool(),
             Type::Array => self.z.make_array(),
             Type::DynObject => self.z.make_dynobject(),
+            Type::BigInt => self.z.make_bigint(),
             Type::Metavar(n) => self
                 .vars
                 .get(*n)
                 .expect("unbound type metavariable")
                 .clone(),
             Type::Function(args, r) => {
-                // Note that this case doesn't setup the grounding constraints
+                // Note that this case doesn't setup the escaping constraints
                 // on args and ret. For those constraints, use `zfun`.
                 let mut z_args = self.zl.make_tnil();
-                let must_ground = z3::ast::Bool::fresh_const(self.z.cxt, "must_ground");
+                let escapes = z3::ast::Bool::fresh_const(self.z.cxt, "escapes");
                 for a in args.iter().rev() {
                     z_args = self.zl.make_tcons(&self.t(a), &z_args);
                 }
-                self.z.make_fun(&must_ground.into(), &z_args, &self.t(r))
+                self.z.make_fun(&escapes.into(), &z_args, &self.t(r))
             }
             _ => todo!("Type: {:?}", t),
         }
     }
 
     fn zfun(
-        &self,
-        must_ground: z3::ast::Bool<'a>,
+        &mut self,
+        escapes: z3::ast::Bool<'a>,
         args: &Vec<Type>,
         r: &Type,
     ) -> z3::ast::Dynamic<'a> {
@@ -172,24 +177,27 @@ impl<'a> Typeinf<'a> {
         for a in args.iter().rev() {
             let z_arg = self.t(a);
             self.solver.assert(&z3f!(self,
-                (or (not (id must_ground.clone()))
+                (or (not (id escapes.clone()))
                     (= (id z_arg.clone()) (typ any)))));
             z_args = self.zl.make_tcons(&z_arg, &z_args);
         }
         let z_r = self.t(r);
+        let r_can_escape = self.can_escape(r);
         self.solver.assert(&z3f!(self,
-            (or (not (id must_ground.clone()))
-                (= (id z_r.clone()) (typ any)))));
-        self.z.make_fun(&must_ground.into(), &z_args, &z_r)
+            (or (not (id escapes.clone()))
+                (id r_can_escape))));
+        self.z.make_fun(&escapes.into(), &z_args, &z_r)
     }
 
-    fn is_ground(&mut self, t: &Type) -> z3::ast::Bool<'a> {
+    /// Whether a value of type `t` may be coerced to `any`, which is any
+    /// value that is not a function, or a function whose type can escape.
+    fn can_escape(&mut self, t: &Type) -> z3::ast::Bool<'a> {
         let t_z = self.t(t);
         let test_fun = self.z.test_fun(&t_z).as_bool().unwrap();
-        let fun_must_ground = self.z.fun_must_ground(&t_z).as_bool().unwrap();
+        let fun_escapes = self.z.fun_escapes(&t_z).as_bool().unwrap();
         z3f!(self,
             (or (not (id test_fun))
-                (id fun_must_ground)))
+                (id fun_escapes)))
     }
 
     fn z3_to_typ_vec(&self, model: &'a Model, mut e: Dynamic<'a>) -> Vec<Type> {
@@ -217,6 +225,8 @@ impl<'a> Typeinf<'a> {
             Type::Array
         } else if self.z.is_dynobject(&model, &e) {
             Type::DynObject
+        } else if self.z.is_bigint(model, &e) {
+            Type::BigInt
         } else if self.z.is_fun(&model, &e) {
             let args = model
                 .eval(&self.z.fun_args(&e))
@@ -283,11 +293,11 @@ impl<'a> Typeinf<'a> {
                 let w = self.fresh_weight();
                 self.solver.assert(&phi);
                 let t_r = self.return_type.clone();
-                let t_is_ground = self.is_ground(&t);
+                let t_can_escape = self.can_escape(&t);
                 self.solver.assert(&z3f!(self,
                     (or (and (id w.clone()) (= (tid t_r.clone()) (tid t.clone())))
                         (and (not (id w))
-                             (id t_is_ground)
+                             (id t_can_escape)
                              (= (tid t_r.clone()) (typ any))))
                 ));
                 **e = coerce(t, t_r, e.take(), p.clone());
@@ -306,15 +316,6 @@ impl<'a> Typeinf<'a> {
                 self.cgen_stmt(then_branch);
                 self.cgen_stmt(else_branch);
             }
-            Stmt::ForIn(x, iter, body, p) => {
-                let (phi, t) = self.cgen_expr(iter);
-                self.solver.assert(&phi);
-                **iter = coerce(t, typ!(string), iter.take(), p.clone());
-                let outer_env = self.env.clone();
-                self.env.update(x.clone(), typ!(string));
-                self.cgen_stmt(body);
-                self.env = outer_env;
-            }
             Stmt::Break(..) => {
                 // Nothing to do
             }
@@ -418,6 +419,8 @@ impl<'a> Typeinf<'a> {
                     | known(&ot, &otf, &Type::Array, &ft, &ftf, &Type::Int)
                     // f : int implies o may be coerced to Array
                     | known(&ft, &ftf, &Type::Int, &ot, &otf, &Type::Array)
+                    // o : String implies f may be coerced to int
+                    | known(&ot, &otf, &Type::String, &ft, &ftf, &Type::Int)
                     // o:any[f:any]. hopefully this doesn't happen in practice
                     | z3f!(self, (and
                         (= (tid ot) (tid otf) (typ any))
@@ -621,9 +624,9 @@ impl<'a> Typeinf<'a> {
     }
 
     // Coerce expr: t at p to either t (preferred) or any. Returns
-    // (phis & additional, t or any). Only coerces to any when t is ground
-    // NOTE(luna): If we end up with z3 performance concerns, not checking for
-    // ground on known types like literals would likely help
+    // (phis & additional, t or any). Only coerces to any when t can escape
+    // NOTE(luna): If we end up with z3 performance concerns, not checking
+    // whether known types like literals can escape would likely help
     fn wobbly<B: Into<Option<ast::Bool<'a>>>>(
         &mut self,
         p: Pos,
@@ -633,12 +636,12 @@ impl<'a> Typeinf<'a> {
     ) -> (ast::Bool<'a>, Type) {
         let w = self.fresh_weight();
         let alpha = self.fresh_metavar("wobbly");
-        let is_ground = self.is_ground(&t);
+        let can_escape = self.can_escape(&t);
         let phi = z3f!(self,
             (or (and (id w.clone()) (= (tid alpha) (tid t)))
                 (and (not (id w))
                     (= (tid alpha) (typ any))
-                    (id is_ground))));
+                    (id can_escape))));
         let e = expr.take();
         *expr = coerce(t, alpha.clone(), e, p);
         match phis.into() {
@@ -875,14 +878,18 @@ impl<'a> Typeinf<'a> {
 
                 let w = self.fresh_weight();
                 let beta = self.fresh_metavar("beta");
-                // When `(not w)` is true, the auxiliary grounding assertions
-                // produced by `zfun` force `args` and `return_typ` to be `any`.
-                let z_fun = self.zfun(z3f!(self, (not (id w.clone()))), &args, &return_typ);
+                // When `escapes` is true, the auxiliary assertions produced
+                // by `zfun` force `args` to be `any`, so that the function
+                // may be coerced to `any`, either here or wherever else its
+                // type is coerced to `any`. Its body and result are still
+                // precisely typed.
+                let escapes = z3::ast::Bool::fresh_const(self.z.cxt, "escapes");
+                let z_fun = self.zfun(escapes.clone(), &args, &return_typ);
 
                 let phi = z3f!(self,
                     (or
                       (and (id w.clone()) (= (tid beta) (id z_fun.clone())))
-                      (and (not (id w.clone())) (= (tid beta) (typ any)))));
+                      (and (not (id w.clone())) (id escapes) (= (tid beta) (typ any)))));
                 let p = p.clone();
                 *expr = coerce(
                     typ!(fun_vec(args) -> unquote return_typ),
@@ -965,7 +972,7 @@ mod tests {
     use super::super::super::javascript::{desugar, parse};
     use super::super::super::shared::NameGen;
     use super::super::syntax::*;
-    use super::super::type_checking::type_check;
+    use super::super::type_checking::{type_check, TypeCheckingError};
     use super::super::walk::*;
     use super::typeinf;
 
@@ -988,7 +995,7 @@ mod tests {
     fn typeinf_test(s: &str) -> usize {
         let mut js = parse("<text>", s).expect("error parsing JavaScript");
         let mut ng = NameGen::default();
-        desugar(&mut js, &mut ng);
+        desugar(&mut js, &mut ng).expect("error desugaring JavaScript");
         let mut janky = crate::jankyscript::from_js::from_javascript(js);
         println!("after from_js: {}", janky);
         typeinf(&mut janky);
@@ -1033,6 +1040,37 @@ mod tests {
         assert_eq!(n, 2);
     }
 
+    #[test]
+    fn default_param() {
+        // every call passes an int for b, so the only any is the comparison
+        // with undefined that decides whether to use the default
+        let n = typeinf_test(
+            r#"
+            function h(a, b = 2) {
+                return a * b;
+            }
+            h(1, 3);
+            h(4, 5);
+        "#,
+        );
+        assert_eq!(n, 1);
+    }
+
+    #[test]
+    fn arguments_keeps_params() {
+        // a and b are still numbers, and the only anys are the arguments in
+        // the arguments array
+        let n = typeinf_test(
+            r#"
+            function f(a, b) {
+                return a * b + arguments.length;
+            }
+            f(2, 3);
+        "#,
+        );
+        assert_eq!(n, 2);
+    }
+
     #[test]
     fn heterogenous_array() {
         let n = typeinf_test(
@@ -1152,7 +1190,41 @@ mod tests {
     }
 
     #[test]
-    fn heterogenous_return_types_must_be_ground() {
+    fn arity_mismatch_is_any_app() {
+        let n = typeinf_test(
+            r#"
+            var f = function(x) { return x; };
+            f = function(x, y) { return y; };
+            f(1, 2, 3);
+            "#,
+        );
+        // both functions are coerced to any, and so are the three arguments
+        assert_eq!(n, 5);
+    }
+
+    #[test]
+    fn arity_mismatch_does_not_type_check() {
+        use crate::pos::Pos;
+        // log_any takes `this` and one argument
+        let log_any = Expr::Id(
+            Id::Named("log_any".to_string()),
+            Type::Function(vec![Type::Any, Type::Any], Box::new(Type::Any)),
+            Pos::UNKNOWN,
+        );
+        let call = Expr::Call(
+            Box::new(log_any),
+            vec![Expr::Lit(Lit::Undefined, Pos::UNKNOWN)],
+            Pos::UNKNOWN,
+        );
+        let program = Stmt::Expr(Box::new(call), Pos::UNKNOWN);
+        assert!(matches!(
+            type_check(&program),
+            Err(TypeCheckingError::ArityMismatch(_, 2, 1, _))
+        ));
+    }
+
+    #[test]
+    fn heterogenous_return_types_escape() {
         let n = typeinf_test(
             r#"
             function F() {
@@ -1166,11 +1238,11 @@ mod tests {
             }
             "#,
         );
-        // - Nested function is coerced to any
+        // - Nested function is coerced to any, with a proxy that tags the
+        //   result of x << 2
         // - x is coerced to a number (does not count, we are counting *to* any)
-        // - x << 2 is coerced to any
         // - "hello" is coerced to any
-        assert_eq!(n, 3);
+        assert_eq!(n, 2);
     }
 
     #[test]
@@ -1322,4 +1394,16 @@ mod tests {
         );
         assert_eq!(n, 2);
     }
+
+    #[test]
+    fn template_literal_is_string() {
+        let n = typeinf_test(
+            r#"
+            var n = 5;
+            var s = `a${n}b`; // only n as any
+            s.length;
+            "#,
+        );
+        assert_eq!(n, 1);
+    }
 }
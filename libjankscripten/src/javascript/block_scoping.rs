@@ -0,0 +1,945 @@
+//! give `let` and `const` block scope
+//!
+//! Later passes treat every variable like a `var`, which is scoped to its
+//! function. So, we rename each `let` and `const` that is declared within a
+//! nested block to a unique name:
+//!
+//! ```javascript
+//! let x = 1;
+//! { let x = 2; f(x); }
+//! f(x);
+//! ```
+//!
+//! becomes
+//!
+//! ```javascript
+//! let x = 1;
+//! { let $let_0 = 2; f($let_0); }
+//! f(x);
+//! ```
+//!
+//! A loop that declares its variable with `let` or `const` creates a fresh
+//! binding on each iteration, which matters when the body creates closures
+//! that capture the variable. We give the body its own copy of the variable,
+//! and copy it back at the end of the iteration:
+//!
+//! ```javascript
+//! for (let i = 0; i < n; i++) { fs.push(() => i); }
+//! ```
+//!
+//! becomes
+//!
+//! ```javascript
+//! {
+//!     let $let_0 = 0;
+//!     for (; $let_0 < n; $let_0++) {
+//!         let $let_1 = $let_0;
+//!         $iteration_2: { fs.push(() => $let_1); }
+//!         $let_0 = $let_1;
+//!     }
+//! }
+//! ```
+//!
+//! where `continue` in the body becomes `break $iteration_2`. Since `let`
+//! within a loop is not lifted by `lift_vars`, each iteration gets its own
+//! `$let_1`.
+//!
+//! The variable of a catch block is renamed too, since it is only in scope
+//! within the catch block, and so is the name of a class, which is only in
+//! scope within the class.
+//!
+//! Using a variable before its declaration has run (i.e., in its temporal
+//! dead zone) throws a `ReferenceError`. When a use may run before the
+//! declaration, we record whether the declaration has run in a variable of
+//! its own, and check it at the use:
+//!
+//! ```javascript
+//! function f() { return x; }
+//! f();
+//! let x = 1;
+//! ```
+//!
+//! becomes
+//!
+//! ```javascript
+//! let $initialized_0 = false;
+//! function f() {
+//!     return ($initialized_0 || __JNKS.throw_error("ReferenceError", ...), x);
+//! }
+//! f();
+//! let x = 1;
+//! $initialized_0 = true;
+//! ```
+//!
+//! A use may run before the declaration when it comes before it, or when it
+//! is within a function statement in the same block, since the function
+//! statement is hoisted. We do not check the variables of destructuring
+//! assignments and `for (x of e)` loops.
+//!
+//! We also report two errors at compile time: assigning to a `const`, and
+//! using a variable in its temporal dead zone when the use definitely runs,
+//! i.e., when it is not within a function, a conditional, or a loop, and
+//! does not follow a statement that jumps.
+//!
+//! Linking modules uses the same walk to give the top-level variables of
+//! each module their unique names (see `rename_module`).
+
+use super::constructors::*;
+use super::desugar::{DesugarError, DesugarResult};
+use super::modules::Renamed;
+use super::syntax::*;
+use super::*;
+use crate::pos::Pos;
+use std::collections::HashMap;
+
+pub fn block_scoping(program: &mut Stmt, ng: &mut NameGen) -> DesugarResult<()> {
+    let mut v = ResolveScopes {
+        ng,
+        scopes: vec![],
+        handlers: vec![],
+        conditionals: 0,
+        checks: vec![],
+        linking: false,
+        top: None,
+        error: None,
+    };
+    program.walk(&mut v);
+    if let Some(err) = v.error {
+        return Err(err);
+    }
+    program.walk(&mut PerIteration { ng });
+    Ok(())
+}
+
+/// Renames the top-level variables of a module, and the variables that it
+/// imports, to the names in `renames`, where they are not shadowed. The
+/// modules are linked before they are desugared, so this leaves the other
+/// variables and the temporal dead zone to `block_scoping`.
+pub(super) fn rename_module(
+    body: &mut Stmt,
+    renames: &HashMap<Id, Renamed>,
+    ng: &mut NameGen,
+) -> DesugarResult<()> {
+    let bindings = renames
+        .iter()
+        .map(|(x, renamed)| {
+            let binding = Binding {
+                name: renamed.name.clone(),
+                kind: VarKind::Var,
+                state: State::Initialized,
+                initialized: None,
+                is_import: renamed.is_import,
+            };
+            (x.clone(), binding)
+        })
+        .collect();
+    let mut v = ResolveScopes {
+        ng,
+        scopes: vec![],
+        handlers: vec![],
+        conditionals: 0,
+        checks: vec![],
+        linking: true,
+        top: Some(Scope {
+            bindings,
+            ..Scope::default()
+        }),
+        error: None,
+    };
+    body.walk(&mut v);
+    match v.error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum State {
+    /// The declaration has not run yet
+    Uninitialized,
+    /// We are within the declaration, e.g., in its initializer
+    Initializing,
+    Initialized,
+}
+
+struct Binding {
+    /// The unique name of the variable
+    name: Id,
+    kind: VarKind,
+    state: State,
+    /// The variable that records whether the declaration has run, when a
+    /// use may run before it
+    initialized: Option<Id>,
+    /// Whether the variable is imported from another module, so it is an
+    /// error to assign to it
+    is_import: bool,
+}
+
+#[derive(Default)]
+struct Scope {
+    bindings: HashMap<Id, Binding>,
+    /// The scope of a function's parameters and `var`s
+    is_function: bool,
+    /// The scope of a function statement, which is hoisted to the top of
+    /// its block
+    is_hoisted: bool,
+    /// The number of enclosing conditionals and loops where the scope begins
+    conditionals: usize,
+    /// Whether a statement in the scope jumps, so that the rest of the
+    /// scope may not run
+    jumps: bool,
+}
+
+impl Scope {
+    fn function(names: impl IntoIterator<Item = Id>) -> Self {
+        let bindings = names
+            .into_iter()
+            .map(|x| {
+                let binding = Binding {
+                    name: x.clone(),
+                    kind: VarKind::Var,
+                    state: State::Initialized,
+                    initialized: None,
+                    is_import: false,
+                };
+                (x, binding)
+            })
+            .collect();
+        Scope {
+            bindings,
+            is_function: true,
+            ..Scope::default()
+        }
+    }
+}
+
+/// Renames `let` and `const` variables, and reports errors
+struct ResolveScopes<'a> {
+    ng: &'a mut NameGen,
+    scopes: Vec<Scope>,
+    /// The catch blocks of the enclosing `try` statements, which we set
+    /// aside while we walk their bodies
+    handlers: Vec<Stmt>,
+    /// The number of enclosing conditionals and loops
+    conditionals: usize,
+    /// For each enclosing use of a variable, the check that its
+    /// declaration has run, if it needs one
+    checks: Vec<Option<Expr>>,
+    /// Whether we are linking a module, which only renames its top-level
+    /// variables and its imports
+    linking: bool,
+    /// The scope of the top-level of the module that we are linking
+    top: Option<Scope>,
+    error: Option<DesugarError>,
+}
+
+impl ResolveScopes<'_> {
+    /// A fresh name for the variable `x`, which keeps its name while we
+    /// link modules
+    fn fresh(&mut self, x: &Id, prefix: &'static str) -> Id {
+        if self.linking {
+            x.clone()
+        } else {
+            self.ng.fresh(prefix)
+        }
+    }
+
+    /// The scope of the lexical declarations in `decls`. When `rename` is
+    /// true, each variable gets a fresh name.
+    fn lexical_scope<'b>(
+        &mut self,
+        decls: impl Iterator<Item = &'b VarDecl>,
+        rename: bool,
+    ) -> Scope {
+        let mut scope = Scope::default();
+        for decl in decls.filter(|d| d.kind != VarKind::Var) {
+            let name = if rename {
+                self.fresh(&decl.name, "let")
+            } else {
+                decl.name.clone()
+            };
+            let binding = Binding {
+                name,
+                kind: decl.kind,
+                state: State::Uninitialized,
+                initialized: None,
+                is_import: false,
+            };
+            scope.bindings.insert(decl.name.clone(), binding);
+        }
+        scope
+    }
+
+    /// Rename the declarations in `decls`, and mark the lexical ones, which
+    /// are in the innermost scope, as being initialized
+    fn declare(&mut self, decls: &mut [VarDecl], s: &Pos) {
+        for decl in decls.iter_mut().filter(|d| d.kind == VarKind::Var) {
+            self.resolve(&mut decl.name, false, s);
+        }
+        let scope = self
+            .scopes
+            .last_mut()
+            .expect("declaration outside of a scope");
+        for decl in decls.iter_mut().filter(|d| d.kind != VarKind::Var) {
+            if let Some(binding) = scope.bindings.get_mut(&decl.name) {
+                binding.state = State::Initializing;
+                decl.name = binding.name.clone();
+            }
+            // later passes may assign to the variable, e.g., to desugar a
+            // destructuring declaration
+            if !self.linking {
+                decl.kind = VarKind::Let;
+            }
+        }
+    }
+
+    /// Mark the variables that `decls` declares, which are in the innermost
+    /// scope, as initialized
+    fn initialize(&mut self, decls: &[VarDecl]) {
+        let scope = self
+            .scopes
+            .last_mut()
+            .expect("declaration outside of a scope");
+        for binding in scope.bindings.values_mut() {
+            if decls.iter().any(|d| d.name == binding.name) {
+                binding.state = State::Initialized;
+            }
+        }
+    }
+
+    /// Push a scope that begins here
+    fn push(&mut self, mut scope: Scope) {
+        scope.conditionals = self.conditionals;
+        self.scopes.push(scope);
+    }
+
+    /// Rename a use of the variable `x`, and report an error if the use is
+    /// in its temporal dead zone, or if it assigns to a constant or an
+    /// import. When the use may run before the declaration of `x`, produces
+    /// the check that the declaration has run
+    fn resolve(&mut self, x: &mut Id, assigns: bool, s: &Pos) -> Option<Expr> {
+        let ng = &mut *self.ng;
+        let mut in_function = false;
+        let mut in_hoisted = false;
+        let mut jumps = false;
+        let mut found = None;
+        for scope in self.scopes.iter_mut().rev() {
+            jumps = jumps || scope.jumps;
+            if let Some(binding) = scope.bindings.get_mut(x) {
+                let definite = !in_function && !jumps && scope.conditionals == self.conditionals;
+                let err = if assigns && binding.is_import {
+                    Some(DesugarError::AssignToImport(x.clone(), s.clone()))
+                } else if self.linking {
+                    None
+                } else if binding.state == State::Uninitialized && definite {
+                    Some(DesugarError::UseBeforeInit(x.clone(), s.clone()))
+                } else if assigns
+                    && binding.kind == VarKind::Const
+                    && binding.state == State::Initialized
+                {
+                    Some(DesugarError::AssignToConst(x.clone(), s.clone()))
+                } else {
+                    None
+                };
+                let early = !self.linking
+                    && (binding.state == State::Uninitialized && !definite
+                        || in_hoisted && binding.kind != VarKind::Var);
+                let check = if early {
+                    let initialized = binding
+                        .initialized
+                        .get_or_insert_with(|| ng.fresh("initialized"));
+                    Some(check_initialized(initialized.clone(), x, s))
+                } else {
+                    None
+                };
+                *x = binding.name.clone();
+                found = Some((err, check));
+                break;
+            }
+            in_function = in_function || scope.is_function;
+            in_hoisted = scope.is_hoisted;
+        }
+        let (err, check) = found?;
+        if let Some(err) = err {
+            self.report(err);
+        }
+        check
+    }
+
+    fn resolve_lval(&mut self, lv: &mut LValue, s: &Pos) -> Option<Expr> {
+        match lv {
+            LValue::Id(x) => self.resolve(x, true, s),
+            _ => None,
+        }
+    }
+
+    fn resolve_pattern(&mut self, pat: &mut Pattern, s: &Pos) {
+        match pat {
+            Pattern::LValue(lv) => {
+                self.resolve_lval(lv, s);
+            }
+            Pattern::Array(elems, rest) => {
+                for pat in elems.iter_mut().flatten() {
+                    self.resolve_pattern(pat, s);
+                }
+                if let Some(rest) = rest {
+                    self.resolve_pattern(rest, s);
+                }
+            }
+            Pattern::Object(props, rest) => {
+                for (_, pat) in props {
+                    self.resolve_pattern(pat, s);
+                }
+                if let Some(rest) = rest {
+                    self.resolve_pattern(rest, s);
+                }
+            }
+            Pattern::Default(pat, _) => self.resolve_pattern(pat, s),
+        }
+    }
+
+    fn report(&mut self, err: DesugarError) {
+        if self.error.is_none() {
+            self.error = Some(err);
+        }
+    }
+}
+
+impl Visitor for ResolveScopes<'_> {
+    fn enter_stmt(&mut self, stmt: &mut Stmt, loc: &Loc) {
+        match stmt {
+            Stmt::Block(stmts, _) => {
+                let top = match loc {
+                    Loc::Top => self.top.take(),
+                    _ => None,
+                };
+                // the variables at the top of a function or the program are
+                // already scoped to the function
+                let rename = !matches!(loc, Loc::Top | Loc::Node(Context::FunctionBody, _));
+                let decls = stmts.iter().flat_map(|stmt| match stmt {
+                    Stmt::VarDecl(decls, _) => decls.as_slice(),
+                    _ => &[],
+                });
+                let scope = match top {
+                    Some(top) => top,
+                    None => self.lexical_scope(decls, rename),
+                };
+                self.push(scope);
+            }
+            Stmt::VarDecl(decls, s) => {
+                let s = s.clone();
+                self.declare(decls, &s);
+            }
+            Stmt::For(init, _, _, _, s) => {
+                // the walker does not visit the initializer
+                match init {
+                    ForInit::Expr(e) => e.walk(self),
+                    ForInit::Decl(decls) => {
+                        let scope = self.lexical_scope(decls.iter(), true);
+                        self.push(scope);
+                        let s = s.clone();
+                        self.declare(decls, &s);
+                        for decl in decls.iter_mut() {
+                            decl.named.walk(self);
+                        }
+                        self.initialize(decls);
+                    }
+                }
+            }
+            Stmt::ForIn(kind, x, _, _, s) | Stmt::ForOf(kind, x, _, _, s) => match kind {
+                None => {
+                    self.resolve(x, true, s);
+                }
+                Some(VarKind::Var) => {
+                    self.resolve(x, false, s);
+                }
+                Some(lexical) => {
+                    let name = self.fresh(x, "let");
+                    let mut scope = Scope::default();
+                    let binding = Binding {
+                        name: name.clone(),
+                        kind: *lexical,
+                        state: State::Initialized,
+                        initialized: None,
+                        is_import: false,
+                    };
+                    scope.bindings.insert(x.clone(), binding);
+                    self.push(scope);
+                    *x = name;
+                    if !self.linking {
+                        *lexical = VarKind::Let;
+                    }
+                }
+            },
+            Stmt::Func(name, params, _, body, s) => {
+                let s = s.clone();
+                self.resolve(name, false, &s);
+                let mut scope = Scope::function(params.iter().cloned().chain(vars_of(body)));
+                scope.is_hoisted = true;
+                self.push(scope);
+            }
+            // the variable of a catch block is only in scope within it, so
+            // we walk the catch block ourselves after the body
+            Stmt::Catch(_, _, handler, _) => self.handlers.push(handler.take()),
+            _ => (),
+        }
+        if is_conditional(stmt) {
+            self.conditionals += 1;
+        }
+    }
+
+    fn exit_stmt(&mut self, stmt: &mut Stmt, _loc: &Loc) {
+        match stmt {
+            Stmt::VarDecl(decls, _) => self.initialize(decls),
+            Stmt::Block(stmts, s) => {
+                let scope = self.scopes.pop().expect("block outside of a scope");
+                if scope.jumps {
+                    if let Some(parent) = self.scopes.last_mut() {
+                        parent.jumps = true;
+                    }
+                }
+                record_initialized(scope, stmts, s);
+            }
+            Stmt::Func(..) => {
+                self.scopes.pop();
+            }
+            Stmt::Break(..) | Stmt::Continue(..) | Stmt::Return(..) | Stmt::Throw(..) => {
+                if let Some(scope) = self.scopes.last_mut() {
+                    scope.jumps = true;
+                }
+            }
+            Stmt::Catch(_, x, handler, s) => {
+                let name = self.fresh(x, "caught");
+                let mut scope = Scope::default();
+                let binding = Binding {
+                    name: name.clone(),
+                    kind: VarKind::Let,
+                    state: State::Initialized,
+                    initialized: None,
+                    is_import: false,
+                };
+                scope.bindings.insert(x.clone(), binding);
+                self.push(scope);
+                // the block within this one is not at the top, so its lexical
+                // declarations are renamed
+                let block = self.handlers.pop().expect("catch block was not set aside");
+                let mut block = Stmt::Block(vec![block], s.clone());
+                block.walk(self);
+                self.scopes.pop();
+                **handler = block;
+                *x = name;
+            }
+            Stmt::For(ForInit::Decl(..), ..) => {
+                self.scopes.pop();
+            }
+            Stmt::ForIn(Some(kind), ..) | Stmt::ForOf(Some(kind), ..) if *kind != VarKind::Var => {
+                self.scopes.pop();
+            }
+            _ => (),
+        }
+        if is_conditional(stmt) {
+            self.conditionals -= 1;
+        }
+    }
+
+    fn enter_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        match expr {
+            Expr::Id(x, s) => {
+                let check = self.resolve(x, false, s);
+                self.checks.push(check);
+            }
+            Expr::Assign(_, lv, _, s) | Expr::UnaryAssign(_, lv, s) => {
+                let s = s.clone();
+                let check = self.resolve_lval(lv, &s);
+                self.checks.push(check);
+            }
+            Expr::Destructure(pat, _, s) => {
+                let s = s.clone();
+                self.resolve_pattern(pat, &s);
+            }
+            Expr::Func(name, params, _, body, _) => {
+                let names = name.iter().chain(params.iter()).cloned();
+                let scope = Scope::function(names.chain(vars_of(body)));
+                self.push(scope);
+            }
+            Expr::Arrow(params, _, body, _) => {
+                let scope = Scope::function(params.iter().cloned().chain(vars_of(body)));
+                self.push(scope);
+            }
+            Expr::Class(class, _) => {
+                // the name of a class is a constant within the class
+                let mut scope = Scope::default();
+                if let Some(x) = &mut class.name {
+                    let name = self.fresh(x, "class");
+                    let binding = Binding {
+                        name: name.clone(),
+                        kind: VarKind::Const,
+                        state: State::Initialized,
+                        initialized: None,
+                        is_import: false,
+                    };
+                    scope.bindings.insert(x.clone(), binding);
+                    *x = name;
+                }
+                self.push(scope);
+            }
+            _ => (),
+        }
+        if is_short_circuit(expr) {
+            self.conditionals += 1;
+        }
+    }
+
+    fn exit_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        match expr {
+            Expr::Func(..) | Expr::Arrow(..) | Expr::Class(..) => {
+                self.scopes.pop();
+            }
+            Expr::Id(_, s) | Expr::Assign(.., s) | Expr::UnaryAssign(.., s) => {
+                let check = self.checks.pop().expect("use was not resolved");
+                if let Some(check) = check {
+                    let s = s.clone();
+                    *expr = Expr::Seq(vec![check, expr.take()], s);
+                }
+            }
+            _ => (),
+        }
+        if is_short_circuit(expr) {
+            self.conditionals -= 1;
+        }
+    }
+}
+
+/// Whether `stmt` may not run all of its parts, or may run them many times
+fn is_conditional(stmt: &Stmt) -> bool {
+    matches!(
+        stmt,
+        Stmt::If(..)
+            | Stmt::Switch(..)
+            | Stmt::While(..)
+            | Stmt::DoWhile(..)
+            | Stmt::For(..)
+            | Stmt::ForIn(..)
+            | Stmt::ForOf(..)
+            | Stmt::Label(..)
+            | Stmt::Catch(..)
+            | Stmt::Finally(..)
+    )
+}
+
+/// Whether `expr` may not evaluate all of its operands
+fn is_short_circuit(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::If(..) | Expr::Binary(BinOp::LogicalOp(..), ..) | Expr::OptChain(..)
+    )
+}
+
+/// `initialized || __JNKS.throw_error("ReferenceError", ...)`, which checks
+/// that the declaration of `x` has run
+fn check_initialized(initialized: Id, x: &Id, s: &Pos) -> Expr {
+    let message = format!("Cannot access '{}' before initialization", x);
+    let throw = call_(
+        dot_(id_("__JNKS", s.clone()), "throw_error", s.clone()),
+        vec![str_("ReferenceError", s.clone()), str_(message, s.clone())],
+        s.clone(),
+    );
+    or_(id_(initialized, s.clone()), throw, s.clone())
+}
+
+/// `{ ...; let x = e; ... }` =>
+/// `{ let $initialized = false; ...; let x = e; $initialized = true; ... }`,
+/// for each variable in `scope` that a use may need to check
+fn record_initialized(scope: Scope, stmts: &mut Vec<Stmt>, s: &Pos) {
+    let mut initialized = scope
+        .bindings
+        .into_values()
+        .filter_map(|binding| Some((binding.name, binding.initialized?)))
+        .collect::<HashMap<_, _>>();
+    if initialized.is_empty() {
+        return;
+    }
+    let mut block = Vec::with_capacity(stmts.len() + 2 * initialized.len());
+    for flag in initialized.values() {
+        block.push(Stmt::VarDecl(
+            vec![VarDecl {
+                name: flag.clone(),
+                named: Box::new(FALSE_),
+                kind: VarKind::Let,
+            }],
+            s.clone(),
+        ));
+    }
+    for stmt in stmts.drain(..) {
+        let mut flags = vec![];
+        if let Stmt::VarDecl(decls, _) = &stmt {
+            for decl in decls {
+                flags.extend(initialized.remove(&decl.name));
+            }
+        }
+        block.push(stmt);
+        for flag in flags {
+            block.push(expr_(assign_(flag, TRUE_, s.clone()), s.clone()));
+        }
+    }
+    *stmts = block;
+}
+
+/// The variables that the `var` declarations and function statements in
+/// `body` declare, excluding those in nested functions
+pub(super) fn vars_of(body: &mut Stmt) -> Vec<Id> {
+    let mut v = VarsOf {
+        vars: vec![],
+        depth: 0,
+    };
+    body.walk(&mut v);
+    v.vars
+}
+
+struct VarsOf {
+    vars: Vec<Id>,
+    /// The number of functions that we are nested in
+    depth: usize,
+}
+
+impl Visitor for VarsOf {
+    fn enter_stmt(&mut self, stmt: &mut Stmt, _loc: &Loc) {
+        if self.depth > 0 {
+            if let Stmt::Func(..) = stmt {
+                self.depth += 1;
+            }
+            return;
+        }
+        match stmt {
+            Stmt::VarDecl(decls, _) | Stmt::For(ForInit::Decl(decls), ..) => {
+                let vars = decls.iter().filter(|d| d.kind == VarKind::Var);
+                self.vars.extend(vars.map(|d| d.name.clone()));
+            }
+            Stmt::ForIn(Some(VarKind::Var), x, ..) | Stmt::ForOf(Some(VarKind::Var), x, ..) => {
+                self.vars.push(x.clone())
+            }
+            Stmt::Func(name, ..) => {
+                self.vars.push(name.clone());
+                self.depth += 1;
+            }
+            _ => (),
+        }
+    }
+
+    fn exit_stmt(&mut self, stmt: &mut Stmt, _loc: &Loc) {
+        if let Stmt::Func(..) = stmt {
+            self.depth -= 1;
+        }
+    }
+
+    fn enter_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        if let Expr::Func(..) | Expr::Arrow(..) = expr {
+            self.depth += 1;
+        }
+    }
+
+    fn exit_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        if let Expr::Func(..) | Expr::Arrow(..) = expr {
+            self.depth -= 1;
+        }
+    }
+}
+
+/// Gives loops whose bodies create closures a fresh binding of their
+/// variables on each iteration
+struct PerIteration<'a> {
+    ng: &'a mut NameGen,
+}
+
+impl PerIteration<'_> {
+    /// `for (let x = e; test; update) body`, where `label` labels the loop
+    fn for_loop(&mut self, stmt: &mut Stmt, label: Option<&Id>) {
+        let (init, body, s) = match stmt {
+            Stmt::For(init, _, _, body, s) => {
+                let lexical = matches!(init, ForInit::Decl(decls) if decls[0].kind != VarKind::Var);
+                if !lexical || !creates_closures(body) {
+                    return;
+                }
+                match std::mem::replace(init, ForInit::Expr(Box::new(UNDEFINED_))) {
+                    ForInit::Decl(decls) => (decls, body, s.clone()),
+                    ForInit::Expr(..) => unreachable!(),
+                }
+            }
+            _ => return,
+        };
+        let mut copies = HashMap::new();
+        let mut iteration = vec![];
+        let mut copy_back = vec![];
+        for decl in &init {
+            let copy = self.ng.fresh("let");
+            iteration.push(Stmt::VarDecl(
+                vec![VarDecl {
+                    name: copy.clone(),
+                    named: Box::new(id_(decl.name.clone(), s.clone())),
+                    kind: VarKind::Let,
+                }],
+                s.clone(),
+            ));
+            copy_back.push(expr_(
+                assign_(decl.name.clone(), id_(copy.clone(), s.clone()), s.clone()),
+                s.clone(),
+            ));
+            copies.insert(decl.name.clone(), copy);
+        }
+        let iteration_label = self.ng.fresh("iteration");
+        let mut old_body = body.take();
+        old_body.walk(&mut Rename(copies));
+        old_body.walk(&mut ContinueToBreak {
+            label,
+            target: &iteration_label,
+        });
+        iteration.push(label_(iteration_label, old_body, s.clone()));
+        iteration.extend(copy_back);
+        **body = Stmt::Block(iteration, s.clone());
+
+        let for_loop = stmt.take();
+        *stmt = Stmt::Block(vec![Stmt::VarDecl(init, s.clone()), for_loop], s);
+    }
+}
+
+impl Visitor for PerIteration<'_> {
+    fn enter_stmt(&mut self, stmt: &mut Stmt, _loc: &Loc) {
+        match stmt {
+            Stmt::Label(x, body, _) if matches!(**body, Stmt::For(..)) => {
+                let x = x.clone();
+                self.for_loop(body, Some(&x));
+            }
+            Stmt::For(..) => self.for_loop(stmt, None),
+            // for (let x of e) body => for (var $x of e) { let x = $x; body }
+            Stmt::ForIn(kind, x, _, body, s) | Stmt::ForOf(kind, x, _, body, s)
+                if *kind == Some(VarKind::Let) =>
+            {
+                *kind = Some(VarKind::Var);
+                if creates_closures(body) {
+                    let elem = self.ng.fresh("elem");
+                    let decl = VarDecl {
+                        name: std::mem::replace(x, elem.clone()),
+                        named: Box::new(id_(elem, s.clone())),
+                        kind: VarKind::Let,
+                    };
+                    let decl = Stmt::VarDecl(vec![decl], s.clone());
+                    **body = Stmt::Block(vec![decl, body.take()], s.clone());
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+/// Whether `stmt` contains a function, which may capture variables
+fn creates_closures(stmt: &mut Stmt) -> bool {
+    struct CreatesClosures(bool);
+    impl Visitor for CreatesClosures {
+        fn enter_stmt(&mut self, stmt: &mut Stmt, _loc: &Loc) {
+            if let Stmt::Func(..) = stmt {
+                self.0 = true;
+            }
+        }
+        fn enter_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+            if let Expr::Func(..) | Expr::Arrow(..) | Expr::Class(..) = expr {
+                self.0 = true;
+            }
+        }
+    }
+    let mut v = CreatesClosures(false);
+    stmt.walk(&mut v);
+    v.0
+}
+
+/// Renames variables that have unique names, so there is no shadowing to
+/// worry about
+struct Rename(HashMap<Id, Id>);
+
+impl Rename {
+    fn rename(&self, x: &mut Id) {
+        if let Some(y) = self.0.get(x) {
+            *x = y.clone();
+        }
+    }
+}
+
+impl Visitor for Rename {
+    fn enter_stmt(&mut self, stmt: &mut Stmt, _loc: &Loc) {
+        match stmt {
+            Stmt::For(ForInit::Expr(e), ..) => e.walk(self),
+            Stmt::For(ForInit::Decl(decls), ..) => {
+                for decl in decls {
+                    decl.named.walk(self);
+                }
+            }
+            Stmt::ForIn(None, x, ..) | Stmt::ForOf(None, x, ..) => self.rename(x),
+            _ => (),
+        }
+    }
+
+    fn enter_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        match expr {
+            Expr::Id(x, _) => self.rename(x),
+            Expr::Assign(_, lv, ..) | Expr::UnaryAssign(_, lv, _) => {
+                if let LValue::Id(x) = &mut **lv {
+                    self.rename(x);
+                }
+            }
+            Expr::Destructure(pat, ..) => rename_pattern(pat, self),
+            _ => (),
+        }
+    }
+}
+
+fn rename_pattern(pat: &mut Pattern, r: &Rename) {
+    match pat {
+        Pattern::LValue(LValue::Id(x)) => r.rename(x),
+        Pattern::LValue(_) => (),
+        Pattern::Array(elems, rest) => {
+            for pat in elems.iter_mut().flatten() {
+                rename_pattern(pat, r);
+            }
+            if let Some(rest) = rest {
+                rename_pattern(rest, r);
+            }
+        }
+        Pattern::Object(props, rest) => {
+            for (_, pat) in props {
+                rename_pattern(pat, r);
+            }
+            if let Some(rest) = rest {
+                rename_pattern(rest, r);
+            }
+        }
+        Pattern::Default(pat, _) => rename_pattern(pat, r),
+    }
+}
+
+/// Turns `continue` in the body of a loop into `break target`, where
+/// `label` labels the loop
+struct ContinueToBreak<'a> {
+    label: Option<&'a Id>,
+    target: &'a Id,
+}
+
+impl Visitor for ContinueToBreak<'_> {
+    fn enter_stmt(&mut self, stmt: &mut Stmt, loc: &Loc) {
+        if let Stmt::Continue(x, s) = stmt {
+            let ours = match x {
+                None => !loc.in_loop() && !in_function(loc),
+                Some(x) => Some(&*x) == self.label,
+            };
+            if ours {
+                *stmt = break_(Some(self.target.clone()), s.clone());
+            }
+        }
+    }
+}
+
+fn in_function(loc: &Loc) -> bool {
+    match loc {
+        Loc::Top => false,
+        Loc::Node(Context::FunctionBody, _) => true,
+        Loc::Node(_, rest) => in_function(rest),
+    }
+}
@@ -27,7 +27,7 @@ pub fn for_(a: ForInit, b: Expr, c: Expr, d: Stmt, s: Pos) -> Stmt {
     Stmt::For(a, Box::new(b), Box::new(c), Box::new(d), s)
 }
 
-pub fn forin_<I: Into<Id>>(a: bool, b: I, c: Expr, d: Stmt, s: Pos) -> Stmt {
+pub fn forin_<I: Into<Id>>(a: Option<VarKind>, b: I, c: Expr, d: Stmt, s: Pos) -> Stmt {
     Stmt::ForIn(a, b.into(), Box::new(c), Box::new(d), s)
 }
 
@@ -60,6 +60,7 @@ pub fn vardecl1_<T: Into<Id>>(name: T, val: Expr, s: Pos) -> Stmt {
         vec![VarDecl {
             name: name.into(),
             named: Box::new(val),
+            kind: VarKind::Var,
         }],
         s,
     )
@@ -69,6 +70,7 @@ pub fn func_<I: Into<Id>, J: Into<Id>>(a: I, b: Vec<J>, c: Stmt, s: Pos) -> Stmt
     Stmt::Func(
         a.into(),
         b.into_iter().map(Into::into).collect(),
+        false,
         Box::new(c),
         s,
     )
@@ -147,6 +149,7 @@ pub fn expr_func_<I: Into<Id>, J: Into<Id>>(a: Option<I>, b: Vec<J>, c: Stmt, s:
     Expr::Func(
         a.map(|x| x.into()),
         b.into_iter().map(Into::into).collect(),
+        false,
         Box::new(c),
         s,
     )
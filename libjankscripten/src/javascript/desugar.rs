@@ -1,6 +1,29 @@
 use super::*;
+use crate::pos::Pos;
+use thiserror::Error;
+
+/// An error in a program that we find while desugaring it
+#[derive(Debug, Error)]
+pub enum DesugarError {
+    #[error("assignment to constant variable {0} at {1}")]
+    AssignToConst(Id, Pos),
+    #[error("cannot access {0} before initialization at {1}")]
+    UseBeforeInit(Id, Pos),
+    #[error("assignment to imported variable {0} at {1}")]
+    AssignToImport(Id, Pos),
+}
 
-pub fn desugar(stmt: &mut Stmt, ng: &mut NameGen) {
+pub type DesugarResult<T> = Result<T, DesugarError>;
+
+pub fn desugar(stmt: &mut Stmt, ng: &mut NameGen) -> DesugarResult<()> {
+    // renames variables, so it goes first
+    block_scoping::block_scoping(stmt, ng)?;
+    // adds the `arguments` parameter, which desugar_params passes or
+    // collects, so it must come before desugar_params
+    desugar_arguments::desugar_arguments(stmt, ng);
+    // needs to see function statements, classes and spread calls before
+    // they are desugared
+    desugar_params::desugar_params(stmt, ng);
     stmt.walk(&mut super::desugar_function_stmts::DesugarFunctionStmts {});
     normalize_std_lib_calls::normalize_std_lib_calls(stmt);
     desugar_switch::desugar_switch(stmt, ng);
@@ -11,21 +34,45 @@ pub fn desugar(stmt: &mut Stmt, ng: &mut NameGen) {
     add_blocks::add_blocks(stmt);
     // dep: desugar_loops, add_blocks
     desugar_vardecls::desugar_vardecls(stmt);
-    // dep: desugar_vardecls
+    // produces CallWithThis for optional method calls, so it must come
+    // before desugar_this. it also puts each operand in its own block, so
+    // anything that inserts statements for an operand must come after it
+    // dep: desugar_loops, add_blocks
+    desugar_logical::desugar_logical(stmt, ng);
+    // inserts statements before the declaration that contains a pattern, so
+    // declarations must be separated first
+    // dep: desugar_vardecls, desugar_logical
+    desugar_destructuring::desugar_destructuring(stmt, ng);
+    // inserts statements before the statement that contains a yield, so
+    // yields within an operand must already be in its own block. the returns
+    // that it inserts must run finally blocks
+    // dep: desugar_logical
+    desugar_generators::desugar_yield(stmt, ng);
+    // dep: desugar_loops, add_blocks
+    desugar_finally::desugar_finally(stmt, ng);
+    // produces CallWithThis, so it must come before desugar_this. the
+    // constructor of a class may refer to the class, so the variable that
+    // holds it must be lifted
+    // dep: add_blocks
+    desugar_classes::desugar_classes(stmt, ng);
+    // dep: desugar_vardecls, desugar_classes
     // we want this to go sooner rather than later to reduce anys
     lift_vars::lift_vars(stmt);
+    // the locals of a generator are the variables lifted to the top of its
+    // body, so this must come after lift_vars. it produces arrows
+    // dep: desugar_yield, desugar_finally, lift_vars
+    desugar_generators::desugar_generators(stmt, ng);
     // dep: add_blocks, normalize_std_lib_calls
     desugar_this::desugar_this(stmt, ng);
     // accesses are immediately applied
     // dep: desugar_this, add_blocks
     desugar_function_applications::desugar_function_applications(stmt, ng);
-    // dep: desugar_loops, add_blocks
-    desugar_logical::desugar_logical(stmt, ng);
     // dep: desugar_function_applications, add_blocks
     desugar_updates::desugar_updates(stmt, ng);
     desugar_bracket_str::desugar_bracket_str(stmt);
     // dep: desugar_this
     resugar_method_call::resugar_method_call(stmt);
+    Ok(())
 }
 
 #[cfg(test)]
@@ -34,7 +81,7 @@ mod test {
     use crate::javascript::testing::desugar_okay;
 
     fn okay(script: &str) {
-        desugar_okay(script, desugar);
+        desugar_okay(script, |stmt, ng| desugar(stmt, ng).unwrap());
     }
 
     #[test]
@@ -459,6 +506,62 @@ mod test {
         okay(program);
     }
 
+    #[test]
+    fn desugar_for_of() {
+        let program = r#"
+            var s = "";
+            outer: for (var x of [1, 2, 3]) {
+                for (var c of "ab") {
+                    if (x == 2) continue outer;
+                    s += c + x;
+                }
+            }
+            s;"#;
+        okay(program);
+    }
+
+    #[test]
+    fn for_of_closes_iterator() {
+        let program = r#"
+            var closed = "";
+            function counter(name) {
+                var i = 0;
+                var it = {
+                    next: function () { i++; return { value: i, done: i > 3 }; },
+                    return: function () { closed += name + i; return {}; }
+                };
+                var o = {};
+                o[Symbol.iterator] = function () { return it; };
+                return o;
+            }
+            for (var a of counter("a")) { }
+            for (var b of counter("b")) { if (b == 2) break; }
+            function f() { for (var c of counter("c")) { return c; } }
+            f();
+            try { for (var d of counter("d")) { throw d; } } catch (e) { }
+            var [x, y] = counter("e");
+            closed;"#;
+        okay(program);
+    }
+
+    #[test]
+    fn desugar_for_in() {
+        let program = r#"
+            var s = "";
+            var o = { x: 3, y: 4 };
+            o.z = 5;
+            o.y = 4;
+            for (var k in o) {
+                if (k == "x") delete o.z;
+                for (let i in "ab") {
+                    if (k == "y") continue;
+                    s += k + i;
+                }
+            }
+            s;"#;
+        okay(program);
+    }
+
     #[test]
     fn desugar_ops() {
         let program = "var x = true && false ? true || false : false; x";
@@ -540,6 +643,333 @@ mod test {
         );
     }
 
+    #[test]
+    fn desugar_arrow_this() {
+        okay(
+            "
+            function Counter() {
+                this.n = 10;
+                var add = (k) => this.n + k;
+                var set = () => { this.n = 20; };
+                set();
+                return add(5);
+            }
+            var obj = { n: 0, count: Counter };
+            var r = obj.count();
+            r + obj.n;",
+        );
+    }
+
+    #[test]
+    fn desugar_class() {
+        // after desugar_this, `new` and `Object.create` use our calling
+        // convention, which node doesn't understand, so stop before it
+        fn desugar_to_classes(stmt: &mut Stmt, ng: &mut NameGen) {
+            block_scoping::block_scoping(stmt, ng).unwrap();
+            add_blocks::add_blocks(stmt);
+            // dep: desugar_loops, add_blocks
+            desugar_finally::desugar_finally(stmt, ng);
+            desugar_classes::desugar_classes(stmt, ng);
+            // nor do the helpers that classes call on `__JNKS` receive their
+            // `this` yet
+            let helpers = parse(
+                "anon",
+                "__JNKS = {
+                    construct: function (f, obj, new_target, args) {
+                        Object.assign(obj, Reflect.construct(f, args, new_target));
+                    },
+                    throw_error: function (name, message) { throw name + message; },
+                    non_constructor: function (f) { return f; }
+                };",
+            )
+            .unwrap();
+            match stmt {
+                Stmt::Block(ss, _) => ss.insert(0, helpers),
+                _ => unreachable!("a program is a block"),
+            }
+        }
+        desugar_okay(
+            "
+            class Shape {
+                constructor(sides) {
+                    this.sides = sides;
+                }
+                describe() {
+                    return this.sides;
+                }
+                static unit() {
+                    return 1;
+                }
+            }
+            class Square extends Shape {
+                constructor(len) {
+                    super(4);
+                    this.len = len;
+                }
+                describe() {
+                    return super.describe() * 10 + this.len;
+                }
+            }
+            var Fact = class F {
+                static of(n) {
+                    return n <= 1 ? 1 : n * F.of(n - 1);
+                }
+            };
+            var keys = '';
+            var sq = new Square(3);
+            for (var k in sq) {
+                keys = keys + k;
+            }
+            keys + (sq.describe() + Square.unit() + Fact.of(4));",
+            desugar_to_classes,
+        );
+    }
+
+    #[test]
+    fn desugar_destructuring() {
+        okay(
+            "
+            var [a, [b, c = 3], , d] = [1, [2], 0, 4];
+            var { x, y: { z = 5 }, w = a } = { x: 6, y: {} };
+            function f([p, q], { r }) {
+                return p * q + r;
+            }
+            var m = 7, n = 8;
+            [m, n] = [n, m];
+            a + b + c + d + x + z + w + f([2, 3], { r: 4 }) + m * 10 + n;",
+        );
+    }
+
+    #[test]
+    fn desugar_destructuring_in_operands() {
+        okay(
+            "
+            var a = 1, b = 2, c = false, n = 0;
+            function count() {
+                n = n + 1;
+                return 10;
+            }
+            c && ([a, b] = [3, 4]);
+            c ? ([a] = [5]) : 0;
+            true || ({ b } = { b: 6 });
+            var [d = count() || 20] = [];
+            var [e = c && count()] = [7];
+            a * 1000 + b * 100 + d + e + n;",
+        );
+    }
+
+    #[test]
+    fn desugar_params() {
+        okay(
+            "
+            function f(a, b = 10, ...rest) {
+                return a + b + rest.length;
+            }
+            var g = (x = 1) => x * 2;
+            var o = { f: f };
+            var n = 0;
+            function tick() {
+                n = n + 1;
+                return n;
+            }
+            function one(x) {
+                return x;
+            }
+            var r = f(1) + f(1, 2) + f(1, 2, 3, 4) + g() + g(5) + o.f(1, 2, 3);
+            r = r * 10 + one(tick(), tick(), tick());
+            r * 10 + n;",
+        );
+    }
+
+    #[test]
+    fn desugar_arguments() {
+        okay(
+            "
+            function cnt(a, b) {
+                return arguments.length;
+            }
+            function each(f) {
+                return f(1, 2, 3);
+            }
+            var o = { cnt: cnt };
+            var r = cnt(1) + cnt(1, 2, 3) * 10 + o.cnt() * 100;
+            r * 1000 + each(cnt) + each(function() { return arguments[2]; }) * 10;",
+        );
+    }
+
+    #[test]
+    fn desugar_block_scoping() {
+        okay(
+            "
+            var fs = [];
+            var n = 0;
+            let x = 10;
+            for (let i = 0; i < 4; i++) {
+                let x = i * 2;
+                if (i == 1) { continue; }
+                fs[n++] = () => i + x;
+            }
+            for (const k of [100, 200]) {
+                fs[n++] = function() { return k; };
+            }
+            { const x = 5; fs[n++] = () => x; }
+            var r = x;
+            for (let j = 0; j < n; j++) { r = r * 1000 + fs[j](); }
+            r;",
+        );
+    }
+
+    #[test]
+    fn desugar_generators() {
+        okay(
+            "
+            function* range(lo, hi) {
+                for (let i = lo; i < hi; i++) {
+                    try {
+                        if (i === 3) { throw i; }
+                        yield i;
+                    } catch (e) {
+                        yield -e;
+                    } finally {
+                        lo = lo + 100;
+                    }
+                }
+                return lo;
+            }
+            function* both() {
+                yield* range(0, 2);
+                yield* [7, 8];
+            }
+            var r = 0;
+            for (var x of range(1, 5)) { r = r * 10 + x; }
+            var it = range(0, 10);
+            var a = it.next().value;
+            var b = it.return(9);
+            var c = it.next();
+            var [p, q, w] = both();
+            [r, a, b.value, b.done, c.done, p, q, w];",
+        );
+    }
+
+    #[test]
+    fn desugar_async() {
+        // what is printed shows the order that the bodies run in. we cannot
+        // call the methods of promises, which are not desugared
+        okay(
+            "
+            async function add(a, b) {
+                print('add');
+                var x = await a;
+                return x + await b;
+            }
+            async function fails() {
+                await null;
+                throw 'boom';
+            }
+            var f = async (n) => {
+                for (let i = 0; i < n; i++) {
+                    try {
+                        await fails();
+                    } catch (e) {
+                        print(e + i);
+                    } finally {
+                        print(await add(i, 10));
+                    }
+                }
+                return n;
+            };
+            async function main() {
+                var done = f(2);
+                print(await add(1, 2));
+                print('done ' + await done);
+            }
+            main();
+            print('sync');",
+        );
+    }
+
+    fn desugar_err(script: &str) -> DesugarError {
+        let mut program = parse("anon", script).unwrap();
+        desugar(&mut program, &mut NameGen::default()).unwrap_err()
+    }
+
+    #[test]
+    fn const_assign_is_error() {
+        let err = desugar_err("const c = 1; function f() { c += 1; }");
+        assert!(matches!(err, DesugarError::AssignToConst(..)));
+        let err = desugar_err("for (const i = 0; i < 10; i++) { }");
+        assert!(matches!(err, DesugarError::AssignToConst(..)));
+    }
+
+    #[test]
+    fn use_before_init_is_error() {
+        let err = desugar_err("x; let x = 1;");
+        assert!(matches!(err, DesugarError::UseBeforeInit(..)));
+        let err = desugar_err("f(x); let x = 1;");
+        assert!(matches!(err, DesugarError::UseBeforeInit(..)));
+        // these uses may not run, so they are checked when they do
+        let mut program = parse("anon", "if (false) { x; } let x = 1;").unwrap();
+        desugar(&mut program, &mut NameGen::default()).unwrap();
+        let mut program = parse("anon", "function f() { return y; } let y = 1; f();").unwrap();
+        desugar(&mut program, &mut NameGen::default()).unwrap();
+    }
+
+    #[test]
+    fn use_before_init_throws() {
+        okay(
+            "
+            var r = '';
+            function f() { return y; }
+            try { f(); } catch (e) { r += e.name; }
+            let y = 1;
+            g();
+            let z = 2;
+            function g() { try { r += z; } catch (e) { r += e.name; } }
+            if (false) { z; }
+            g();
+            r + f();",
+        );
+    }
+
+    #[test]
+    fn desugar_optional_chain() {
+        okay(
+            "
+            var o = { a: { b: 10 }, n: null, m: function() { return this.a.b; } };
+            var r = o?.a.b + (o.n?.b ?? 1) + o.m?.() + (o.x?.y.z ?? 2);
+            r;",
+        );
+    }
+
+    #[test]
+    fn desugar_finally() {
+        okay(
+            "
+            var log = '';
+            function f(x) {
+                try {
+                    if (x === 0) { return 'zero'; }
+                    if (x === 1) { throw 'one'; }
+                } finally {
+                    log += x;
+                }
+                return 'other';
+            }
+            for (var i = 0; i < 4; i++) {
+                try {
+                    if (i === 2) { continue; }
+                    var r = f(i);
+                    log += r;
+                } catch (e) {
+                    log += e;
+                } finally {
+                    log += ';';
+                }
+            }
+            log;",
+        );
+    }
+
     #[test]
     fn test_desugar_bracket_str() {
         okay(
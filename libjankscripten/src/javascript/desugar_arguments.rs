@@ -0,0 +1,114 @@
+//! give each function that mentions `arguments` its own `arguments` array
+//!
+//! The array is an extra parameter after the declared parameters:
+//!
+//! ```javascript
+//! function f(x, y) { return arguments.length; }
+//! ```
+//!
+//! becomes
+//!
+//! ```javascript
+//! function f(x, y, $arguments) { return $arguments.length; }
+//! ```
+//!
+//! `desugar_params` passes an array of exactly the arguments of the call as
+//! that parameter at every call to `f`, when it knows all of them. Otherwise,
+//! it collects all the arguments of `f` into `$arguments` instead, and takes
+//! the parameters from it, so that `f` is variadic. A function with a rest
+//! parameter always collects its arguments, and its `arguments` array is the
+//! one that it collects them into. Arrows do not have their own `arguments`,
+//! so an `arguments` within an arrow is the `arguments` of the enclosing
+//! function.
+//!
+//! `arguments` is a copy of the arguments, as it is in strict mode: assigning
+//! to a parameter does not change `arguments`, and vice versa.
+
+use super::syntax::*;
+use super::*;
+use crate::pos::Pos;
+
+struct DesugarArguments<'a> {
+    ng: &'a mut NameGen,
+    /// For each enclosing function, the name of its `arguments` array, if
+    /// it has been mentioned yet
+    functions: Vec<Option<Id>>,
+}
+
+fn is_arguments(x: &Id) -> bool {
+    matches!(x, Id::Named(name) if name == "arguments")
+}
+
+/// Whether `x` is the `arguments` array that this pass added as the last
+/// parameter of a function
+pub(super) fn is_arguments_param(x: &Id) -> bool {
+    x.is_generated("arguments")
+}
+
+impl DesugarArguments<'_> {
+    /// The name of the `arguments` array of the innermost enclosing
+    /// function, or `None` at the top level
+    fn arguments(&mut self) -> Option<Id> {
+        let ng = &mut self.ng;
+        let arguments = self.functions.last_mut()?;
+        Some(
+            arguments
+                .get_or_insert_with(|| ng.fresh("arguments"))
+                .clone(),
+        )
+    }
+
+    /// Add the `arguments` array to the parameters of a function that
+    /// mentions `arguments`
+    fn exit_function(&mut self, params: &mut Vec<Id>, rest: &mut bool, body: &mut Stmt, s: &Pos) {
+        let arguments = match self.functions.pop().unwrap() {
+            Some(arguments) => arguments,
+            None => return,
+        };
+        if *rest {
+            desugar_params::collect_args_into(arguments, params, rest, body, s);
+        } else {
+            params.push(arguments);
+        }
+    }
+}
+
+impl Visitor for DesugarArguments<'_> {
+    fn enter_stmt(&mut self, stmt: &mut Stmt, _loc: &Loc) {
+        if let Stmt::Func(..) = stmt {
+            self.functions.push(None);
+        }
+    }
+
+    fn exit_stmt(&mut self, stmt: &mut Stmt, _loc: &Loc) {
+        if let Stmt::Func(_, params, rest, body, s) = stmt {
+            self.exit_function(params, rest, body, s);
+        }
+    }
+
+    fn enter_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        match expr {
+            Expr::Func(..) => self.functions.push(None),
+            Expr::Id(x, _) if is_arguments(x) => {
+                if let Some(arguments) = self.arguments() {
+                    *x = arguments;
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn exit_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        if let Expr::Func(_, params, rest, body, s) = expr {
+            self.exit_function(params, rest, body, s);
+        }
+    }
+}
+
+pub fn desugar_arguments(program: &mut Stmt, ng: &mut NameGen) {
+    let mut v = DesugarArguments {
+        ng,
+        functions: vec![],
+    };
+    program.walk(&mut v);
+}
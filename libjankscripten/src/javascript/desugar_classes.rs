@@ -0,0 +1,332 @@
+//! desugar classes into constructor functions and prototypes
+//!
+//! ```javascript
+//! class C extends B {
+//!     constructor(x) { super(x); }
+//!     m() { return super.m(); }
+//!     static s() { return 0; }
+//! }
+//! ```
+//!
+//! becomes
+//!
+//! ```javascript
+//! var $super_0 = B;
+//! var $class_0 = function(x) {
+//!     if (new.target === undefined) { __JNKS.throw_error("TypeError", ...); }
+//!     __JNKS.construct($super_0, this, new.target, [x]);
+//! };
+//! $class_0.prototype = Object.create($super_0.prototype);
+//! $class_0.__proto__ = $super_0;
+//! Object.defineProperty($class_0.prototype, "constructor", { value: $class_0, ... });
+//! Object.defineProperty($class_0.prototype, "m", {
+//!     value: __JNKS.non_constructor(function() { return $super_0.prototype.m.call(this); }),
+//!     ...
+//! });
+//! Object.defineProperty($class_0, "s", {
+//!     value: __JNKS.non_constructor(function() { return 0; }), ...
+//! });
+//! var C = $class_0;
+//! ```
+//!
+//! where `f.call(this, ...)` is `Expr::CallWithThis`, which `desugar_this`
+//! eliminates. `super(...)` passes on `new.target`, so that it is `C` in the
+//! constructor of `B` too. Calling a class without `new` throws, and so does
+//! `new` on a method, which is not a constructor. Like the builtin methods,
+//! the methods of a class and the `constructor` of its prototype are
+//! writable and configurable, but not enumerable. `block_scoping` gives the
+//! name of a class a unique name within the class, which we use for the
+//! constructor, so that a named class expression can refer to itself.
+
+use super::constructors::*;
+use super::syntax::*;
+use super::*;
+use crate::pos::Pos;
+
+pub fn desugar_classes(program: &mut Stmt, ng: &mut NameGen) {
+    let mut v = DesugarClasses { ng };
+    program.walk(&mut v);
+}
+
+struct DesugarClasses<'a> {
+    ng: &'a mut NameGen,
+}
+
+impl Visitor for DesugarClasses<'_> {
+    fn exit_expr(&mut self, expr: &mut Expr, loc: &Loc) {
+        // nested classes are desugared on the way up, so any `super` that
+        // remains in this class refers to this class's parent
+        if let Expr::Class(class, s) = expr {
+            let cxt = loc.enclosing_block().expect("class outside of a block");
+            let class_name = match class.name.take() {
+                Some(name) => name,
+                None => self.ng.fresh("class"),
+            };
+            let (super_name, parent) = match class.extends.take() {
+                Some(extends) => {
+                    let super_name = self.ng.fresh("super");
+                    cxt.insert(
+                        cxt.index,
+                        vardecl1_(super_name.clone(), *extends, s.clone()),
+                    );
+                    (Some(super_name.clone()), id_(super_name, s.clone()))
+                }
+                // super.m() in a base class refers to Object.prototype.m
+                None => (None, id_("Object", s.clone())),
+            };
+            let parent_proto = dot_(parent.clone(), "prototype", s.clone());
+
+            let mut constructor = match class.constructor.take() {
+                Some(ctor) => *ctor,
+                None => default_constructor(super_name.is_some(), self.ng, s.clone()),
+            };
+            resolve_super(&mut constructor, &parent, &parent_proto);
+            require_new(&mut constructor, s);
+            cxt.insert(
+                cxt.index,
+                vardecl1_(class_name.clone(), constructor, s.clone()),
+            );
+
+            let proto = || dot_(id_(class_name.clone(), s.clone()), "prototype", s.clone());
+            if let Some(super_name) = super_name {
+                let create = call_(
+                    dot_(id_("Object", s.clone()), "create", s.clone()),
+                    vec![dot_(
+                        id_(super_name.clone(), s.clone()),
+                        "prototype",
+                        s.clone(),
+                    )],
+                    s.clone(),
+                );
+                cxt.insert(
+                    cxt.index,
+                    expr_(
+                        assign_(
+                            LValue::Dot(id_(class_name.clone(), s.clone()), "prototype".into()),
+                            create,
+                            s.clone(),
+                        ),
+                        s.clone(),
+                    ),
+                );
+                // static members are inherited too
+                cxt.insert(
+                    cxt.index,
+                    expr_(
+                        assign_(
+                            LValue::Dot(id_(class_name.clone(), s.clone()), "__proto__".into()),
+                            id_(super_name, s.clone()),
+                            s.clone(),
+                        ),
+                        s.clone(),
+                    ),
+                );
+            }
+            cxt.insert(
+                cxt.index,
+                define_method(
+                    proto(),
+                    Key::Str("constructor".into()),
+                    id_(class_name.clone(), s.clone()),
+                    s,
+                ),
+            );
+
+            for ClassMethod {
+                is_static,
+                key,
+                mut func,
+            } in class.methods.drain(..)
+            {
+                let (owner, home) = if is_static {
+                    (id_(class_name.clone(), s.clone()), &parent)
+                } else {
+                    (proto(), &parent_proto)
+                };
+                resolve_super(&mut func, &parent, home);
+                let method = call_(
+                    dot_(id_("__JNKS", s.clone()), "non_constructor", s.clone()),
+                    vec![func],
+                    s.clone(),
+                );
+                cxt.insert(cxt.index, define_method(owner, key, method, s));
+            }
+
+            *expr = id_(class_name, s.clone());
+        }
+    }
+}
+
+/// `Object.defineProperty(owner, key, { value: method, writable: true,
+/// configurable: true })`, which is not enumerable
+fn define_method(owner: Expr, key: Key, method: Expr, s: &Pos) -> Stmt {
+    let key = match key {
+        Key::Str(x) => str_(x, s.clone()),
+        Key::Int(n) => int_(n, s.clone()),
+    };
+    let t = || Expr::Lit(Lit::Bool(true), s.clone());
+    let desc = Expr::Object(
+        vec![
+            (Key::Str("value".into()), method),
+            (Key::Str("writable".into()), t()),
+            (Key::Str("configurable".into()), t()),
+        ],
+        s.clone(),
+    );
+    let define = call_(
+        dot_(id_("Object", s.clone()), "defineProperty", s.clone()),
+        vec![owner, key, desc],
+        s.clone(),
+    );
+    expr_(define, s.clone())
+}
+
+/// A class can only be called with `new`, so its constructor starts with
+///
+/// ```javascript
+/// if (new.target === undefined) {
+///     __JNKS.throw_error("TypeError", "Class constructor cannot be invoked without 'new'");
+/// }
+/// ```
+fn require_new(constructor: &mut Expr, s: &Pos) {
+    let body = match constructor {
+        Expr::Func(_, _, _, body, _) => body,
+        _ => unreachable!("a constructor is a function"),
+    };
+    let throw = call_(
+        dot_(id_("__JNKS", s.clone()), "throw_error", s.clone()),
+        vec![
+            str_("TypeError", s.clone()),
+            str_(
+                "Class constructor cannot be invoked without 'new'",
+                s.clone(),
+            ),
+        ],
+        s.clone(),
+    );
+    let check = if_(
+        binary_(
+            BinOp::BinaryOp(BinaryOp::StrictEqual),
+            Expr::NewTarget,
+            UNDEFINED_,
+            s.clone(),
+        ),
+        Stmt::Block(vec![expr_(throw, s.clone())], s.clone()),
+        Stmt::Block(vec![], s.clone()),
+        s.clone(),
+    );
+    match &mut **body {
+        Stmt::Block(ss, _) => ss.insert(0, check),
+        body => *body = Stmt::Block(vec![check, body.take()], s.clone()),
+    }
+}
+
+/// `constructor() { }`, or `constructor(...args) { super(...args); }` for a
+/// derived class
+pub fn default_constructor(is_derived: bool, ng: &mut NameGen, s: Pos) -> Expr {
+    if is_derived {
+        let args = ng.fresh("args");
+        let body = vec![expr_(
+            Expr::Apply(
+                Box::new(Expr::Super),
+                None,
+                Box::new(id_(args.clone(), s.clone())),
+                s.clone(),
+            ),
+            s.clone(),
+        )];
+        Expr::Func(
+            None,
+            vec![args],
+            true,
+            Box::new(Stmt::Block(body, s.clone())),
+            s,
+        )
+    } else {
+        Expr::Func(
+            None,
+            vec![],
+            false,
+            Box::new(Stmt::Block(vec![], s.clone())),
+            s,
+        )
+    }
+}
+
+/// Replace `super` in the body of a method. `parent` is the parent
+/// constructor, and `home` is the object that `super.x` reads from
+fn resolve_super(func: &mut Expr, parent: &Expr, home: &Expr) {
+    func.walk(&mut ResolveSuper { parent, home });
+}
+
+struct ResolveSuper<'a> {
+    parent: &'a Expr,
+    home: &'a Expr,
+}
+
+impl ResolveSuper<'_> {
+    /// `super.x` => `home.x`, `super[e]` => `home[e]`
+    fn resolve_member(&self, e: &mut Expr) -> bool {
+        match e {
+            Expr::Dot(obj, _, _) | Expr::Bracket(obj, _, _) if **obj == Expr::Super => {
+                **obj = self.home.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Call the parent constructor with the array `args`. The parent
+    /// constructs `this` for the constructor that `new` called, so it gets
+    /// the same `new.target`
+    fn construct(&self, args: Expr, s: &Pos) -> Expr {
+        call_(
+            dot_(id_("__JNKS", s.clone()), "construct", s.clone()),
+            vec![self.parent.clone(), Expr::This, Expr::NewTarget, args],
+            s.clone(),
+        )
+    }
+}
+
+impl Visitor for ResolveSuper<'_> {
+    fn enter_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        match expr {
+            Expr::Call(f, args, s) => {
+                if **f == Expr::Super {
+                    // super(args) => __JNKS.construct(parent, this, new.target, [args])
+                    let args = Expr::Array(std::mem::take(args), s.clone());
+                    *expr = self.construct(args, s);
+                } else if self.resolve_member(f) {
+                    // super.m(args) => home.m.call(this, args)
+                    *expr = Expr::CallWithThis(
+                        Box::new(f.take()),
+                        Box::new(Expr::This),
+                        std::mem::take(args),
+                        s.clone(),
+                    );
+                }
+            }
+            Expr::Apply(f, this_arg, args, s) if this_arg.is_none() => {
+                if **f == Expr::Super {
+                    // super(...args) => __JNKS.construct(parent, this, new.target, args)
+                    *expr = self.construct(args.take(), s);
+                } else if self.resolve_member(f) {
+                    // super.m(...args) => home.m.apply(this, args)
+                    *this_arg = Some(Box::new(Expr::This));
+                }
+            }
+            // super.x = e assigns to this.x
+            Expr::Assign(_, lv, _, _) => match &mut **lv {
+                LValue::Dot(obj, _) | LValue::Bracket(obj, _) if *obj == Expr::Super => {
+                    *obj = Expr::This;
+                }
+                _ => (),
+            },
+            Expr::Dot(..) | Expr::Bracket(..) => {
+                self.resolve_member(expr);
+            }
+            Expr::Super => panic!("super outside of a call or member expression"),
+            _ => (),
+        }
+    }
+}
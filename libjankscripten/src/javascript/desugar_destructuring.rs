@@ -0,0 +1,184 @@
+//! desugar destructuring assignments into plain assignments
+//!
+//! ```javascript
+//! [a, { b, c = 1 }, ...d] = e;
+//! ```
+//!
+//! becomes
+//!
+//! ```javascript
+//! var $destruct_0 = e;
+//! var $destruct_1 = __JNKS.iter_take($destruct_0, -1);
+//! a = $destruct_1[0];
+//! var $destruct_2 = $destruct_1[1];
+//! b = $destruct_2.b;
+//! var $destruct_3 = $destruct_2.c;
+//! if ($destruct_3 === undefined) {
+//!     $destruct_3 = 1;
+//! }
+//! c = $destruct_3;
+//! d = $destruct_1.slice(2, $destruct_1.length);
+//! ($destruct_0);
+//! ```
+//!
+//! An array pattern takes the elements that it binds from any iterable with
+//! `__JNKS.iter_take(e, n)`, which produces an array of the first `n`
+//! elements, or of all of them when the pattern has a rest element. It
+//! produces arrays as they are.
+//!
+//! The parser turns destructuring declarations and parameters into
+//! `Expr::Destructure`, so this is the only form that we need to handle.
+//!
+//! This runs after `desugar_logical`, so that a destructuring assignment in
+//! an operand is only inserted into the block that evaluates the operand.
+//! `desugar_logical` leaves patterns alone, since their defaults and lvalues
+//! are only evaluated as they are bound, so we desugar the logical
+//! expressions in the statements that we produce here.
+
+use super::constructors::*;
+use super::syntax::*;
+use super::*;
+use crate::pos::Pos;
+
+pub fn desugar_destructuring(program: &mut Stmt, ng: &mut NameGen) {
+    let mut v = DesugarDestructuring { ng };
+    program.walk(&mut v);
+}
+
+struct DesugarDestructuring<'a> {
+    ng: &'a mut NameGen,
+}
+
+impl Visitor for DesugarDestructuring<'_> {
+    fn exit_expr(&mut self, expr: &mut Expr, loc: &Loc) {
+        if let Expr::Destructure(pat, e, s) = expr {
+            let cxt = loc
+                .enclosing_block()
+                .expect("destructuring outside of a block");
+            let mut b = Bind {
+                ng: self.ng,
+                stmts: vec![],
+                s: s.clone(),
+            };
+            let value = b.name(e.take());
+            b.bind(*pat.clone(), id_(value.clone(), s.clone()));
+            let mut block = Stmt::Block(b.stmts, s.clone());
+            desugar_logical::desugar_logical(&mut block, self.ng);
+            if let Stmt::Block(stmts, _) = block {
+                for stmt in stmts {
+                    cxt.insert(cxt.index, stmt);
+                }
+            }
+            *expr = id_(value, s.clone());
+        }
+    }
+}
+
+/// Produces the assignments for one destructuring assignment, which are
+/// inserted before the statement that contains it
+struct Bind<'a> {
+    ng: &'a mut NameGen,
+    stmts: Vec<Stmt>,
+    s: Pos,
+}
+
+impl Bind<'_> {
+    fn insert(&mut self, stmt: Stmt) {
+        self.stmts.push(stmt);
+    }
+
+    /// Store `e` in a fresh variable
+    fn name(&mut self, e: Expr) -> Id {
+        let x = self.ng.fresh("destruct");
+        self.insert(vardecl1_(x.clone(), e, self.s.clone()));
+        x
+    }
+
+    /// Assign `value` to `pat`. `value` is evaluated exactly once
+    fn bind(&mut self, pat: Pattern, value: Expr) {
+        let s = self.s.clone();
+        match pat {
+            Pattern::LValue(lv) => self.insert(expr_(assign_(lv, value, s.clone()), s)),
+            Pattern::Default(pat, default) => {
+                // the default is only evaluated when the value is undefined
+                let x = self.name(value);
+                self.insert(if_(
+                    binary_(
+                        BinOp::BinaryOp(BinaryOp::StrictEqual),
+                        id_(x.clone(), s.clone()),
+                        UNDEFINED_,
+                        s.clone(),
+                    ),
+                    Stmt::Block(
+                        vec![expr_(assign_(x.clone(), *default, s.clone()), s.clone())],
+                        s.clone(),
+                    ),
+                    Stmt::Block(vec![], s.clone()),
+                    s.clone(),
+                ));
+                self.bind(*pat, id_(x, s));
+            }
+            Pattern::Array(elems, rest) => {
+                let len = elems.len() as i32;
+                let take = call_(
+                    dot_(id_("__JNKS", s.clone()), "iter_take", s.clone()),
+                    vec![
+                        value,
+                        int_(if rest.is_some() { -1 } else { len }, s.clone()),
+                    ],
+                    s.clone(),
+                );
+                let x = id_(self.name(take), s.clone());
+                for (i, pat) in elems.into_iter().enumerate() {
+                    if let Some(pat) = pat {
+                        self.bind(
+                            pat,
+                            bracket_(x.clone(), int_(i as i32, s.clone()), s.clone()),
+                        );
+                    }
+                }
+                if let Some(rest) = rest {
+                    // ...r => r = x.slice(n, x.length)
+                    let slice = call_(
+                        dot_(x.clone(), "slice", s.clone()),
+                        vec![int_(len, s.clone()), dot_(x, "length", s.clone())],
+                        s,
+                    );
+                    self.bind(*rest, slice);
+                }
+            }
+            Pattern::Object(props, rest) => {
+                let x = self.atom(value);
+                let mut keys = vec![];
+                for (key, pat) in props {
+                    let field = match &key {
+                        Key::Str(k) => dot_(x.clone(), k.as_str(), s.clone()),
+                        Key::Int(n) => bracket_(x.clone(), int_(*n, s.clone()), s.clone()),
+                    };
+                    keys.push(match key {
+                        Key::Str(k) => str_(k, s.clone()),
+                        Key::Int(n) => str_(n.to_string(), s.clone()),
+                    });
+                    self.bind(pat, field);
+                }
+                if let Some(rest) = rest {
+                    // ...r => r = a copy of x without the keys above
+                    let copy = call_(
+                        dot_(id_("__JNKS", s.clone()), "object_rest", s.clone()),
+                        vec![x, Expr::Array(keys, s.clone())],
+                        s,
+                    );
+                    self.bind(*rest, copy);
+                }
+            }
+        }
+    }
+
+    /// An expression that produces `value` and can be copied freely
+    fn atom(&mut self, value: Expr) -> Expr {
+        match value {
+            Expr::Id(..) => value,
+            _ => id_(self.name(value), self.s.clone()),
+        }
+    }
+}
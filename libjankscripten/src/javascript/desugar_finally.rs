@@ -0,0 +1,234 @@
+//! desugar `try .. finally` into `try .. catch`
+//!
+//! The finally block runs once, after the body completes in any way. We
+//! record how the body completed, run the finally block, and then complete
+//! in the same way. For example,
+//!
+//! ```javascript
+//! l: while (true) {
+//!     try {
+//!         if (x) { return 1; }
+//!         break l;
+//!     } finally {
+//!         cleanup();
+//!     }
+//! }
+//! ```
+//!
+//! becomes
+//!
+//! ```javascript
+//! l: while (true) {
+//!     var $completion_0 = 0;
+//!     var $value_1 = undefined;
+//!     $finally_2: {
+//!         try {
+//!             if (x) { $completion_0 = 2; $value_1 = 1; break $finally_2; }
+//!             $completion_0 = 3; break $finally_2;
+//!         } catch ($exn_3) {
+//!             $completion_0 = 1; $value_1 = $exn_3;
+//!         }
+//!     }
+//!     cleanup();
+//!     if ($completion_0 === 1) { throw $value_1; }
+//!     if ($completion_0 === 2) { return $value_1; }
+//!     if ($completion_0 === 3) { break l; }
+//! }
+//! ```
+//!
+//! Only breaks to labels outside of the body complete it, so this must come
+//! after `desugar_loops` makes every break explicit.
+
+use super::constructors::*;
+use super::*;
+use crate::pos::Pos;
+
+/// The completion of a body that ran to its end
+const NORMAL: i32 = 0;
+/// The completion of a body that threw
+const THROW: i32 = 1;
+/// The completion of a body that returned
+const RETURN: i32 = 2;
+/// The completion of a body that broke to the first label outside of it.
+/// Each other label gets the next number.
+const BREAK: i32 = 3;
+
+pub fn desugar_finally(stmt: &mut Stmt, ng: &mut NameGen) {
+    stmt.walk(&mut DesugarFinally(ng));
+}
+
+struct DesugarFinally<'a>(&'a mut NameGen);
+
+impl Visitor for DesugarFinally<'_> {
+    fn exit_stmt(&mut self, stmt: &mut Stmt, _loc: &Loc) {
+        if let Stmt::Finally(body, finally_block, s) = stmt {
+            let completion = self.0.fresh("completion");
+            let value = self.0.fresh("value");
+            let finally_label = self.0.fresh("finally");
+            let exn = self.0.fresh("exn");
+            let mut exits = Exits {
+                completion: completion.clone(),
+                value: value.clone(),
+                finally_label: finally_label.clone(),
+                fn_depth: 0,
+                inner_labels: vec![],
+                breaks: vec![],
+                returns: false,
+            };
+            body.walk(&mut exits);
+
+            let set_completion = |n, s: &Pos| {
+                expr_(
+                    assign_(completion.clone(), int_(n, s.clone()), s.clone()),
+                    s.clone(),
+                )
+            };
+            let completed = |n, s: &Pos| {
+                binary_(
+                    BinOp::BinaryOp(BinaryOp::StrictEqual),
+                    id_(completion.clone(), s.clone()),
+                    int_(n, s.clone()),
+                    s.clone(),
+                )
+            };
+            let handler = Stmt::Block(
+                vec![
+                    set_completion(THROW, s),
+                    expr_(
+                        assign_(value.clone(), id_(exn.clone(), s.clone()), s.clone()),
+                        s.clone(),
+                    ),
+                ],
+                s.clone(),
+            );
+            let mut stmts = vec![
+                vardecl1_(completion.clone(), int_(NORMAL, s.clone()), s.clone()),
+                vardecl1_(value.clone(), UNDEFINED_, s.clone()),
+                label_(
+                    finally_label,
+                    Stmt::Block(
+                        vec![catch_(body.take(), exn, handler, s.clone())],
+                        s.clone(),
+                    ),
+                    s.clone(),
+                ),
+                finally_block.take(),
+                if_(
+                    completed(THROW, s),
+                    Stmt::Block(
+                        vec![throw_(id_(value.clone(), s.clone()), s.clone())],
+                        s.clone(),
+                    ),
+                    Stmt::Empty,
+                    s.clone(),
+                ),
+            ];
+            if exits.returns {
+                stmts.push(if_(
+                    completed(RETURN, s),
+                    Stmt::Block(
+                        vec![return_(id_(value.clone(), s.clone()), s.clone())],
+                        s.clone(),
+                    ),
+                    Stmt::Empty,
+                    s.clone(),
+                ));
+            }
+            for (i, label) in exits.breaks.into_iter().enumerate() {
+                stmts.push(if_(
+                    completed(BREAK + i as i32, s),
+                    Stmt::Block(vec![break_(Some(label), s.clone())], s.clone()),
+                    Stmt::Empty,
+                    s.clone(),
+                ));
+            }
+            *stmt = Stmt::Block(stmts, s.clone());
+        }
+    }
+}
+
+/// Replaces the returns and breaks that leave the body of a `try .. finally`
+/// with breaks to the finally block, recording the completion.
+struct Exits {
+    completion: Id,
+    value: Id,
+    finally_label: Id,
+    /// The number of functions that we are in within the body. Their returns
+    /// and breaks do not leave the body
+    fn_depth: usize,
+    /// Labels within the body, which breaks do not leave
+    inner_labels: Vec<Id>,
+    /// Labels outside the body that are broken to, in the order of their
+    /// completion numbers
+    breaks: Vec<Id>,
+    returns: bool,
+}
+
+impl Exits {
+    /// The statements that record `completion` and skip to the finally block
+    fn complete(&self, completion: i32, value: Option<Expr>, s: &Pos) -> Stmt {
+        let mut stmts = vec![expr_(
+            assign_(
+                self.completion.clone(),
+                int_(completion, s.clone()),
+                s.clone(),
+            ),
+            s.clone(),
+        )];
+        if let Some(value) = value {
+            stmts.push(expr_(
+                assign_(self.value.clone(), value, s.clone()),
+                s.clone(),
+            ));
+        }
+        stmts.push(break_(Some(self.finally_label.clone()), s.clone()));
+        Stmt::Block(stmts, s.clone())
+    }
+}
+
+impl Visitor for Exits {
+    fn enter_stmt(&mut self, stmt: &mut Stmt, _loc: &Loc) {
+        match stmt {
+            Stmt::Func(..) => self.fn_depth += 1,
+            Stmt::Label(x, ..) => self.inner_labels.push(x.clone()),
+            _ => (),
+        }
+    }
+
+    fn exit_stmt(&mut self, stmt: &mut Stmt, _loc: &Loc) {
+        match stmt {
+            Stmt::Func(..) => self.fn_depth -= 1,
+            Stmt::Label(..) => {
+                self.inner_labels.pop();
+            }
+            _ if self.fn_depth > 0 => (),
+            Stmt::Return(e, s) => {
+                self.returns = true;
+                *stmt = self.complete(RETURN, Some(e.take()), s);
+            }
+            Stmt::Break(Some(x), s) if !self.inner_labels.contains(x) => {
+                let index = match self.breaks.iter().position(|y| y == x) {
+                    Some(index) => index,
+                    None => {
+                        self.breaks.push(x.clone());
+                        self.breaks.len() - 1
+                    }
+                };
+                *stmt = self.complete(BREAK + index as i32, None, s);
+            }
+            _ => (),
+        }
+    }
+
+    fn enter_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        if let Expr::Func(..) | Expr::Arrow(..) = expr {
+            self.fn_depth += 1;
+        }
+    }
+
+    fn exit_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        if let Expr::Func(..) | Expr::Arrow(..) = expr {
+            self.fn_depth -= 1;
+        }
+    }
+}
@@ -1,17 +1,20 @@
+use super::constructors::*;
 use super::syntax::*;
 use super::walk::*;
 use crate::pos::Pos;
 
 pub struct DesugarFunctionStmts {}
 
+type FunStmt = (Id, Vec<Id>, bool, Box<Stmt>, Pos);
+
 /// If `stmt` is a `Stmt::Func`, replace it with empty and return the functino's name, arguments,
 /// and body.
-fn take_fun_stmt(stmt: &mut Stmt) -> Option<(Id, Vec<Id>, Box<Stmt>, Pos)> {
+fn take_fun_stmt(stmt: &mut Stmt) -> Option<FunStmt> {
     if let Stmt::Func(..) = stmt {
         let fun_stmt = stmt.take();
         match fun_stmt {
-            Stmt::Func(name, args, body, s) => {
-                return Some((name, args, body, s));
+            Stmt::Func(name, args, rest, body, s) => {
+                return Some((name, args, rest, body, s));
             }
             _ => unreachable!(),
         }
@@ -19,14 +22,39 @@ fn take_fun_stmt(stmt: &mut Stmt) -> Option<(Id, Vec<Id>, Box<Stmt>, Pos)> {
     return None;
 }
 
+/// Whether `body` is the body of a generator or async function, which the
+/// parser makes return the iterator or the promise (see `Expr::Generator`)
+fn is_generator_or_async(body: &Stmt) -> bool {
+    match body {
+        Stmt::Block(stmts, _) => match stmts.last() {
+            Some(Stmt::Return(e, _)) => matches!(**e, Expr::Generator(..) | Expr::Async(..)),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
 impl Visitor for DesugarFunctionStmts {
     fn exit_stmt(&mut self, stmt: &mut Stmt, loc: &Loc) {
-        if let Some((name, args, body, s)) = take_fun_stmt(stmt) {
-            let named = Box::new(Expr::Func(None, args, body, s.clone()));
+        if let Some((name, args, rest, body, s)) = take_fun_stmt(stmt) {
+            let constructor = !is_generator_or_async(&body);
+            let mut named = Expr::Func(None, args, rest, body, s.clone());
+            // like an arrow, a generator or async function is not a
+            // constructor
+            if !constructor {
+                let non_constructor = dot_(id_("__JNKS", s.clone()), "non_constructor", s.clone());
+                named = call_(non_constructor, vec![named], s.clone());
+            }
+            let named = Box::new(named);
             let block_cxt = loc.body_of_enclosing_function_or_program();
             // Insert `var name = function(args ...) { body ... }` at the top of the block that
             // defines the innermost enclosing function.
-            block_cxt.insert(0, Stmt::VarDecl(vec![VarDecl { name, named }], s.clone()));
+            let decl = VarDecl {
+                name,
+                named,
+                kind: VarKind::Var,
+            };
+            block_cxt.insert(0, Stmt::VarDecl(vec![decl], s.clone()));
         }
     }
 }
@@ -0,0 +1,750 @@
+//! desugar generator functions into state machines
+//!
+//! The parser turns the body of a generator function into a function that
+//! resumes it (see `Expr::Generator`). We desugar in two passes:
+//!
+//! 1. `desugar_yield` gives the resume function two parameters: how it was
+//!    resumed (`next`, `throw` or `return`), and the value that it was
+//!    resumed with. It also makes each `yield` a statement of its own, which
+//!    is followed by the statements that act on how the body was resumed:
+//!
+//!    ```javascript
+//!    var x = 1 + (yield y);
+//!    ```
+//!
+//!    becomes
+//!
+//!    ```javascript
+//!    yield y;
+//!    if ($mode_0 === 1) { throw $sent_1; }
+//!    if ($mode_0 === 2) { return $sent_1; }
+//!    var $received_2 = $sent_1;
+//!    var x = 1 + $received_2;
+//!    ```
+//!
+//!    So, the operands to the left of a `yield` are evaluated after it. A
+//!    `return` before `desugar_finally` runs the enclosing finally blocks,
+//!    which is what `iterator.return(v)` should do.
+//!
+//! 2. `desugar_generators` explodes the resume function into cases, which
+//!    run in turn until one of them yields. The locals of the body must
+//!    survive between resumptions, so they are lifted into the generator
+//!    function, where they are captured by the resume function like any other
+//!    free variable. For example,
+//!
+//!    ```javascript
+//!    function* g(n) {
+//!        while (true) { if (n) { yield n; } else { return; } }
+//!    }
+//!    ```
+//!
+//!    roughly becomes
+//!
+//!    ```javascript
+//!    function g(n) {
+//!        var $state_0 = 0;
+//!        var $resume_1 = ($mode, $sent) => {
+//!            // ... complete immediately when done, or when first resumed
+//!            // with `throw` or `return` ...
+//!            $loop_2: while (true) {
+//!                try {
+//!                    $cases_3: {
+//!                        if ($state_0 === 0) { $state_0 = 1; }
+//!                        if ($state_0 === 1) {
+//!                            if (!n) { $state_0 = 3; break $cases_3; }
+//!                            $state_0 = 4; return { value: n, done: false };
+//!                        }
+//!                        if ($state_0 === 4) { $state_0 = 1; break $cases_3; }
+//!                        // ...
+//!                    }
+//!                } catch ($exn_4) {
+//!                    $state_0 = -1; throw $exn_4;
+//!                }
+//!            }
+//!        };
+//!        var $gen_5 = {
+//!            next: function (v) { return $resume_1(0, v); },
+//!            return: function (v) { return $resume_1(2, v); },
+//!            throw: function (e) { return $resume_1(1, e); },
+//!            [Symbol.iterator]: function () { return this; },
+//!        };
+//!        return $gen_5;
+//!    }
+//!    ```
+//!
+//!    A case that falls through to the next case sets the state without
+//!    breaking. A `try` whose body yields is replaced by a handler around
+//!    all the cases, which uses the state to pick the catch block to jump to.
+//!    Statements that do not yield are left as they are, except for returns
+//!    and for breaks to labels that were exploded.
+//!
+//! `yield* e` yields each element of `e` with the iteration protocol. It does
+//! not forward `throw` and `return` to the inner iterator, and it produces
+//! `undefined` instead of the value that the inner iterator returns.
+//!
+//! The body of an async function (see `Expr::Async`) is desugared in the same
+//! way, where `await e` is `yield e`. Instead of an iterator, the async
+//! function produces `__JNKS.async_run($resume)`, the promise that the body
+//! settles. The runtime resumes the body with `next` when the promise that it
+//! awaited is fulfilled, and with `throw` when that promise is rejected.
+
+use super::constructors::*;
+use super::*;
+use crate::pos::Pos;
+
+/// The resume function was applied by `next`
+const NEXT: i32 = 0;
+/// The resume function was applied by `throw`
+const THROW: i32 = 1;
+/// The resume function was applied by `return`
+const RETURN: i32 = 2;
+/// The state of a generator that has completed
+const DONE: i32 = -1;
+
+pub fn desugar_yield(stmt: &mut Stmt, ng: &mut NameGen) {
+    stmt.walk(&mut DesugarYield {
+        ng,
+        resumes: vec![],
+        received: None,
+    });
+}
+
+pub fn desugar_generators(stmt: &mut Stmt, ng: &mut NameGen) {
+    stmt.walk(&mut DesugarGenerators(ng));
+}
+
+fn strict_equal_(a: Expr, b: Expr, s: &Pos) -> Expr {
+    binary_(BinOp::BinaryOp(BinaryOp::StrictEqual), a, b, s.clone())
+}
+
+struct DesugarYield<'a> {
+    ng: &'a mut NameGen,
+    /// The parameters of the enclosing resume functions, innermost last
+    resumes: Vec<(Id, Id)>,
+    /// The variable that received the value of the last `yield`, which is
+    /// left behind as a statement of its own by `yield e;`
+    received: Option<Id>,
+}
+
+impl DesugarYield<'_> {
+    /// The statements that yield `value` and put the value that the body is
+    /// resumed with in `received`
+    fn suspend(&self, value: Expr, received: &Id, s: &Pos) -> Vec<Stmt> {
+        let (mode, sent) = self.resumes.last().expect("yield outside of a generator");
+        let resumed_by = |m| strict_equal_(id_(mode.clone(), s.clone()), int_(m, s.clone()), s);
+        vec![
+            expr_(Expr::Yield(Box::new(value), false, s.clone()), s.clone()),
+            if_(
+                resumed_by(THROW),
+                Stmt::Block(
+                    vec![throw_(id_(sent.clone(), s.clone()), s.clone())],
+                    s.clone(),
+                ),
+                Stmt::Empty,
+                s.clone(),
+            ),
+            if_(
+                resumed_by(RETURN),
+                Stmt::Block(
+                    vec![return_(id_(sent.clone(), s.clone()), s.clone())],
+                    s.clone(),
+                ),
+                Stmt::Empty,
+                s.clone(),
+            ),
+            vardecl1_(received.clone(), id_(sent.clone(), s.clone()), s.clone()),
+        ]
+    }
+
+    /// The statements that yield each element of `iterable`
+    fn delegate(&mut self, iterable: Expr, received: &Id, s: &Pos) -> Vec<Stmt> {
+        let iter = self.ng.fresh("iter");
+        let elem = self.ng.fresh("elem");
+        let label = self.ng.fresh("delegate");
+        let jnks = |f: &str, arg: Expr| {
+            call_(
+                dot_(id_("__JNKS", s.clone()), f, s.clone()),
+                vec![arg],
+                s.clone(),
+            )
+        };
+        let mut body = vec![
+            vardecl1_(
+                elem.clone(),
+                jnks("iter_next", id_(iter.clone(), s.clone())),
+                s.clone(),
+            ),
+            if_(
+                jnks("iter_done", id_(elem.clone(), s.clone())),
+                Stmt::Block(vec![break_(Some(label.clone()), s.clone())], s.clone()),
+                Stmt::Empty,
+                s.clone(),
+            ),
+        ];
+        body.extend(self.suspend(id_(elem, s.clone()), received, s));
+        vec![
+            vardecl1_(iter, jnks("iter", iterable), s.clone()),
+            label_(
+                label,
+                while_(TRUE_, Stmt::Block(body, s.clone()), s.clone()),
+                s.clone(),
+            ),
+        ]
+    }
+}
+
+impl Visitor for DesugarYield<'_> {
+    fn enter_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        if let Expr::Generator(resume, _) | Expr::Async(resume, _) = expr {
+            match &mut **resume {
+                Expr::Arrow(params, _, _, _) => {
+                    let mode = self.ng.fresh("mode");
+                    let sent = self.ng.fresh("sent");
+                    *params = vec![mode.clone(), sent.clone()];
+                    self.resumes.push((mode, sent));
+                }
+                _ => panic!("the body of a generator is not a function"),
+            }
+        }
+    }
+
+    fn exit_expr(&mut self, expr: &mut Expr, loc: &Loc) {
+        match expr {
+            Expr::Generator(..) | Expr::Async(..) => {
+                self.resumes.pop();
+            }
+            Expr::Yield(_, _, s) | Expr::Await(_, s) => {
+                let s = s.clone();
+                let (value, delegate) = match expr {
+                    Expr::Yield(value, delegate, _) => (value.take(), *delegate),
+                    Expr::Await(value, _) => (value.take(), false),
+                    _ => unreachable!(),
+                };
+                let received = self.ng.fresh("received");
+                let stmts = if delegate {
+                    self.delegate(value, &received, &s)
+                } else {
+                    self.suspend(value, &received, &s)
+                };
+                let ctx = loc.enclosing_block().expect("yield outside of a block");
+                for stmt in stmts {
+                    ctx.insert(ctx.index, stmt);
+                }
+                *expr = if delegate {
+                    UNDEFINED_
+                } else {
+                    id_(received.clone(), s)
+                };
+                self.received = Some(received);
+            }
+            _ => (),
+        }
+    }
+
+    fn exit_stmt(&mut self, stmt: &mut Stmt, _loc: &Loc) {
+        if let Stmt::Expr(e, _) = stmt {
+            match (&**e, &self.received) {
+                (Expr::Id(x, _), Some(received)) if x == received => *stmt = Stmt::Empty,
+                (Expr::Lit(Lit::Undefined, _), _) => *stmt = Stmt::Empty,
+                _ => (),
+            }
+        }
+    }
+}
+
+struct DesugarGenerators<'a>(&'a mut NameGen);
+
+impl Visitor for DesugarGenerators<'_> {
+    fn exit_expr(&mut self, expr: &mut Expr, loc: &Loc) {
+        if let Expr::Generator(resume, s) | Expr::Async(resume, s) = expr {
+            let s = s.clone();
+            let resume_fn = resume.take();
+            let is_async = matches!(expr, Expr::Async(..));
+            let (mode, sent, body) = match resume_fn {
+                Expr::Arrow(mut params, _, body, _) if params.len() == 2 => {
+                    let sent = params.pop().unwrap();
+                    let mode = params.pop().unwrap();
+                    (mode, sent, *body)
+                }
+                _ => panic!("desugar_yield did not run on the body of a generator"),
+            };
+            let state = self.0.fresh("state");
+            let mut machine = StateMachine {
+                cases_label: self.0.fresh("cases"),
+                ng: self.0,
+                state: state.clone(),
+                cases: vec![],
+                current: (0, vec![]),
+                next_case: 1,
+                labels: vec![],
+                tries: vec![],
+                handlers: vec![],
+                hoisted: vec![],
+                s: s.clone(),
+            };
+            machine.explode(body);
+            let resume_body = machine.finish(&mode, &sent);
+
+            let fn_body = loc.body_of_enclosing_function_or_program();
+            for x in machine.hoisted {
+                fn_body.insert(0, vardecl1_(x, UNDEFINED_, s.clone()));
+            }
+            fn_body.insert(0, vardecl1_(state, int_(0, s.clone()), s.clone()));
+
+            let resume = self.0.fresh("resume");
+            let ctx = loc.enclosing_block().expect("generator outside of a block");
+            ctx.insert(
+                ctx.index,
+                vardecl1_(
+                    resume.clone(),
+                    Expr::Arrow(vec![mode, sent], false, Box::new(resume_body), s.clone()),
+                    s.clone(),
+                ),
+            );
+            if is_async {
+                // the promise settles when the body completes
+                *expr = call_(
+                    dot_(id_("__JNKS", s.clone()), "async_run", s.clone()),
+                    vec![id_(resume, s.clone())],
+                    s,
+                );
+                return;
+            }
+
+            let gen = self.0.fresh("gen");
+            let method = |params: Vec<Id>, m: i32, arg: Expr| {
+                lambda(
+                    params,
+                    Stmt::Block(
+                        vec![return_(
+                            call_(
+                                id_(resume.clone(), s.clone()),
+                                vec![int_(m, s.clone()), arg],
+                                s.clone(),
+                            ),
+                            s.clone(),
+                        )],
+                        s.clone(),
+                    ),
+                    s.clone(),
+                )
+            };
+            let sent = self.0.fresh("v");
+            let v = self.0.fresh("v");
+            let e = self.0.fresh("e");
+            let iterator = Expr::Object(
+                vec![
+                    (
+                        Key::Str("next".into()),
+                        method(vec![sent.clone()], NEXT, id_(sent, s.clone())),
+                    ),
+                    (
+                        Key::Str("return".into()),
+                        method(vec![v.clone()], RETURN, id_(v, s.clone())),
+                    ),
+                    (
+                        Key::Str("throw".into()),
+                        method(vec![e.clone()], THROW, id_(e, s.clone())),
+                    ),
+                    (
+                        Key::Str(ITERATOR_KEY.into()),
+                        lambda(
+                            Vec::<Id>::new(),
+                            Stmt::Block(vec![return_(Expr::This, s.clone())], s.clone()),
+                            s.clone(),
+                        ),
+                    ),
+                ],
+                s.clone(),
+            );
+            ctx.insert(ctx.index, vardecl1_(gen.clone(), iterator, s.clone()));
+            *expr = id_(gen, s);
+        }
+    }
+}
+
+/// An exploded `try` block
+struct Try {
+    /// The case that starts the catch block
+    handler: i32,
+    /// The variable that the catch block binds
+    exn: Id,
+    /// The cases of the body, excluding the cases of nested `try` blocks
+    cases: Vec<i32>,
+}
+
+/// The cases that a generator body is exploded into. Each case is numbered
+/// by the state that runs it.
+struct StateMachine<'a> {
+    ng: &'a mut NameGen,
+    state: Id,
+    /// The label of the block of cases, which jumps break out of
+    cases_label: Id,
+    /// The completed cases, in the order that they run when they fall through
+    cases: Vec<(i32, Vec<Stmt>)>,
+    /// The case that statements are being added to
+    current: (i32, Vec<Stmt>),
+    next_case: i32,
+    /// The exploded labels that enclose the current case, with the cases
+    /// that follow their statements
+    labels: Vec<(Id, i32)>,
+    /// The exploded `try` blocks whose bodies enclose the current case,
+    /// innermost last
+    tries: Vec<Try>,
+    /// The exploded `try` blocks whose bodies are complete
+    handlers: Vec<Try>,
+    /// The variables to declare in the generator function
+    hoisted: Vec<Id>,
+    s: Pos,
+}
+
+impl StateMachine<'_> {
+    fn new_case(&mut self) -> i32 {
+        let case = self.next_case;
+        self.next_case += 1;
+        case
+    }
+
+    /// Completes the current case, falling through to `case`
+    fn mark(&mut self, case: i32) {
+        let stmt = set_state_(&self.state, case, &self.s);
+        self.current.1.push(stmt);
+        let current = std::mem::replace(&mut self.current, (case, vec![]));
+        self.cases.push(current);
+        if let Some(t) = self.tries.last_mut() {
+            t.cases.push(case);
+        }
+    }
+
+    fn jump(&mut self, case: i32) {
+        let stmt = jump_(&self.state, &self.cases_label, case, &self.s);
+        self.current.1.push(stmt);
+    }
+
+    fn explode(&mut self, stmt: Stmt) {
+        let s = self.s.clone();
+        match stmt {
+            Stmt::VarDecl(decls, _) => {
+                for decl in decls {
+                    self.hoisted.push(decl.name.clone());
+                    if !matches!(*decl.named, Expr::Lit(Lit::Undefined, _)) {
+                        let stmt = expr_(assign_(decl.name, *decl.named, s.clone()), s.clone());
+                        self.verbatim(stmt);
+                    }
+                }
+            }
+            stmt if !yields(&stmt) => self.verbatim(stmt),
+            Stmt::Block(stmts, _) => {
+                for stmt in stmts {
+                    self.explode(stmt);
+                }
+            }
+            Stmt::Expr(e, _) => match *e {
+                Expr::Yield(value, false, _) => {
+                    let next = self.new_case();
+                    let stmts = vec![
+                        set_state_(&self.state, next, &s),
+                        return_(result_(*value, false, &s), s.clone()),
+                    ];
+                    self.current.1.push(Stmt::Block(stmts, s.clone()));
+                    self.mark(next);
+                }
+                _ => panic!("yield within an expression (desugar_yield did not run)"),
+            },
+            Stmt::If(c, t, f, _) => {
+                let else_case = self.new_case();
+                let end = self.new_case();
+                let jump = jump_(&self.state, &self.cases_label, else_case, &s);
+                self.current.1.push(if_(
+                    not_(*c, s.clone()),
+                    Stmt::Block(vec![jump], s.clone()),
+                    Stmt::Empty,
+                    s.clone(),
+                ));
+                self.explode(*t);
+                self.jump(end);
+                self.mark(else_case);
+                self.explode(*f);
+                self.mark(end);
+            }
+            Stmt::While(c, body, _) => {
+                let top = self.new_case();
+                let end = self.new_case();
+                self.mark(top);
+                if *c != TRUE_ {
+                    let jump = jump_(&self.state, &self.cases_label, end, &s);
+                    self.current.1.push(if_(
+                        not_(*c, s.clone()),
+                        Stmt::Block(vec![jump], s.clone()),
+                        Stmt::Empty,
+                        s.clone(),
+                    ));
+                }
+                self.explode(*body);
+                self.jump(top);
+                self.mark(end);
+            }
+            Stmt::Label(x, body, _) => {
+                let end = self.new_case();
+                self.labels.push((x, end));
+                self.explode(*body);
+                self.labels.pop();
+                self.mark(end);
+            }
+            Stmt::Catch(body, exn, handler, _) => {
+                // the catch block runs in a later case, so its variable must
+                // be hoisted like any other. block_scoping has made its name
+                // unique
+                self.hoisted.push(exn.clone());
+                let start = self.new_case();
+                let handler_case = self.new_case();
+                let end = self.new_case();
+                self.tries.push(Try {
+                    handler: handler_case,
+                    exn,
+                    cases: vec![],
+                });
+                self.mark(start);
+                self.explode(*body);
+                self.jump(end);
+                let t = self.tries.pop().unwrap();
+                self.handlers.push(t);
+                self.mark(handler_case);
+                self.explode(*handler);
+                self.mark(end);
+            }
+            _ => panic!("unexpected statement in generator"),
+        }
+    }
+
+    /// Adds a statement that does not yield to the current case
+    fn verbatim(&mut self, mut stmt: Stmt) {
+        stmt.walk(&mut Exits {
+            state: &self.state,
+            cases_label: &self.cases_label,
+            labels: &self.labels,
+            fn_depth: 0,
+            inner_labels: vec![],
+        });
+        self.current.1.push(stmt);
+    }
+
+    /// The body of the resume function, after the body of the generator has
+    /// been exploded
+    fn finish(&mut self, mode: &Id, sent: &Id) -> Stmt {
+        let s = self.s.clone();
+        let state_id = self.state.clone();
+        let state = || id_(state_id.clone(), s.clone());
+        let mode_is = |m| strict_equal_(id_(mode.clone(), s.clone()), int_(m, s.clone()), &s);
+        let block = |stmts| Stmt::Block(stmts, s.clone());
+
+        let last = block(vec![
+            set_state_(&self.state, DONE, &s),
+            return_(result_(UNDEFINED_, true, &s), s.clone()),
+        ]);
+        self.current.1.push(last);
+        let last = std::mem::replace(&mut self.current, (DONE, vec![]));
+        self.cases.push(last);
+        let cases = self
+            .cases
+            .drain(..)
+            .map(|(case, stmts)| {
+                if_(
+                    strict_equal_(state(), int_(case, s.clone()), &s),
+                    block(stmts),
+                    Stmt::Empty,
+                    s.clone(),
+                )
+            })
+            .collect();
+
+        // an exception in the body of an exploded `try` jumps to its catch
+        // block, and any other exception completes the generator
+        let exn = self.ng.fresh("exn");
+        let mut dispatch = block(vec![
+            set_state_(&self.state, DONE, &s),
+            throw_(id_(exn.clone(), s.clone()), s.clone()),
+        ]);
+        for t in self.handlers.iter().rev() {
+            for case in t.cases.iter().rev() {
+                dispatch = block(vec![if_(
+                    strict_equal_(state(), int_(*case, s.clone()), &s),
+                    block(vec![
+                        expr_(
+                            assign_(t.exn.clone(), id_(exn.clone(), s.clone()), s.clone()),
+                            s.clone(),
+                        ),
+                        set_state_(&self.state, t.handler, &s),
+                    ]),
+                    dispatch,
+                    s.clone(),
+                )]);
+            }
+        }
+
+        let loop_label = self.ng.fresh("loop");
+        block(vec![
+            // throw and return before the body starts complete it
+            if_(
+                strict_equal_(state(), int_(0, s.clone()), &s),
+                block(vec![if_(
+                    not_(mode_is(NEXT), s.clone()),
+                    block(vec![set_state_(&self.state, DONE, &s)]),
+                    Stmt::Empty,
+                    s.clone(),
+                )]),
+                Stmt::Empty,
+                s.clone(),
+            ),
+            if_(
+                strict_equal_(state(), int_(DONE, s.clone()), &s),
+                block(vec![
+                    if_(
+                        mode_is(THROW),
+                        block(vec![throw_(id_(sent.clone(), s.clone()), s.clone())]),
+                        Stmt::Empty,
+                        s.clone(),
+                    ),
+                    if_(
+                        mode_is(RETURN),
+                        block(vec![return_(
+                            result_(id_(sent.clone(), s.clone()), true, &s),
+                            s.clone(),
+                        )]),
+                        Stmt::Empty,
+                        s.clone(),
+                    ),
+                    return_(result_(UNDEFINED_, true, &s), s.clone()),
+                ]),
+                Stmt::Empty,
+                s.clone(),
+            ),
+            label_(
+                loop_label,
+                while_(
+                    TRUE_,
+                    block(vec![catch_(
+                        block(vec![label_(
+                            self.cases_label.clone(),
+                            block(cases),
+                            s.clone(),
+                        )]),
+                        exn,
+                        dispatch,
+                        s.clone(),
+                    )]),
+                    s.clone(),
+                ),
+                s.clone(),
+            ),
+        ])
+    }
+}
+
+/// `$state = case;`
+fn set_state_(state: &Id, case: i32, s: &Pos) -> Stmt {
+    expr_(
+        assign_(state.clone(), int_(case, s.clone()), s.clone()),
+        s.clone(),
+    )
+}
+
+/// `{ $state = case; break $cases; }`
+fn jump_(state: &Id, cases_label: &Id, case: i32, s: &Pos) -> Stmt {
+    Stmt::Block(
+        vec![
+            set_state_(state, case, s),
+            break_(Some(cases_label.clone()), s.clone()),
+        ],
+        s.clone(),
+    )
+}
+
+/// `{ value: value, done: done }`
+fn result_(value: Expr, done: bool, s: &Pos) -> Expr {
+    Expr::Object(
+        vec![
+            (Key::Str("value".into()), value),
+            (
+                Key::Str("done".into()),
+                Expr::Lit(Lit::Bool(done), s.clone()),
+            ),
+        ],
+        s.clone(),
+    )
+}
+
+/// Does the statement yield, not counting nested functions?
+fn yields(stmt: &Stmt) -> bool {
+    struct Yields(bool);
+    impl Visitor for Yields {
+        fn enter_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+            if let Expr::Yield(..) = expr {
+                self.0 = true;
+            }
+        }
+    }
+    // nested generators have already been desugared, and no other function
+    // can yield to this one
+    let mut v = Yields(false);
+    stmt.clone().walk(&mut v);
+    v.0
+}
+
+/// Rewrites returns, and breaks to exploded labels, in a statement that does
+/// not yield
+struct Exits<'a> {
+    state: &'a Id,
+    cases_label: &'a Id,
+    labels: &'a [(Id, i32)],
+    /// The number of functions that we are in within the statement. Their
+    /// returns and breaks are left alone
+    fn_depth: usize,
+    /// Labels within the statement
+    inner_labels: Vec<Id>,
+}
+
+impl Visitor for Exits<'_> {
+    fn enter_stmt(&mut self, stmt: &mut Stmt, _loc: &Loc) {
+        if let Stmt::Label(x, ..) = stmt {
+            self.inner_labels.push(x.clone());
+        }
+    }
+
+    fn exit_stmt(&mut self, stmt: &mut Stmt, _loc: &Loc) {
+        match stmt {
+            Stmt::Label(..) => {
+                self.inner_labels.pop();
+            }
+            _ if self.fn_depth > 0 => (),
+            Stmt::Return(e, s) => {
+                let s = s.clone();
+                *stmt = Stmt::Block(
+                    vec![
+                        set_state_(self.state, DONE, &s),
+                        return_(result_(e.take(), true, &s), s.clone()),
+                    ],
+                    s,
+                );
+            }
+            Stmt::Break(Some(x), s) if !self.inner_labels.contains(x) => {
+                if let Some((_, case)) = self.labels.iter().rev().find(|(y, _)| y == x) {
+                    *stmt = jump_(self.state, self.cases_label, *case, &s.clone());
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn enter_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        if let Expr::Func(..) | Expr::Arrow(..) = expr {
+            self.fn_depth += 1;
+        }
+    }
+
+    fn exit_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        if let Expr::Func(..) | Expr::Arrow(..) = expr {
+            self.fn_depth -= 1;
+        }
+    }
+}
@@ -1,58 +1,240 @@
-//! desugar &&, ||, ?:, and , (seq)
+//! desugar &&, ||, ??, ?:, , (seq), and optional chains
+//!
+//! Each of these becomes an `if` statement, and the branches are blocks, so
+//! that statements that later passes insert for an operand stay inside the
+//! branch that evaluates it. For example,
+//!
+//! ```javascript
+//! var x = a?.b.c(y ?? 0);
+//! ```
+//!
+//! becomes
+//!
+//! ```javascript
+//! var $opt_chain_0 = undefined;
+//! var $opt_1 = a;
+//! if ($opt_1 == null) { } else {
+//!     var $left_2 = y;
+//!     var $nullish_3 = undefined;
+//!     if ($left_2 == null) { $nullish_3 = 0; } else { $nullish_3 = $left_2; }
+//!     $opt_chain_0 = $opt_1.b.c($nullish_3);
+//! }
+//! var x = $opt_chain_0;
+//! ```
 
 use super::constructors::*;
-use super::syntax::LogicalOp;
+use super::syntax::{ChainLink, LogicalOp, Pattern};
 use super::*;
+use crate::pos::Pos;
 
-/// desugar &&, ||, ?:, and , (seq)
+/// desugar &&, ||, ??, ?:, , (seq), and optional chains
 pub fn desugar_logical(stmt: &mut Stmt, ng: &mut NameGen) {
-    stmt.walk(&mut DesugarLogical(ng));
+    stmt.walk(&mut DesugarLogical {
+        ng,
+        patterns: vec![],
+    });
 }
 
 /// visitor for all logical desugars
-struct DesugarLogical<'a>(&'a mut NameGen);
+struct DesugarLogical<'a> {
+    ng: &'a mut NameGen,
+    /// The patterns of the enclosing destructuring assignments, which we
+    /// take out while we visit their right-hand sides. The defaults and
+    /// lvalues in a pattern are only evaluated as they are bound, so
+    /// `desugar_destructuring` desugars them once it has put them in order
+    patterns: Vec<Pattern>,
+}
+
+impl DesugarLogical<'_> {
+    /// Desugar `stmts` and insert them before the current statement. The
+    /// visitor does not visit inserted statements, so we do it here.
+    fn insert(&mut self, ctx: &BlockContext, stmts: Vec<Stmt>, s: &Pos) {
+        let mut block = Stmt::Block(stmts, s.clone());
+        block.walk(self);
+        if let Stmt::Block(stmts, _) = block {
+            for stmt in stmts {
+                ctx.insert(ctx.index, stmt);
+            }
+        }
+    }
+
+    /// The statements that evaluate the optional chain `cur` followed by
+    /// `links`, and assign the result to `result`. Nothing is assigned when
+    /// the chain short-circuits.
+    fn chain(
+        &mut self,
+        cur: Expr,
+        mut links: std::vec::IntoIter<(bool, ChainLink)>,
+        result: &Id,
+        s: &Pos,
+    ) -> Vec<Stmt> {
+        let (optional, link) = match links.next() {
+            None => return vec![expr_(assign_(result.clone(), cur, s.clone()), s.clone())],
+            Some(next) => next,
+        };
+        if !optional {
+            return self.chain(apply_link(cur, link, s), links, result, s);
+        }
+        let mut stmts = vec![];
+        let (checked, next) = match (cur, link) {
+            // o.m?.(args) calls the method with `this` bound to o
+            (Expr::Dot(obj, x, _), ChainLink::Call(args)) if *obj != Expr::Super => {
+                let obj = self.name(*obj, &mut stmts, s);
+                let f = self.name(dot_(obj.clone(), x, s.clone()), &mut stmts, s);
+                let call = Expr::CallWithThis(Box::new(f.clone()), Box::new(obj), args, s.clone());
+                (f, call)
+            }
+            (Expr::Bracket(obj, key, _), ChainLink::Call(args)) if *obj != Expr::Super => {
+                let obj = self.name(*obj, &mut stmts, s);
+                let key = self.name(*key, &mut stmts, s);
+                let f = self.name(bracket_(obj.clone(), key, s.clone()), &mut stmts, s);
+                let call = Expr::CallWithThis(Box::new(f.clone()), Box::new(obj), args, s.clone());
+                (f, call)
+            }
+            (cur, link) => {
+                let cur = self.name(cur, &mut stmts, s);
+                (cur.clone(), apply_link(cur, link, s))
+            }
+        };
+        stmts.push(if_(
+            is_nullish(checked, s),
+            Stmt::Block(vec![], s.clone()),
+            Stmt::Block(self.chain(next, links, result, s), s.clone()),
+            s.clone(),
+        ));
+        stmts
+    }
+
+    /// Store `e` in a fresh variable, so that it is evaluated exactly once
+    fn name(&mut self, e: Expr, stmts: &mut Vec<Stmt>, s: &Pos) -> Expr {
+        let x = self.ng.fresh("opt");
+        stmts.push(vardecl1_(x.clone(), e, s.clone()));
+        id_(x, s.clone())
+    }
+}
+
 impl Visitor for DesugarLogical<'_> {
-    fn exit_expr(&mut self, expr: &mut Expr, loc: &Loc) {
-        let ctx = loc.enclosing_block().expect("expected block context");
+    fn enter_expr(&mut self, expr: &mut Expr, loc: &Loc) {
+        if let Expr::Destructure(pat, _, _) = expr {
+            let pat = std::mem::replace(&mut **pat, Pattern::Array(vec![], None));
+            self.patterns.push(pat);
+            return;
+        }
+        // we desugar on the way down, so that the operands are desugared
+        // within the blocks that we produce for them
+        let ctx = match expr {
+            Expr::Binary(BinOp::LogicalOp(..), ..)
+            | Expr::If(..)
+            | Expr::Seq(..)
+            | Expr::OptChain(..) => loc.enclosing_block().expect("expected block context"),
+            _ => return,
+        };
         match expr {
-            Expr::Binary(BinOp::LogicalOp(op), left, right, ref s) => {
-                let left_name = self.0.fresh("left");
-                let (cons, alt, op_name) = match op {
-                    LogicalOp::And => (right.take(), id_(left_name.clone(), s.clone()), "and"),
-                    LogicalOp::Or => (id_(left_name.clone(), s.clone()), right.take(), "or"),
+            Expr::Binary(BinOp::LogicalOp(op), left, right, s) => {
+                let s = s.clone();
+                let left_name = self.ng.fresh("left");
+                let left_id = id_(left_name.clone(), s.clone());
+                let (test, cons, alt, op_name) = match op {
+                    LogicalOp::And => (left_id.clone(), right.take(), left_id, "and"),
+                    LogicalOp::Or => (left_id.clone(), left_id, right.take(), "or"),
+                    LogicalOp::Nullish => (
+                        is_nullish(left_id.clone(), &s),
+                        right.take(),
+                        left_id,
+                        "nullish",
+                    ),
                 };
-                let result = self.0.fresh(op_name);
+                let result = self.ng.fresh(op_name);
                 let if_stmt = if_(
-                    id_(left_name.clone(), s.clone()),
-                    expr_(assign_(result.clone(), cons, s.clone()), s.clone()),
-                    expr_(assign_(result.clone(), alt, s.clone()), s.clone()),
+                    test,
+                    Stmt::Block(
+                        vec![expr_(assign_(result.clone(), cons, s.clone()), s.clone())],
+                        s.clone(),
+                    ),
+                    Stmt::Block(
+                        vec![expr_(assign_(result.clone(), alt, s.clone()), s.clone())],
+                        s.clone(),
+                    ),
                     s.clone(),
                 );
-                ctx.insert(ctx.index, vardecl1_(result.clone(), UNDEFINED_, s.clone()));
-                ctx.insert(ctx.index, vardecl1_(left_name, left.take(), s.clone()));
-                ctx.insert(ctx.index, if_stmt);
-                *expr = id_(result, s.clone());
+                let stmts = vec![
+                    vardecl1_(left_name, left.take(), s.clone()),
+                    vardecl1_(result.clone(), UNDEFINED_, s.clone()),
+                    if_stmt,
+                ];
+                self.insert(ctx, stmts, &s);
+                *expr = id_(result, s);
             }
             Expr::If(cond, cons, alt, s) => {
-                let result = self.0.fresh("if_expr");
-                ctx.insert(ctx.index, vardecl1_(result.clone(), UNDEFINED_, s.clone()));
+                let s = s.clone();
+                let result = self.ng.fresh("if_expr");
                 let if_stmt = if_(
                     cond.take(),
-                    expr_(assign_(result.clone(), cons.take(), s.clone()), s.clone()),
-                    expr_(assign_(result.clone(), alt.take(), s.clone()), s.clone()),
+                    Stmt::Block(
+                        vec![expr_(
+                            assign_(result.clone(), cons.take(), s.clone()),
+                            s.clone(),
+                        )],
+                        s.clone(),
+                    ),
+                    Stmt::Block(
+                        vec![expr_(
+                            assign_(result.clone(), alt.take(), s.clone()),
+                            s.clone(),
+                        )],
+                        s.clone(),
+                    ),
                     s.clone(),
                 );
-                ctx.insert(ctx.index, if_stmt);
-                *expr = id_(result, s.clone());
+                let stmts = vec![vardecl1_(result.clone(), UNDEFINED_, s.clone()), if_stmt];
+                self.insert(ctx, stmts, &s);
+                *expr = id_(result, s);
             }
             Expr::Seq(es, s) => {
+                let s = s.clone();
                 let last = es.pop().expect("sequence with no exprs");
-                for e in es {
-                    ctx.insert(ctx.index, expr_(e.take(), s.clone()));
-                }
+                let stmts = es.drain(..).map(|e| expr_(e, s.clone())).collect();
+                self.insert(ctx, stmts, &s);
                 *expr = last;
+                // the visitor does not enter the expression that replaces
+                // this one
+                self.enter_expr(expr, loc);
             }
-            _ => (),
+            Expr::OptChain(e, links, s) => {
+                let s = s.clone();
+                let result = self.ng.fresh("opt_chain");
+                let links = std::mem::take(links).into_iter();
+                let mut stmts = vec![vardecl1_(result.clone(), UNDEFINED_, s.clone())];
+                stmts.extend(self.chain(e.take(), links, &result, &s));
+                self.insert(ctx, stmts, &s);
+                *expr = id_(result, s);
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn exit_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        if let Expr::Destructure(pat, _, _) = expr {
+            **pat = self.patterns.pop().unwrap();
         }
     }
 }
+
+/// `e == null`, which is also true when `e` is `undefined`
+fn is_nullish(e: Expr, s: &Pos) -> Expr {
+    binary_(
+        BinOp::BinaryOp(BinaryOp::Equal),
+        e,
+        Expr::Lit(Lit::Null, s.clone()),
+        s.clone(),
+    )
+}
+
+/// `cur.x`, `cur[e]` or `cur(args)`
+fn apply_link(cur: Expr, link: ChainLink, s: &Pos) -> Expr {
+    match link {
+        ChainLink::Dot(x) => dot_(cur, x, s.clone()),
+        ChainLink::Bracket(e) => bracket_(cur, e, s.clone()),
+        ChainLink::Call(args) => call_(cur, args, s.clone()),
+    }
+}
@@ -1,6 +1,8 @@
 //! desugar loops
 //!
-//! - label for..in and while with a break label and a continue label
+//! - desugar for..of to a for loop that uses the iteration protocol, and
+//!   for..in to one that iterates over the keys of the object
+//! - label while with a break label and a continue label
 //! - change continue to break to the continue label
 //! - use explicit labels in all breaks
 //! - desugar for and do..while to while
@@ -12,11 +14,128 @@ use crate::pos::Pos;
 use std::collections::HashMap;
 
 pub fn desugar_loops(script: &mut Stmt, ng: &mut NameGen) {
+    script.walk(&mut ForOfToFor::new(ng));
     script.walk(&mut ExplicitBreaks);
     script.walk(&mut LabelLoops::new(ng));
     script.walk(&mut ForToWhile);
 }
 
+/// changes for..of loops to for loops that use the iteration protocol:
+///
+/// ```javascript
+/// for (var x of e) body
+/// ```
+///
+/// becomes
+///
+/// ```javascript
+/// {
+///     var $iter = __JNKS.iter(e);
+///     try {
+///         for (var $elem = __JNKS.iter_next($iter);
+///              !__JNKS.iter_done($elem);
+///              $elem = __JNKS.iter_next($iter)) {
+///             var x = $elem;
+///             body
+///         }
+///     } finally {
+///         __JNKS.iter_close($iter);
+///     }
+/// }
+/// ```
+///
+/// arrays and strings are indexed directly, and other values are iterated
+/// by calling their `Symbol.iterator` method (see `jnks_iter` in the NotWasm
+/// runtime). when the loop stops before the iterator is done, e.g., by
+/// `break`, `return` or an exception, `iter_close` calls the `return` method
+/// of the iterator
+///
+/// `for (x in e)` is the same, except that the iteration starts with
+/// `__JNKS.for_in(e)`, which iterates over the keys that the loop enumerates
+/// (see `jnks_for_in` in the NotWasm runtime), and there is nothing to close
+///
+/// this must happen first, so that the resulting for loop is labeled and
+/// desugared like any other
+struct ForOfToFor<'a> {
+    ng: &'a mut NameGen,
+}
+impl<'a> ForOfToFor<'a> {
+    fn new(ng: &'a mut NameGen) -> Self {
+        Self { ng }
+    }
+}
+impl Visitor for ForOfToFor<'_> {
+    fn enter_stmt(&mut self, node: &mut Stmt, _loc: &Loc) {
+        match node {
+            // the label stays on the loop, so that `continue` can refer to it
+            Label(x, body, _) if matches!(**body, ForIn(..) | ForOf(..)) => {
+                let x = x.clone();
+                *node = self.for_loop(body.take(), Some(x));
+            }
+            ForIn(..) | ForOf(..) => *node = self.for_loop(node.take(), None),
+            _ => (),
+        }
+    }
+}
+impl ForOfToFor<'_> {
+    /// `for (x of e) body` or `for (x in e) body`, labeled with `label`
+    fn for_loop(&mut self, node: Stmt, label: Option<Id>) -> Stmt {
+        let closes = matches!(node, ForOf(..));
+        let (start, is_var, bind, container, body, s) = match node {
+            ForIn(is_var, bind, container, body, s) => ("for_in", is_var, bind, container, body, s),
+            ForOf(is_var, bind, container, body, s) => ("iter", is_var, bind, container, body, s),
+            _ => unreachable!("not a for..in or for..of loop"),
+        };
+        let iter = self.ng.fresh("iter");
+        let elem = self.ng.fresh("elem");
+        let jnks = |f: &str, arg: super::Expr| {
+            call_(
+                dot_(id_("__JNKS", s.clone()), f, s.clone()),
+                vec![arg],
+                s.clone(),
+            )
+        };
+        let start = vardecl1_(iter.clone(), jnks(start, *container), s.clone());
+        let init = ForInit::Decl(vec![super::VarDecl {
+            name: elem.clone(),
+            named: Box::new(jnks("iter_next", id_(iter.clone(), s.clone()))),
+            kind: VarKind::Var,
+        }]);
+        let cond = not_(jnks("iter_done", id_(elem.clone(), s.clone())), s.clone());
+        let advance = assign_(
+            elem.clone(),
+            jnks("iter_next", id_(iter.clone(), s.clone())),
+            s.clone(),
+        );
+        let elem = id_(elem, s.clone());
+        let bind_elem = match is_var {
+            Some(kind) => Stmt::VarDecl(
+                vec![super::VarDecl {
+                    name: bind,
+                    named: Box::new(elem),
+                    kind,
+                }],
+                s.clone(),
+            ),
+            None => expr_(assign_(bind, elem, s.clone()), s.clone()),
+        };
+        let body = Block(vec![bind_elem, *body], s.clone());
+        let mut for_loop = for_(init, cond, advance, body, s.clone());
+        if let Some(label) = label {
+            for_loop = label_(label, for_loop, s.clone());
+        }
+        if closes {
+            let close = expr_(jnks("iter_close", id_(iter, s.clone())), s.clone());
+            for_loop = finally_(
+                Block(vec![for_loop], s.clone()),
+                Block(vec![close], s.clone()),
+                s.clone(),
+            );
+        }
+        Block(vec![start, for_loop], s)
+    }
+}
+
 /// changes for loops to while loops
 ///
 /// precondition: already labeled because statements will be inserted after
@@ -188,8 +307,7 @@ impl LabelLoops<'_> {
     }
 }
 fn if_loop_then_body(stmt: &mut Stmt) -> Option<(&mut Box<Stmt>, Pos)> {
-    if let For(.., body, s) | DoWhile(body, .., s) | ForIn(.., body, s) | While(.., body, s) = stmt
-    {
+    if let For(.., body, s) | DoWhile(body, .., s) | While(.., body, s) = stmt {
         Some((body, s.clone()))
     } else {
         None
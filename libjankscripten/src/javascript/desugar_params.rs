@@ -0,0 +1,628 @@
+//! fit the arguments of calls to known functions to their parameters
+//!
+//! A function is called with exactly as many arguments as it has parameters.
+//! A missing argument is `undefined`, and an extra argument is evaluated
+//! after the others, but dropped:
+//!
+//! ```javascript
+//! function f(x, y) { }
+//! f(1);
+//! f(1, 2, g());
+//! ```
+//!
+//! becomes
+//!
+//! ```javascript
+//! function f(x, y) { }
+//! f(1, undefined);
+//! f(1, [2, g()][0]);
+//! ```
+//!
+//! We only know the parameters of the callee when it is a variable that is
+//! only ever bound to functions with the same parameters, or `super` in a
+//! class that extends such a variable. Other calls are left alone, and so is
+//! a call with extra arguments to a function without parameters. The
+//! arguments of `f.call(this_arg, args...)` are fitted to the parameters of
+//! `f` too.
+//!
+//! A function with a rest parameter collects its arguments itself. Its only
+//! parameter is an array of all of its arguments, from which it takes its
+//! other parameters:
+//!
+//! ```javascript
+//! function f(x, ...ys) { }
+//! ```
+//!
+//! becomes
+//!
+//! ```javascript
+//! function f(...$args) {
+//!     var x = $args[0];
+//!     var ys = __JNKS.rest_args($args, 1);
+//! }
+//! ```
+//!
+//! `desugar_this` marks such a function as variadic, so that every call
+//! passes it an array of exactly the arguments of the call, whether or not
+//! we know the callee. So does a function with more parameters than the
+//! arity of a function in an any can hold. We never fit the arguments of a
+//! call to a variadic function.
+//!
+//! A function declaration that mentions `arguments` takes its `arguments`
+//! array after its other parameters (see `desugar_arguments`). When the
+//! function is only ever called directly, every call passes it an array of
+//! its arguments:
+//!
+//! ```javascript
+//! function f(x, y, $arguments) { }
+//! f(1);
+//! ```
+//!
+//! becomes
+//!
+//! ```javascript
+//! function f(x, y, $arguments) { }
+//! f(1, undefined, [1]);
+//! ```
+//!
+//! Otherwise, the function is variadic, and collects its `arguments` array
+//! like the function above.
+//!
+//! This is also where `f.bind(this_arg, args...)` becomes a function, since
+//! the parameters of the bound function are the parameters of `f` that
+//! `args` do not supply:
+//!
+//! ```javascript
+//! function f(x, y) { }
+//! var g = f.bind(o, 1);
+//! ```
+//!
+//! becomes
+//!
+//! ```javascript
+//! function f(x, y) { }
+//! var g = (function ($f, $this, $arg) {
+//!     if (typeof $f === "function") {
+//!         return function ($y) {
+//!             if (new.target) { return new $f($arg, $y); }
+//!             return $f.call($this, $arg, $y);
+//!         };
+//!     }
+//!     return $f.bind($this, $arg);
+//! })(f, o, 1);
+//! ```
+//!
+//! When we do not know the parameters of `f`, `f.bind(...)` is left alone,
+//! so it calls `Function.prototype.bind` in stdlib.notwasm, which applies `f`
+//! to all of the arguments of the bound function.
+//!
+//! A call that is left alone goes through `any`, which pads or drops its
+//! arguments to fit the arity of the callee at runtime.
+
+use super::constructors::*;
+use super::syntax::*;
+use super::*;
+use crate::pos::Pos;
+use std::collections::HashMap;
+
+pub fn desugar_params(program: &mut Stmt, ng: &mut NameGen) {
+    let mut collect = CollectParams {
+        ng,
+        params: HashMap::new(),
+        mentions: HashMap::new(),
+        bound: vec![],
+    };
+    program.walk(&mut collect);
+    collect.bind_bound();
+    collect.escape_arguments();
+    let mut v = FitArgs {
+        ng: collect.ng,
+        params: collect.params,
+        parents: vec![],
+    };
+    program.walk(&mut v);
+}
+
+/// A function in an any holds its arity in six bits, which includes `this`
+const MAX_PARAMS: usize = 62;
+
+/// The number of parameters of a function that is not variadic
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Params {
+    arity: usize,
+    /// Whether the function also takes its `arguments` array, after its
+    /// other parameters (see `desugar_arguments`)
+    arguments: bool,
+}
+
+impl Params {
+    fn new(params: &[Id], rest: bool) -> Option<Self> {
+        if is_variadic(params, rest) {
+            None
+        } else if params
+            .last()
+            .is_some_and(desugar_arguments::is_arguments_param)
+        {
+            Some(Params {
+                arity: params.len() - 1,
+                arguments: true,
+            })
+        } else {
+            Some(Params {
+                arity: params.len(),
+                arguments: false,
+            })
+        }
+    }
+
+    /// The parameters of the function that `e` produces, if it is a function
+    /// or a class. Only a function declaration takes its `arguments` array
+    /// as a parameter, so any other function that mentions `arguments` is
+    /// variadic
+    fn of_expr(e: &Expr) -> Option<Self> {
+        match e {
+            Expr::Func(_, params, rest, ..) | Expr::Arrow(params, rest, ..) => {
+                Params::new(params, *rest).filter(|params| !params.arguments)
+            }
+            Expr::Class(class, _) => Params::of_class(class),
+            _ => None,
+        }
+    }
+
+    /// The parameters of the constructor of `class`
+    fn of_class(class: &Class) -> Option<Self> {
+        match &class.constructor {
+            Some(ctor) => Params::of_expr(ctor),
+            // see `desugar_classes::default_constructor`, which has a rest
+            // parameter in a derived class
+            None if class.extends.is_some() => None,
+            None => Some(Params {
+                arity: 0,
+                arguments: false,
+            }),
+        }
+    }
+
+    /// The parameters of `f.bind(this_arg, args)`, where `args` has `n`
+    /// elements
+    fn bound(&self, n: usize) -> Self {
+        Params {
+            arity: self.arity.saturating_sub(n),
+            arguments: false,
+        }
+    }
+
+    /// `f(args)` => `f(args')`, where `args'` has exactly one element for
+    /// each parameter of `f`
+    fn fit(&self, args: &mut Vec<Expr>, s: &Pos) {
+        if args.len() > self.arity && self.arity == 0 {
+            // there is no argument to evaluate the extra ones with, so the
+            // call goes through any, which drops them
+            return;
+        }
+        normalize_std_lib_calls::fit_args(args, self.arity, || UNDEFINED_, s);
+    }
+}
+
+/// Whether a function with these parameters collects its arguments itself
+fn is_variadic(params: &[Id], rest: bool) -> bool {
+    rest || params.len() > MAX_PARAMS
+}
+
+/// `function (x, ...ys) { body }` =>
+/// `function (...$args) { var x = $args[0]; var ys = __JNKS.rest_args($args, 1); body }`
+fn collect_args(ng: &mut NameGen, params: &mut Vec<Id>, rest: &mut bool, body: &mut Stmt, s: &Pos) {
+    if !is_variadic(params, *rest) || params.len() == 1 && *rest {
+        return;
+    }
+    collect_args_into(ng.fresh("args"), params, rest, body, s);
+}
+
+/// A function that takes its `arguments` array as its last parameter, but
+/// that is not only called where we can pass it the array, collects its
+/// arguments into the array instead
+fn collect_arguments(params: &mut Vec<Id>, rest: &mut bool, body: &mut Stmt, s: &Pos) {
+    if !params
+        .last()
+        .is_some_and(desugar_arguments::is_arguments_param)
+    {
+        return;
+    }
+    let arguments = params.pop().unwrap();
+    collect_args_into(arguments, params, rest, body, s);
+}
+
+/// Like `collect_args`, but collects the arguments into `args`, whether or
+/// not the function needs to be variadic
+pub(super) fn collect_args_into(
+    args: Id,
+    params: &mut Vec<Id>,
+    rest: &mut bool,
+    body: &mut Stmt,
+    s: &Pos,
+) {
+    let rest_param = if *rest { params.pop() } else { None };
+    let mut prologue = vec![];
+    for (i, x) in params.drain(..).enumerate() {
+        let arg = bracket_(
+            id_(args.clone(), s.clone()),
+            int_(i as i32, s.clone()),
+            s.clone(),
+        );
+        prologue.push(vardecl1_(x, arg, s.clone()));
+    }
+    if let Some(ys) = rest_param {
+        let rest_args = call_(
+            dot_(id_("__JNKS", s.clone()), "rest_args", s.clone()),
+            vec![
+                id_(args.clone(), s.clone()),
+                int_(prologue.len() as i32, s.clone()),
+            ],
+            s.clone(),
+        );
+        prologue.push(vardecl1_(ys, rest_args, s.clone()));
+    }
+    params.push(args);
+    *rest = true;
+    match body {
+        Stmt::Block(ss, _) => {
+            prologue.append(ss);
+            *ss = prologue;
+        }
+        _ => {
+            prologue.push(body.take());
+            *body = Stmt::Block(prologue, s.clone());
+        }
+    }
+}
+
+/// Maps each variable to the parameters of the functions that it is bound
+/// to, or to `None` if it may be bound to anything else
+struct CollectParams<'a> {
+    ng: &'a mut NameGen,
+    params: HashMap<Id, Option<Params>>,
+    /// The number of times that each variable is mentioned, and the number
+    /// of times that it is called directly. We can only pass a function its
+    /// `arguments` array when every mention of it is a call
+    mentions: HashMap<Id, (usize, usize)>,
+    /// `(x, f, n)` when `x` is bound to `f.bind(this_arg, args)`, where
+    /// `args` has `n` elements. We only know the parameters of `x` once we
+    /// know the parameters of `f`
+    bound: Vec<(Id, Id, usize)>,
+}
+
+impl CollectParams<'_> {
+    /// Binds `x` to `e`, unless `e` is a call to `bind`, which `bind_bound`
+    /// takes care of
+    fn bind_expr(&mut self, x: &Id, e: &Expr) {
+        match e {
+            Expr::Call(f, args, _) if is_method(f, "bind") => match &**f {
+                Expr::Dot(f, ..) => match &**f {
+                    Expr::Id(f, _) => {
+                        let n = args.len().saturating_sub(1);
+                        self.bound.push((x.clone(), f.clone(), n));
+                    }
+                    _ => self.bind(x, None),
+                },
+                _ => unreachable!(),
+            },
+            _ => self.bind(x, Params::of_expr(e)),
+        }
+    }
+
+    fn bind_bound(&mut self) {
+        for (x, f, n) in std::mem::take(&mut self.bound) {
+            let params = self.params.get(&f).copied().flatten();
+            self.bind(&x, params.map(|params| params.bound(n)));
+        }
+    }
+
+    fn bind(&mut self, x: &Id, params: Option<Params>) {
+        let entry = self.params.entry(x.clone()).or_insert(params);
+        if *entry != params {
+            *entry = None;
+        }
+    }
+
+    fn bind_params(&mut self, params: &[Id]) {
+        for x in params {
+            self.bind(x, None);
+        }
+    }
+
+    /// A function that takes its `arguments` array, but that may be called
+    /// where we cannot pass it the array, is variadic instead
+    fn escape_arguments(&mut self) {
+        for (x, params) in self.params.iter_mut() {
+            if params.is_some_and(|params| params.arguments) {
+                let (mentions, calls) = self.mentions.get(x).copied().unwrap_or_default();
+                if mentions != calls {
+                    *params = None;
+                }
+            }
+        }
+    }
+}
+
+impl Visitor for CollectParams<'_> {
+    fn enter_stmt(&mut self, stmt: &mut Stmt, _loc: &Loc) {
+        match stmt {
+            Stmt::Func(name, params, rest, ..) => {
+                self.bind(name, Params::new(params, *rest));
+                self.bind_params(params);
+            }
+            Stmt::VarDecl(decls, _) => {
+                for VarDecl { name, named, .. } in decls {
+                    self.bind_expr(name, named);
+                }
+            }
+            Stmt::ForIn(_, x, ..) | Stmt::ForOf(_, x, ..) | Stmt::Catch(_, x, ..) => {
+                self.bind(x, None)
+            }
+            _ => (),
+        }
+    }
+
+    fn enter_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        match expr {
+            Expr::Id(x, _) => self.mentions.entry(x.clone()).or_default().0 += 1,
+            Expr::Call(f, ..) => {
+                if let Expr::Id(f, _) = &**f {
+                    self.mentions.entry(f.clone()).or_default().1 += 1;
+                }
+            }
+            Expr::Func(..) => {
+                let f = Params::of_expr(expr);
+                if let Expr::Func(name, params, ..) = expr {
+                    if let Some(name) = name {
+                        self.bind(name, f);
+                    }
+                    self.bind_params(params);
+                }
+            }
+            Expr::Arrow(params, ..) => self.bind_params(params),
+            Expr::Assign(op, lv, e, _) => {
+                if let LValue::Id(x) = &**lv {
+                    match op {
+                        AssignOp::Equal => self.bind_expr(x, e),
+                        _ => self.bind(x, None),
+                    }
+                }
+            }
+            Expr::UnaryAssign(_, lv, _) => {
+                if let LValue::Id(x) = &**lv {
+                    self.bind(x, None);
+                }
+            }
+            Expr::Destructure(pat, ..) => {
+                for x in pat.bound_ids() {
+                    self.bind(&x, None);
+                }
+            }
+            Expr::Class(class, s) => {
+                if let Some(name) = &class.name {
+                    let f = Params::of_class(class);
+                    self.bind(name, f);
+                }
+                // a derived class without a constructor forwards its
+                // arguments to its parent. We write out that constructor
+                // here so that the call to the parent is fitted below
+                if class.constructor.is_none() && class.extends.is_some() {
+                    let ctor = desugar_classes::default_constructor(true, self.ng, s.clone());
+                    class.constructor = Some(Box::new(ctor));
+                }
+            }
+            _ => (),
+        }
+    }
+}
+
+struct FitArgs<'a> {
+    ng: &'a mut NameGen,
+    params: HashMap<Id, Option<Params>>,
+    /// The parameters of the parents of the enclosing classes
+    parents: Vec<Option<Params>>,
+}
+
+impl FitArgs<'_> {
+    /// The parameters of the function that a call to `f` calls
+    fn callee(&self, f: &Expr) -> Option<Params> {
+        match f {
+            Expr::Id(x, _) => self.callee_params(x),
+            Expr::Super => self.parents.last().copied().flatten(),
+            _ => None,
+        }
+    }
+
+    fn callee_params(&self, x: &Id) -> Option<Params> {
+        self.params.get(x).copied().flatten()
+    }
+
+    /// `f(args)` => `f(args', [args])`, where `args'` are fitted to the
+    /// parameters of `f`. When an argument may have an effect, the arguments
+    /// are evaluated once, by an application:
+    ///
+    /// ```javascript
+    /// (function ($arg_0, $arg_1) { return f($arg_0, [$arg_0, $arg_1]); })(g(), 2)
+    /// ```
+    fn pass_arguments(&mut self, f: Expr, params: Params, args: Vec<Expr>, s: &Pos) -> Expr {
+        if args
+            .iter()
+            .all(|arg| matches!(arg, Expr::Lit(..) | Expr::Id(..)))
+        {
+            let mut fitted = args.clone();
+            fitted.resize_with(params.arity, || UNDEFINED_);
+            fitted.push(Expr::Array(args, s.clone()));
+            return call_(f, fitted, s.clone());
+        }
+        let xs = args
+            .iter()
+            .map(|_| self.ng.fresh("arg"))
+            .collect::<Vec<_>>();
+        let ids = xs.iter().map(|x| id_(x.clone(), s.clone())).collect();
+        let call = self.pass_arguments(f, params, ids, s);
+        let body = Stmt::Block(vec![return_(call, s.clone())], s.clone());
+        call_(lambda(xs, body, s.clone()), args, s.clone())
+    }
+
+    /// `e.bind(args)` => the function in the module documentation, where
+    /// `params` are the parameters of `e`
+    fn bind(&mut self, e: Expr, params: Params, mut args: Vec<Expr>, s: &Pos) -> Expr {
+        if args.is_empty() {
+            args.push(UNDEFINED_);
+        }
+        let f = self.ng.fresh("bound_fn");
+        let this = self.ng.fresh("bound_this");
+        let prefix = (1..args.len())
+            .map(|_| self.ng.fresh("bound_arg"))
+            .collect::<Vec<_>>();
+        let ids = |xs: &[Id]| {
+            xs.iter()
+                .map(|x| id_(x.clone(), s.clone()))
+                .collect::<Vec<_>>()
+        };
+        // the parameters of `f` that `args` do not supply
+        let bound = params.bound(prefix.len());
+        let bound_params = (0..bound.arity)
+            .map(|_| self.ng.fresh("bound_param"))
+            .collect::<Vec<_>>();
+        let mut f_args = ids(&prefix);
+        f_args.extend(ids(&bound_params));
+        // extra arguments are dropped
+        f_args.truncate(params.arity);
+        // under `new`, the bound function constructs with `f`, and ignores
+        // `this_arg`
+        let construct = Expr::New(
+            Box::new(id_(f.clone(), s.clone())),
+            f_args.clone(),
+            s.clone(),
+        );
+        let call = Expr::CallWithThis(
+            Box::new(id_(f.clone(), s.clone())),
+            Box::new(id_(this.clone(), s.clone())),
+            f_args,
+            s.clone(),
+        );
+        let bound = lambda(
+            bound_params,
+            Stmt::Block(
+                vec![
+                    if_(
+                        Expr::NewTarget,
+                        Stmt::Block(vec![return_(construct, s.clone())], s.clone()),
+                        Stmt::Block(vec![], s.clone()),
+                        s.clone(),
+                    ),
+                    return_(call, s.clone()),
+                ],
+                s.clone(),
+            ),
+            s.clone(),
+        );
+        let is_function = binary_(
+            BinOp::BinaryOp(BinaryOp::StrictEqual),
+            unary_(UnaryOp::TypeOf, id_(f.clone(), s.clone()), s.clone()),
+            str_("function", s.clone()),
+            s.clone(),
+        );
+        let mut method_args = vec![id_(this.clone(), s.clone())];
+        method_args.extend(ids(&prefix));
+        let method = call_(
+            dot_(id_(f.clone(), s.clone()), "bind", s.clone()),
+            method_args,
+            s.clone(),
+        );
+        let body = Stmt::Block(
+            vec![
+                if_(
+                    is_function,
+                    Stmt::Block(vec![return_(bound, s.clone())], s.clone()),
+                    Stmt::Block(vec![], s.clone()),
+                    s.clone(),
+                ),
+                return_(method, s.clone()),
+            ],
+            s.clone(),
+        );
+        let mut iife_params = vec![f, this];
+        iife_params.extend(prefix);
+        let mut iife_args = vec![e];
+        iife_args.extend(args);
+        call_(lambda(iife_params, body, s.clone()), iife_args, s.clone())
+    }
+}
+
+impl Visitor for FitArgs<'_> {
+    fn exit_stmt(&mut self, stmt: &mut Stmt, _loc: &Loc) {
+        if let Stmt::Func(name, params, rest, body, s) = stmt {
+            if self.callee_params(name).is_none() {
+                collect_arguments(params, rest, body, s);
+            }
+            collect_args(self.ng, params, rest, body, s);
+        }
+    }
+
+    fn enter_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        if let Expr::Class(class, _) = expr {
+            let parent = class.extends.as_ref().and_then(|e| self.callee(e));
+            self.parents.push(parent);
+        }
+    }
+
+    fn exit_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        match expr {
+            Expr::Class(..) => {
+                self.parents.pop();
+            }
+            Expr::Call(f, args, s) if is_method(f, "bind") => {
+                if let Expr::Dot(e, ..) = &mut **f {
+                    if let Some(params) = self.callee(e) {
+                        let args = std::mem::take(args);
+                        *expr = self.bind(e.take(), params, args, &s.clone());
+                    }
+                }
+            }
+            Expr::Call(f, args, s)
+                if is_method(f, "call") || is_method(f, "apply") && args.len() < 2 =>
+            {
+                // the first argument is `this`, and `f.apply(this_arg)` is
+                // `f.call(this_arg)`
+                if let Expr::Dot(e, method, _) = &mut **f {
+                    if let Some(params) = self.callee(e) {
+                        *method = Id::Named("call".to_string());
+                        if args.is_empty() {
+                            args.push(UNDEFINED_);
+                        }
+                        let mut rest = args.split_off(1);
+                        params.fit(&mut rest, s);
+                        args.append(&mut rest);
+                    }
+                }
+            }
+            Expr::Call(f, args, s) => match self.callee(f) {
+                Some(params) if params.arguments => {
+                    let args = std::mem::take(args);
+                    *expr = self.pass_arguments(f.take(), params, args, &s.clone());
+                }
+                Some(params) => params.fit(args, s),
+                None => (),
+            },
+            Expr::New(f, args, s) => {
+                if let Some(params) = self.callee(f) {
+                    params.fit(args, s);
+                }
+            }
+            Expr::Func(_, params, rest, body, s) => {
+                collect_arguments(params, rest, body, s);
+                collect_args(self.ng, params, rest, body, s);
+            }
+            Expr::Arrow(params, rest, body, s) => collect_args(self.ng, params, rest, body, s),
+            _ => (),
+        }
+    }
+}
+
+/// Whether `f` is `e.method`
+fn is_method(f: &Expr, method: &str) -> bool {
+    matches!(f, Expr::Dot(_, Id::Named(m), _) if m == method)
+}
@@ -1,100 +1,347 @@
 use super::constructors::*;
 use super::syntax::*;
 use super::*;
+use crate::pos::Pos;
 
 struct ThisParameter<'a> {
     ng: &'a mut NameGen,
     this_name: Id,
+    /// For each enclosing function, the name of its `new.target` parameter,
+    /// if it mentions `new.target`
+    new_targets: Vec<Option<Id>>,
 }
 
 impl Visitor for ThisParameter<'_> {
+    fn enter_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+        if let Expr::Func(_, params, rest, body, s) = expr {
+            // a function that mentions `new.target` takes it as a parameter
+            // after `this`, and collects its other arguments, since calls
+            // that are not `new` do not pass it. we collect them before
+            // walking the body, so that `__JNKS.rest_args` gets its `this`
+            let new_target = if mentions_new_target(body) {
+                if !*rest {
+                    let args = self.ng.fresh("args");
+                    desugar_params::collect_args_into(args, params, rest, body, s);
+                }
+                Some(self.ng.fresh("new_target"))
+            } else {
+                None
+            };
+            self.new_targets.push(new_target);
+        }
+    }
+
     fn exit_expr(&mut self, expr: &mut Expr, loc: &Loc) {
         match expr {
-            Expr::Call(f, args, _) => match &mut **f {
-                // only syntactically immediate bracket/dot preserves the
-                // object as `this`
-                Expr::Bracket(obj, _, s) | Expr::Dot(obj, _, s) => {
-                    // fresh the obj so we can pass it to the method
-                    let cxt = loc.enclosing_block().unwrap();
-                    let obj_name = self.ng.fresh("obj4this");
-                    cxt.insert(
-                        cxt.index,
-                        vardecl1_(obj_name.clone(), obj.take(), s.clone()),
-                    );
-                    *obj = Box::new(id_(obj_name.clone(), s.clone()));
-                    args.insert(0, id_(obj_name, s.clone()));
-                }
-                // for the rest, we'll hand undefined. all our benchmarks
-                // play nice with "use strict";
-                _ => args.insert(0, UNDEFINED_),
-            },
+            Expr::Call(f, args, s) if is_method(f, "call") || is_method(f, "apply") => {
+                *expr = self.call_or_apply(f, args, s, loc);
+            }
+            Expr::Call(f, args, _) => {
+                let this_arg = self.this_of_callee(f, loc);
+                args.insert(0, this_arg);
+            }
+            Expr::Apply(f, this_arg, args, s) => {
+                // f(...args) => __JNKS.apply(f, this, args)
+                let this_arg = match this_arg.take() {
+                    Some(this_arg) => *this_arg,
+                    None => self.this_of_callee(f, loc),
+                };
+                let jnks = id_("__JNKS", s.clone());
+                *expr = call_(
+                    dot_(jnks.clone(), "apply", s.clone()),
+                    vec![jnks, f.take(), this_arg, args.take()],
+                    s.clone(),
+                );
+            }
+            Expr::CallWithThis(f, this_arg, args, s) => {
+                // name the function, so that a dot or bracket callee does
+                // not turn into a method call on its object
+                let f = match f.take() {
+                    Expr::Id(f, _) => f,
+                    f_expr => {
+                        let cxt = loc.enclosing_block().unwrap();
+                        let f = self.ng.fresh("fun4this");
+                        cxt.insert(cxt.index, vardecl1_(f.clone(), f_expr, s.clone()));
+                        f
+                    }
+                };
+                let mut args = std::mem::take(args);
+                args.insert(0, this_arg.take());
+                *expr = call_(id_(f, s.clone()), args, s.clone());
+            }
             Expr::New(f, args, s) => {
-                // Desugar `new` into:
-                //     new MyFunc(args...) => var $func = MyFunc; var $obj = Object.create($func.prototype), $func.call($obj, args...), $obj
+                *expr = self.construct(f, args, s, loc);
+            }
+            Expr::Func(_, params, rest, _, s) => {
+                // yes for once using a named id is correct here, because
+                // it's a special name that may or may not be used by the body
+                params.insert(0, self.this_name.clone());
+                let variadic = *rest;
+                *rest = false;
+                if let Some(new_target) = self.new_targets.pop().unwrap() {
+                    params.insert(1, new_target);
+                }
+                if variadic {
+                    let s = s.clone();
+                    *expr = variadic_(expr.take(), &s);
+                }
+            }
+            Expr::Arrow(params, rest, body, s) => {
+                // an arrow function does not bind `this`. it still receives
+                // a `this` argument like every other function, but under a
+                // name the body never mentions, so `this` in the body refers
+                // to the `this` parameter of the enclosing function
+                let mut params = std::mem::take(params);
+                params.insert(0, self.ng.fresh("arrow_this"));
+                let func = Expr::Func(None, params, false, Box::new(body.take()), s.clone());
+                let func = jnks_(s, "non_constructor", vec![func]);
+                *expr = if *rest { variadic_(func, s) } else { func };
+            }
+            Expr::This => {
+                *expr = id_(self.this_name.clone(), Default::default());
+            }
+            Expr::NewTarget => {
+                // at the top level, there is no function to construct. like
+                // `this`, an arrow does not have its own `new.target`
+                *expr = match self.new_targets.last() {
+                    Some(Some(new_target)) => id_(new_target.clone(), Default::default()),
+                    _ => UNDEFINED_,
+                };
+            }
+            _ => (),
+        }
+    }
+}
 
-                // syntax block surrounding this new expression
-                let cxt = loc.enclosing_block().unwrap();
+impl ThisParameter<'_> {
+    /// `var $x = e;`, which is inserted before the current statement
+    fn name(&mut self, x: &'static str, e: Expr, s: &Pos, loc: &Loc) -> Expr {
+        let cxt = loc.enclosing_block().unwrap();
+        let x = self.ng.fresh(x);
+        cxt.insert(cxt.index, vardecl1_(x.clone(), e, s.clone()));
+        id_(x, s.clone())
+    }
 
-                // generate a new name for the function
-                let func_name = self.ng.fresh("new_constructor");
-
-                // let $func = func;
-                cxt.insert(cxt.index, vardecl1_(func_name.clone(), f.take(), s.clone()));
-
-                // generate a new name for the object
-                let obj_name = self.ng.fresh("new_obj");
-
-                // Object.create(f.prototype)
-                let new_obj = call_(
-                    dot_(id_("Object", s.clone()), "create", s.clone()),
-                    vec![
-                        id_("Object", s.clone()),
-                        Expr::Dot(
-                            Box::new(Expr::Id(func_name.clone(), s.clone())),
-                            Id::Named("prototype".to_string()),
-                            s.clone(),
-                        ),
-                    ],
-                    s.clone(),
-                );
+    /// `new f(args...)` is
+    ///
+    /// ```javascript
+    /// var $new_constructor = f; var $new_arg = arg; ...
+    /// var $new_obj = __JNKS.construct_this($new_constructor, "f is not a constructor ...");
+    /// var $new_result = undefined;
+    /// if (__JNKS.takes_new_target($new_constructor)) {
+    ///     $new_result = __JNKS.construct($new_constructor, $new_obj, $new_constructor, [$new_arg, ...]);
+    /// } else {
+    ///     $new_result = $new_constructor($new_obj, $new_arg, ...);
+    /// }
+    /// __JNKS.construct_result($new_obj, $new_result)
+    /// ```
+    ///
+    /// where `construct_this` throws a TypeError if `f` is not a
+    /// constructor, and `construct_result` is `$new_result` if it is an
+    /// object, and `$new_obj` otherwise. A function that mentions
+    /// `new.target` takes it after `this`, so `construct` passes it `f`. We
+    /// call any other constructor directly. The arguments are named so that
+    /// they are evaluated before `f` is checked, as they are in JavaScript.
+    fn construct(&mut self, f: &mut Expr, args: &mut Vec<Expr>, s: &Pos, loc: &Loc) -> Expr {
+        let message = format!("{} is not a constructor ({})", f.to_pretty(usize::MAX), s);
+        let ctor = self.name("new_constructor", f.take(), s, loc);
+        let args = std::mem::take(args)
+            .into_iter()
+            .map(|arg| self.name("new_arg", arg, s, loc))
+            .collect::<Vec<_>>();
+        let construct_this = jnks_(
+            s,
+            "construct_this",
+            vec![ctor.clone(), str_(message, s.clone())],
+        );
+        let obj = self.name("new_obj", construct_this, s, loc);
+        let result = self.ng.fresh("new_result");
+        let cxt = loc.enclosing_block().unwrap();
+        cxt.insert(cxt.index, vardecl1_(result.clone(), UNDEFINED_, s.clone()));
+
+        let construct = jnks_(
+            s,
+            "construct",
+            vec![
+                ctor.clone(),
+                obj.clone(),
+                ctor.clone(),
+                Expr::Array(args.clone(), s.clone()),
+            ],
+        );
+        let mut direct_args = args;
+        direct_args.insert(0, obj.clone());
+        let direct_call = call_(ctor.clone(), direct_args, s.clone());
+        let assign_result = |e| {
+            let assign = assign_(result.clone(), e, s.clone());
+            Stmt::Block(vec![expr_(assign, s.clone())], s.clone())
+        };
+        cxt.insert(
+            cxt.index,
+            if_(
+                jnks_(s, "takes_new_target", vec![ctor]),
+                assign_result(construct),
+                assign_result(direct_call),
+                s.clone(),
+            ),
+        );
+        jnks_(s, "construct_result", vec![obj, id_(result, s.clone())])
+    }
 
-                // Insert into the surrounding syntax block:
-                //     let $name = Object.create(f.prototype);
-                cxt.insert(cxt.index, vardecl1_(obj_name.clone(), new_obj, s.clone()));
+    /// `e.call(this_arg, args...)` and `e.apply(this_arg, args)` are the
+    /// methods of Function.prototype when `e` is a function, so we call `e`
+    /// with `this_arg` directly. Otherwise, they are ordinary methods:
+    ///
+    /// ```javascript
+    /// var $fun = e; var $arg = this_arg; ...
+    /// var $r = undefined;
+    /// if (typeof $fun === "function") { $r = $fun($arg, ...); }
+    /// else { $r = $fun.call($fun, $arg, ...); }
+    /// ```
+    fn call_or_apply(&mut self, f: &mut Expr, args: &mut Vec<Expr>, s: &Pos, loc: &Loc) -> Expr {
+        let (fun, method) = match f {
+            Expr::Dot(fun, method, _) => (fun.take(), method.clone()),
+            _ => unreachable!("expected a method call"),
+        };
+        let fun = self.name("fun4call", fun, s, loc);
+        let args = std::mem::take(args)
+            .into_iter()
+            .map(|arg| self.name("arg4call", arg, s, loc))
+            .collect::<Vec<_>>();
+        let result = self.ng.fresh("call_result");
+        let cxt = loc.enclosing_block().unwrap();
+        cxt.insert(cxt.index, vardecl1_(result.clone(), UNDEFINED_, s.clone()));
 
-                // args => $obj, args...
-                args.insert(0, id_(obj_name.clone(), s.clone()));
+        let mut method_args = args.clone();
+        method_args.insert(0, fun.clone());
+        let method_call = call_(
+            dot_(fun.clone(), method.clone(), s.clone()),
+            method_args,
+            s.clone(),
+        );
 
-                // generate constructor call
-                let new_call = call_(
-                    Expr::Id(func_name.clone(), s.clone()),
-                    std::mem::replace(args, vec![]),
+        let this_arg = args.first().cloned().unwrap_or(UNDEFINED_);
+        let direct_call = match (&method, args.get(1)) {
+            // f.apply(this_arg, args) => __JNKS.apply(f, this_arg, args)
+            (Id::Named(m), Some(arr)) if m == "apply" => {
+                let jnks = id_("__JNKS", s.clone());
+                call_(
+                    dot_(jnks.clone(), "apply", s.clone()),
+                    vec![jnks, fun.clone(), this_arg, arr.clone()],
                     s.clone(),
-                );
+                )
+            }
+            (Id::Named(m), _) if m == "apply" => call_(fun.clone(), vec![this_arg], s.clone()),
+            _ => {
+                let mut args = args;
+                if args.is_empty() {
+                    args.push(UNDEFINED_);
+                }
+                call_(fun.clone(), args, s.clone())
+            }
+        };
 
-                // insert that into the surrounding syntax block
-                cxt.insert(cxt.index, expr_(new_call, s.clone()));
+        let is_function = binary_(
+            BinOp::BinaryOp(BinaryOp::StrictEqual),
+            unary_(UnaryOp::TypeOf, fun, s.clone()),
+            str_("function", s.clone()),
+            s.clone(),
+        );
+        let assign_result = |e| {
+            let assign = assign_(result.clone(), e, s.clone());
+            Stmt::Block(vec![expr_(assign, s.clone())], s.clone())
+        };
+        cxt.insert(
+            cxt.index,
+            if_(
+                is_function,
+                assign_result(direct_call),
+                assign_result(method_call),
+                s.clone(),
+            ),
+        );
+        id_(result, s.clone())
+    }
 
-                // make the entire `new` expression evaluate to just the new object
-                *expr = id_(obj_name, s.clone());
+    /// The `this` argument for a call to `f`
+    fn this_of_callee(&mut self, f: &mut Expr, loc: &Loc) -> Expr {
+        match f {
+            // only syntactically immediate bracket/dot preserves the
+            // object as `this`
+            Expr::Bracket(obj, _, s) | Expr::Dot(obj, _, s) => {
+                // fresh the obj so we can pass it to the method
+                let cxt = loc.enclosing_block().unwrap();
+                let obj_name = self.ng.fresh("obj4this");
+                cxt.insert(
+                    cxt.index,
+                    vardecl1_(obj_name.clone(), obj.take(), s.clone()),
+                );
+                **obj = id_(obj_name.clone(), s.clone());
+                id_(obj_name, s.clone())
             }
-            Expr::Func(_, params, _, _) => {
-                // yes for once using a named id is correct here, because
-                // it's a special name that may or may not be used by the body
-                params.insert(0, self.this_name.clone());
+            // for the rest, we'll hand undefined. all our benchmarks
+            // play nice with "use strict";
+            _ => UNDEFINED_,
+        }
+    }
+}
+
+/// `__JNKS.variadic(f)`, where `f` has a rest parameter, which is its only
+/// parameter after `this`. It receives an array of its arguments, even when
+/// it is called with a different number of arguments than it takes (see
+/// `desugar_params`)
+fn variadic_(f: Expr, s: &Pos) -> Expr {
+    jnks_(s, "variadic", vec![f])
+}
+
+/// `__JNKS.f(args...)` after `this` is desugared, so the arguments start
+/// with `__JNKS` as `this`
+fn jnks_(s: &Pos, f: &str, mut args: Vec<Expr>) -> Expr {
+    let jnks = id_("__JNKS", s.clone());
+    args.insert(0, jnks.clone());
+    call_(dot_(jnks, f, s.clone()), args, s.clone())
+}
+
+/// Whether the body of a function mentions `new.target`, including within
+/// its arrows, which do not have their own
+fn mentions_new_target(body: &mut Stmt) -> bool {
+    struct MentionsNewTarget {
+        /// The number of enclosing functions that are not arrows
+        depth: usize,
+        found: bool,
+    }
+    impl Visitor for MentionsNewTarget {
+        fn enter_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+            match expr {
+                Expr::Func(..) => self.depth += 1,
+                Expr::NewTarget if self.depth == 0 => self.found = true,
+                _ => (),
             }
-            Expr::This => {
-                *expr = id_(self.this_name.clone(), Default::default());
+        }
+        fn exit_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
+            if let Expr::Func(..) = expr {
+                self.depth -= 1;
             }
-            _ => (),
         }
     }
+    let mut v = MentionsNewTarget {
+        depth: 0,
+        found: false,
+    };
+    body.walk(&mut v);
+    v.found
+}
+
+/// Whether `f` is `e.method`
+fn is_method(f: &Expr, method: &str) -> bool {
+    matches!(f, Expr::Dot(_, Id::Named(m), _) if m == method)
 }
 
 pub fn desugar_this(program: &mut Stmt, ng: &mut NameGen) {
     let mut v = ThisParameter {
         this_name: ng.fresh("this"),
         ng,
+        new_targets: vec![],
     };
     program.walk(&mut v);
 }
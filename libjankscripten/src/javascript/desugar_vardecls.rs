@@ -1,4 +1,3 @@
-use super::constructors::*;
 use super::syntax::*;
 use super::*;
 
@@ -19,13 +18,10 @@ impl Visitor for SeparateVarDecls {
 
                     // insert previous decls in order above stmt
                     for decl in decls.drain(0..) {
-                        block_ctx.insert(
-                            block_ctx.index,
-                            vardecl1_(decl.name, *decl.named, s.clone()),
-                        );
+                        block_ctx.insert(block_ctx.index, Stmt::VarDecl(vec![decl], s.clone()));
                     }
 
-                    *stmt = vardecl1_(last_decl.name, *last_decl.named, s.clone());
+                    *stmt = Stmt::VarDecl(vec![last_decl], s.clone());
                 }
             }
             _ => {
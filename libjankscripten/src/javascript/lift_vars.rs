@@ -12,6 +12,9 @@ struct LiftVars;
 impl Visitor for LiftVars {
     fn exit_stmt(&mut self, stmt: &mut Stmt, loc: &Loc) {
         match stmt {
+            // a `let` within a loop is a fresh binding on each iteration, and
+            // `block_scoping` has already made its name unique
+            Stmt::VarDecl(decl, _) if decl[0].kind != VarKind::Var && loc.in_loop() => (),
             Stmt::VarDecl(decl, s) => {
                 let decl1 = decl.pop().expect("no decls in vardecl");
                 assert_eq!(decl.pop(), None, "vardecls not desugared");
@@ -20,12 +23,6 @@ impl Visitor for LiftVars {
                     .insert(0, new_decl);
                 *stmt = expr_(assign_(decl1.name, *decl1.named, s.clone()), s.clone());
             }
-            Stmt::ForIn(is_var, bind, container, _, s) if *is_var => {
-                let new_decl = vardecl1_(bind.clone(), container.take(), s.clone());
-                let block_context = loc.body_of_enclosing_function_or_program();
-                block_context.insert(block_context.index, new_decl);
-                *is_var = false;
-            }
             _ => (),
         }
     }
@@ -2,18 +2,26 @@
 //! and desugaring.
 
 mod add_blocks;
+mod block_scoping;
 pub mod constructors;
 mod desugar;
+mod desugar_arguments;
 mod desugar_bracket_str;
+mod desugar_classes;
+mod desugar_destructuring;
+mod desugar_finally;
 mod desugar_function_applications;
 mod desugar_function_stmts;
+mod desugar_generators;
 mod desugar_logical;
 mod desugar_loops;
+mod desugar_params;
 mod desugar_switch;
 mod desugar_this;
 mod desugar_updates;
 mod desugar_vardecls;
 mod lift_vars;
+mod modules;
 mod normalize_std_lib_calls;
 mod parser;
 mod resugar_method_call;
@@ -22,6 +30,7 @@ pub mod walk;
 
 pub use crate::shared::NameGen;
 pub use desugar::*;
+pub use modules::*;
 pub use parser::*;
 pub use syntax::*;
 pub use walk::*;
@@ -0,0 +1,522 @@
+//! link the ES modules of a program into one script
+//!
+//! Starting from the entry module, we load each module that is imported,
+//! resolving its path relative to the module that imports it. The script has
+//! the body of each module, in the order that ECMAScript evaluates them: a
+//! module runs after the modules that it imports, and the entry module runs
+//! last.
+//!
+//! Every top-level variable of a module gets a unique name, so that modules
+//! cannot see each other's variables. An imported variable is renamed to the
+//! unique name of the variable that it refers to, so imports are live
+//! bindings:
+//!
+//! ```javascript
+//! // counter.js
+//! export let count = 0;
+//! export function incr() { count++; }
+//! // main.js
+//! import { count, incr } from "./counter.js";
+//! incr();
+//! console.log(count);
+//! ```
+//!
+//! becomes
+//!
+//! ```javascript
+//! let $module_0 = 0;
+//! function $module_1() { $module_0++; }
+//! $module_1();
+//! console.log($module_0);
+//! ```
+//!
+//! `import * as m` refers to an object that holds the exports of the module.
+//! We create it after the module runs, so its properties are not live.
+//!
+//! A program that is a single file without imports or exports is not renamed.
+
+use super::block_scoping::{rename_module, vars_of};
+use super::constructors::*;
+use super::desugar::DesugarError;
+use super::parser::{parse_module, ParseError};
+use super::syntax::*;
+use super::NameGen;
+use crate::pos::Pos;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::rc::Rc;
+use swc_common::SourceMap;
+use thiserror::Error;
+
+/// An error in loading or linking the modules of a program
+#[derive(Debug, Error)]
+pub enum ModuleError {
+    #[error("error reading {}: {1}", .0.display())]
+    Io(PathBuf, std::io::Error),
+    #[error("{}: {1}", .0.display())]
+    Parse(PathBuf, ParseError),
+    #[error("cannot import {0} at {1}: only relative paths are supported")]
+    BareSpecifier(String, Pos),
+    #[error("{} does not export {1}, which is imported at {2}", .0.display())]
+    MissingExport(PathBuf, Id, Pos),
+    #[error("export of undeclared variable {0} at {1}")]
+    UndeclaredExport(Id, Pos),
+    #[error(transparent)]
+    Rename(#[from] DesugarError),
+}
+
+pub type ModuleResult<T> = Result<T, ModuleError>;
+
+/// Link the program whose entry module is at `entry` into one script. `load`
+/// reads the source code of a module.
+pub fn link_modules<L>(entry: &Path, load: L, ng: &mut NameGen) -> ModuleResult<Stmt>
+where
+    L: FnMut(&Path) -> std::io::Result<String>,
+{
+    let mut linker = Linker {
+        load,
+        source_map: Default::default(),
+        paths: HashMap::new(),
+        modules: vec![],
+        order: vec![],
+    };
+    linker.load(normalize(entry))?;
+    let entry = &linker.modules[0].module;
+    if linker.modules.len() == 1 && entry.imports.is_empty() && entry.exports.is_empty() {
+        let body = linker.modules.pop().unwrap().module.body;
+        return Ok(Stmt::Block(body, Default::default()));
+    }
+    linker.link(ng)
+}
+
+struct Linker<L> {
+    load: L,
+    source_map: Rc<SourceMap>,
+    /// The index of each module that we have loaded
+    paths: HashMap<PathBuf, usize>,
+    modules: Vec<Loaded>,
+    /// The indices of the modules, in the order that they run
+    order: Vec<usize>,
+}
+
+struct Loaded {
+    path: PathBuf,
+    module: Module,
+    /// The index of the module that each request refers to
+    resolved: HashMap<String, usize>,
+}
+
+/// What an imported name refers to
+#[derive(Clone)]
+enum Binding {
+    /// A top-level variable of a module, by its original name
+    Var(usize, Id),
+    /// The namespace object of a module
+    Namespace(usize),
+}
+
+/// What a top-level variable is renamed to
+pub(super) struct Renamed {
+    pub name: Id,
+    pub is_import: bool,
+}
+
+impl<L> Linker<L>
+where
+    L: FnMut(&Path) -> std::io::Result<String>,
+{
+    /// Load the module at `path` and the modules that it imports
+    fn load(&mut self, path: PathBuf) -> ModuleResult<usize> {
+        if let Some(index) = self.paths.get(&path) {
+            // either already loaded, or part of a cycle
+            return Ok(*index);
+        }
+        let code = (self.load)(&path).map_err(|err| ModuleError::Io(path.clone(), err))?;
+        let module = parse_module(&self.source_map, &path.to_string_lossy(), &code)
+            .map_err(|err| ModuleError::Parse(path.clone(), err))?;
+        let index = self.modules.len();
+        self.paths.insert(path.clone(), index);
+        let requests = module.requests.clone();
+        self.modules.push(Loaded {
+            path: path.clone(),
+            module,
+            resolved: HashMap::new(),
+        });
+        for (request, pos) in requests {
+            let dep = self.load(resolve(&path, &request, &pos)?)?;
+            self.modules[index].resolved.insert(request, dep);
+        }
+        self.order.push(index);
+        Ok(index)
+    }
+
+    fn link(mut self, ng: &mut NameGen) -> ModuleResult<Stmt> {
+        // a fresh name for the top-level variables of each module
+        let mut names = vec![];
+        for loaded in self.modules.iter_mut() {
+            let mut body = Stmt::Block(loaded.module.body.split_off(0), Default::default());
+            let mut module_names = HashMap::new();
+            for x in top_level_vars(&mut body) {
+                module_names.entry(x).or_insert_with(|| ng.fresh("module"));
+            }
+            loaded.module.body = match body {
+                Stmt::Block(stmts, _) => stmts,
+                _ => unreachable!(),
+            };
+            names.push(module_names);
+        }
+
+        // an export of a variable that the module neither declares nor
+        // imports is an error
+        for (index, loaded) in self.modules.iter().enumerate() {
+            for export in loaded.module.exports.iter() {
+                if let Export::Local(_, local, pos) = export {
+                    let imported = loaded.module.imports.iter().any(|i| &i.local == local);
+                    if !imported && !names[index].contains_key(local) {
+                        return Err(ModuleError::UndeclaredExport(local.clone(), pos.clone()));
+                    }
+                }
+            }
+        }
+
+        let mut namespaces = HashMap::new();
+        let mut renames = vec![];
+        for (index, loaded) in self.modules.iter().enumerate() {
+            let mut module_renames = names[index]
+                .iter()
+                .map(|(x, name)| {
+                    let renamed = Renamed {
+                        name: name.clone(),
+                        is_import: false,
+                    };
+                    (x.clone(), renamed)
+                })
+                .collect::<HashMap<_, _>>();
+            for import in loaded.module.imports.iter() {
+                let dep = loaded.resolved[&import.source];
+                let binding = match &import.name {
+                    None => Binding::Namespace(dep),
+                    Some(name) => self.resolve_export(dep, name, &mut vec![]).ok_or_else(|| {
+                        let path = self.modules[dep].path.clone();
+                        ModuleError::MissingExport(path, name.clone(), import.pos.clone())
+                    })?,
+                };
+                let name = self.binding_var(binding, &names, &mut namespaces, ng);
+                let renamed = Renamed {
+                    name,
+                    is_import: true,
+                };
+                module_renames.insert(import.local.clone(), renamed);
+            }
+            renames.push(module_renames);
+        }
+
+        // namespace objects can refer to other namespace objects, so we
+        // create them until there are no new ones
+        let mut namespace_objects = HashMap::new();
+        while let Some(index) = namespaces
+            .keys()
+            .find(|index| !namespace_objects.contains_key(*index))
+            .cloned()
+        {
+            let mut props = vec![];
+            for name in self.export_names(index, &mut vec![]) {
+                let binding = self
+                    .resolve_export(index, &name, &mut vec![])
+                    .expect("exported name does not resolve");
+                let x = self.binding_var(binding, &names, &mut namespaces, ng);
+                props.push((Key::Str(name.to_string()), id_(x, Pos::UNKNOWN)));
+            }
+            namespace_objects.insert(index, Expr::Object(props, Pos::UNKNOWN));
+        }
+
+        let mut program = vec![];
+        for index in self.order.iter() {
+            let mut body = Stmt::Block(self.modules[*index].module.body.split_off(0), Pos::UNKNOWN);
+            rename_module(&mut body, &renames[*index], ng)?;
+            match body {
+                Stmt::Block(stmts, _) => program.extend(stmts),
+                _ => unreachable!(),
+            }
+            if let Some(object) = namespace_objects.remove(index) {
+                program.push(vardecl1_(namespaces[index].clone(), object, Pos::UNKNOWN));
+            }
+        }
+        Ok(Stmt::Block(program, Pos::UNKNOWN))
+    }
+
+    /// The unique name of the variable that holds `binding`
+    fn binding_var(
+        &self,
+        binding: Binding,
+        names: &[HashMap<Id, Id>],
+        namespaces: &mut HashMap<usize, Id>,
+        ng: &mut NameGen,
+    ) -> Id {
+        match binding {
+            Binding::Var(index, x) => names[index][&x].clone(),
+            Binding::Namespace(index) => namespaces
+                .entry(index)
+                .or_insert_with(|| ng.fresh("namespace"))
+                .clone(),
+        }
+    }
+
+    /// What the module at `index` exports as `name`. `visited` holds the
+    /// names that we are already resolving, so that cycles of re-exports do
+    /// not resolve.
+    fn resolve_export(
+        &self,
+        index: usize,
+        name: &Id,
+        visited: &mut Vec<(usize, Id)>,
+    ) -> Option<Binding> {
+        if visited.contains(&(index, name.clone())) {
+            return None;
+        }
+        visited.push((index, name.clone()));
+        let loaded = &self.modules[index];
+        for export in loaded.module.exports.iter() {
+            match export {
+                Export::Local(exported, local, _) if exported == name => {
+                    // the module may export a variable that it imported
+                    let import = loaded.module.imports.iter().find(|i| &i.local == local);
+                    return match import {
+                        None => Some(Binding::Var(index, local.clone())),
+                        Some(Import {
+                            name: None, source, ..
+                        }) => Some(Binding::Namespace(loaded.resolved[source])),
+                        Some(Import {
+                            name: Some(imported),
+                            source,
+                            ..
+                        }) => self.resolve_export(loaded.resolved[source], imported, visited),
+                    };
+                }
+                Export::Indirect {
+                    exported,
+                    name: imported,
+                    source,
+                    ..
+                } if exported == name => {
+                    let dep = loaded.resolved[source];
+                    return match imported {
+                        None => Some(Binding::Namespace(dep)),
+                        Some(imported) => self.resolve_export(dep, imported, visited),
+                    };
+                }
+                _ => (),
+            }
+        }
+        // export * does not re-export the default export
+        if name.to_string() == "default" {
+            return None;
+        }
+        for export in loaded.module.exports.iter() {
+            if let Export::Star(source) = export {
+                let dep = loaded.resolved[source];
+                if let Some(binding) = self.resolve_export(dep, name, visited) {
+                    return Some(binding);
+                }
+            }
+        }
+        None
+    }
+
+    /// The names that the module at `index` exports
+    fn export_names(&self, index: usize, visited: &mut Vec<usize>) -> Vec<Id> {
+        if visited.contains(&index) {
+            return vec![];
+        }
+        visited.push(index);
+        let loaded = &self.modules[index];
+        let mut names = vec![];
+        for export in loaded.module.exports.iter() {
+            match export {
+                Export::Local(exported, ..) | Export::Indirect { exported, .. } => {
+                    names.push(exported.clone())
+                }
+                Export::Star(source) => {
+                    let dep = loaded.resolved[source];
+                    for name in self.export_names(dep, visited) {
+                        if name.to_string() != "default" && !names.contains(&name) {
+                            names.push(name);
+                        }
+                    }
+                }
+            }
+        }
+        names
+    }
+}
+
+/// The path of the module that `request` refers to, within the module at
+/// `importer`
+fn resolve(importer: &Path, request: &str, pos: &Pos) -> ModuleResult<PathBuf> {
+    if !(request.starts_with("./") || request.starts_with("../") || request.starts_with('/')) {
+        return Err(ModuleError::BareSpecifier(request.to_string(), pos.clone()));
+    }
+    let dir = importer.parent().unwrap_or_else(|| Path::new(""));
+    Ok(normalize(&dir.join(request)))
+}
+
+/// Remove the `.` and `..` components of `path`, so that a module has the
+/// same path no matter how it is imported
+fn normalize(path: &Path) -> PathBuf {
+    let mut normal = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir
+                if matches!(normal.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                normal.pop();
+            }
+            _ => normal.push(component),
+        }
+    }
+    normal
+}
+
+/// The variables that are declared at the top-level of a module
+fn top_level_vars(body: &mut Stmt) -> Vec<Id> {
+    let mut vars = vars_of(body);
+    if let Stmt::Block(stmts, _) = body {
+        for stmt in stmts {
+            if let Stmt::VarDecl(decls, _) = stmt {
+                vars.extend(decls.iter().map(|d| d.name.clone()));
+            }
+        }
+    }
+    vars
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::javascript::testing::expect_result;
+
+    /// Link the program whose entry module is `main.js`, where `files` has
+    /// the path and code of each module
+    fn link(files: &[(&str, &str)]) -> ModuleResult<Stmt> {
+        let files: HashMap<PathBuf, String> = files
+            .iter()
+            .map(|(path, code)| (PathBuf::from(path), code.to_string()))
+            .collect();
+        let load = |path: &Path| {
+            files
+                .get(path)
+                .cloned()
+                .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "no such module"))
+        };
+        link_modules(Path::new("main.js"), load, &mut NameGen::default())
+    }
+
+    #[test]
+    fn live_bindings() {
+        let program = link(&[
+            (
+                "main.js",
+                r#"import { n, incr } from "./lib/counter.js";
+                   incr(); incr(); n"#,
+            ),
+            (
+                "lib/counter.js",
+                "export let n = 0; export function incr() { n++; }",
+            ),
+        ])
+        .unwrap();
+        expect_result(&program, "2");
+    }
+
+    #[test]
+    fn top_level_vars_do_not_collide() {
+        let program = link(&[
+            (
+                "main.js",
+                r#"import f from "./a.js"; import { g } from "./b/../b.js";
+                   var x = "main"; f() + g() + x"#,
+            ),
+            (
+                "a.js",
+                r#"var x = "a"; export default function () { return x; }"#,
+            ),
+            ("b.js", r#"let x = "b"; export const g = () => x;"#),
+        ])
+        .unwrap();
+        expect_result(&program, "abmain");
+    }
+
+    #[test]
+    fn shadowed_imports_are_not_renamed() {
+        let program = link(&[
+            (
+                "main.js",
+                r#"import { x } from "./a.js";
+                   function f(x) { return x; }
+                   function g() { var x = 2; return x; }
+                   function h() { try { throw 3; } catch (x) { return x; } }
+                   function k() { { let x = 4; return x; } }
+                   function m() { for (const x of [5]) { return x; } }
+                   [x, f(1), g(), h(), k(), m()].toString()"#,
+            ),
+            ("a.js", "export var x = 0;"),
+        ])
+        .unwrap();
+        expect_result(&program, "0,1,2,3,4,5");
+    }
+
+    #[test]
+    fn namespaces_and_re_exports() {
+        let program = link(&[
+            (
+                "main.js",
+                r#"import * as m from "./m.js"; import { y as z } from "./m.js";
+                   m.x + m.y + z + m.default + m.inner.x"#,
+            ),
+            (
+                "m.js",
+                r#"export * from "./a.js"; export { x as y } from "./a.js";
+                   export * as inner from "./a.js"; export default 100;"#,
+            ),
+            ("a.js", "export const x = 1; export default 1000;"),
+        ])
+        .unwrap();
+        expect_result(&program, "104");
+    }
+
+    #[test]
+    fn missing_export() {
+        let err = link(&[
+            ("main.js", r#"import { y } from "./a.js";"#),
+            ("a.js", "export const x = 1;"),
+        ])
+        .unwrap_err();
+        assert!(matches!(err, ModuleError::MissingExport(..)));
+    }
+
+    #[test]
+    fn assign_to_import() {
+        let err = link(&[
+            ("main.js", r#"import { x } from "./a.js"; x = 2;"#),
+            ("a.js", "export let x = 1;"),
+        ])
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            ModuleError::Rename(DesugarError::AssignToImport(..))
+        ));
+    }
+
+    #[test]
+    fn undeclared_export() {
+        let err = link(&[("main.js", "export { nope };")]).unwrap_err();
+        assert!(matches!(err, ModuleError::UndeclaredExport(..)));
+    }
+
+    #[test]
+    fn bare_specifier() {
+        let err = link(&[("main.js", r#"import fs from "fs";"#)]).unwrap_err();
+        assert!(matches!(err, ModuleError::BareSpecifier(..)));
+    }
+}
@@ -9,38 +9,58 @@
 //!
 //! parseInt => default radix insertion (10)
 //! Error => default message insertion ("")
+//! setTimeout, setInterval => default delay insertion (0), and the arguments
+//! after the delay are passed as one array, which the callback is applied to
+//! clearTimeout, clearInterval => default id insertion (undefined)
+//!
+//! extra arguments to the rest are evaluated and ignored
+//!
+//! promises are the same, except that it is a method called `then` rather
+//! than a function that we assume is the library method:
+//!
+//! e.then(f) => default on_rejected insertion (undefined)
+//! Promise.resolve() => default value insertion (undefined)
+//!
+//! the callbacks that are given to the library need no normalization, since
+//! a function that is called through an any may take any number of
+//! parameters
 use super::constructors::*;
 use super::syntax::*;
 use super::*;
+use crate::pos::Pos;
 
 struct NormalizeStdLibCalls;
 
 impl Visitor for NormalizeStdLibCalls {
     fn exit_expr(&mut self, expr: &mut Expr, _loc: &Loc) {
         match expr {
+            Expr::Call(f, args, _) if is_method(f, "then") && args.len() == 1 => {
+                args.push(UNDEFINED_);
+            }
+            Expr::Call(f, args, _) if is_promise_resolve(f) && args.is_empty() => {
+                args.push(UNDEFINED_);
+            }
             // why not do this after `new` desugaring? because then we lose the
             // name of the call because of how we happen to do it
             Expr::Call(f, args, s) | Expr::New(f, args, s) => {
                 if let Expr::Id(Id::Named(id), _) = &**f {
                     match &id[..] {
-                        "parseInt" => {
-                            match args.len() {
-                                // default radix of 10
-                                1 => args.push(int_(10, s.clone())),
-                                // perfect already
-                                2 => (),
-                                got => panic!("why was parseInt given {} arguments", got),
-                            }
-                        }
-                        "Error" => {
-                            match args.len() {
-                                // default message of empty
-                                0 => args.push(str_("", s.clone())),
-                                // perfect already
-                                1 => (),
-                                got => panic!("why was {} given {} arguments at {}", id, got, s),
+                        // default radix of 10
+                        "parseInt" => fit_args(args, 2, || int_(10, s.clone()), s),
+                        // default message of empty
+                        "Error" => fit_args(args, 1, || str_("", s.clone()), s),
+                        "setTimeout" | "setInterval" => {
+                            // default delay of 0
+                            if args.len() < 2 {
+                                args.resize_with(1, || UNDEFINED_);
+                                args.push(int_(0, s.clone()));
                             }
+                            // the rest are the arguments of the callback
+                            let callback_args = args.split_off(2);
+                            args.push(Expr::Array(callback_args, s.clone()));
                         }
+                        // clears nothing
+                        "clearTimeout" | "clearInterval" => fit_args(args, 1, || UNDEFINED_, s),
                         _ => (),
                     }
                 }
@@ -50,6 +70,37 @@ impl Visitor for NormalizeStdLibCalls {
     }
 }
 
+/// Gives `args` exactly `n` arguments, where `n > 0`. Missing arguments are
+/// `default()`. Extra arguments are ignored, but they are still evaluated
+/// after the others, so the last argument that is kept becomes
+/// `[arg, extra, ...][0]`
+pub(super) fn fit_args(args: &mut Vec<Expr>, n: usize, default: impl Fn() -> Expr, s: &Pos) {
+    if args.len() > n {
+        let rest = args.split_off(n - 1);
+        args.push(bracket_(
+            Expr::Array(rest, s.clone()),
+            int_(0, s.clone()),
+            s.clone(),
+        ));
+    }
+    args.resize_with(n, default);
+}
+
+/// Whether `f` is `e.method`
+fn is_method(f: &Expr, method: &str) -> bool {
+    matches!(f, Expr::Dot(_, Id::Named(m), _) if m == method)
+}
+
+/// Whether `f` is `Promise.resolve`
+fn is_promise_resolve(f: &Expr) -> bool {
+    match f {
+        Expr::Dot(e, Id::Named(m), _) if m == "resolve" => {
+            matches!(&**e, Expr::Id(Id::Named(id), _) if id == "Promise")
+        }
+        _ => false,
+    }
+}
+
 pub fn normalize_std_lib_calls(program: &mut Stmt) {
     program.walk(&mut NormalizeStdLibCalls);
 }
@@ -7,9 +7,9 @@ use super::constructors::*;
 use super::syntax as S;
 use crate::pos::Pos;
 use std::rc::Rc;
-use swc_common::{FileName, SourceMap, Span};
+use swc_common::{FileName, SourceMap, Span, Spanned};
 use swc_ecma_ast as swc;
-use swc_ecma_parser::{lexer, Parser, StringInput, Syntax};
+use swc_ecma_parser::{lexer, EsConfig, Parser, StringInput, Syntax};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -104,6 +104,223 @@ fn parse_id(ident: swc::Ident) -> S::Id {
     S::Id::Named(ident.sym.to_string())
 }
 
+/// Parse an ES module. All the modules of a program are registered with the
+/// same `source_map`, so that their positions are distinct.
+pub(crate) fn parse_module(
+    source_map: &Rc<SourceMap>,
+    src_name: &str,
+    js_code: &str,
+) -> ParseResult<S::Module> {
+    let source_file =
+        source_map.new_source_file(FileName::Custom(src_name.to_string()), js_code.into());
+    let lexer = lexer::Lexer::new(
+        // `export * as m from "m.js"` is part of ES2020
+        Syntax::Es(EsConfig {
+            export_namespace_from: true,
+            ..Default::default()
+        }),
+        Default::default(),
+        StringInput::from(&*source_file),
+        None,
+    );
+    let mut parser = Parser::new_from(lexer);
+    let module = match parser.parse_module() {
+        Ok(m) => m,
+        Err(e) => return Err(ParseError::SWC(e)),
+    };
+
+    let mut parsed = S::Module {
+        body: vec![],
+        requests: vec![],
+        imports: vec![],
+        exports: vec![],
+    };
+    for item in module.body {
+        match item {
+            swc::ModuleItem::Stmt(stmt) => parsed.body.push(parse_stmt(stmt, source_map)?),
+            swc::ModuleItem::ModuleDecl(decl) => parse_module_decl(decl, &mut parsed, source_map)?,
+        }
+    }
+    Ok(parsed)
+}
+
+/// The name of the variable that holds a default export
+fn default_id() -> S::Id {
+    S::Id::Named("*default*".to_string())
+}
+
+/// Parse an import or export declaration into `module`
+fn parse_module_decl(
+    decl: swc::ModuleDecl,
+    module: &mut S::Module,
+    source_map: &Rc<SourceMap>,
+) -> ParseResult<()> {
+    use swc::ModuleDecl::*;
+    match decl {
+        Import(swc::ImportDecl {
+            span,
+            specifiers,
+            src,
+            ..
+        }) => {
+            let p = Pos::from_swc(source_map, span);
+            let source = src.value.to_string();
+            module.requests.push((source.clone(), p.clone()));
+            for specifier in specifiers {
+                let (local, name) = match specifier {
+                    swc::ImportSpecifier::Named(swc::ImportNamedSpecifier {
+                        local,
+                        imported,
+                        ..
+                    }) => {
+                        let name = parse_id(imported.unwrap_or_else(|| local.clone()));
+                        (local, Some(name))
+                    }
+                    swc::ImportSpecifier::Default(swc::ImportDefaultSpecifier {
+                        local, ..
+                    }) => (local, Some(S::Id::Named("default".to_string()))),
+                    swc::ImportSpecifier::Namespace(swc::ImportStarAsSpecifier {
+                        local, ..
+                    }) => (local, None),
+                };
+                module.imports.push(S::Import {
+                    local: parse_id(local),
+                    name,
+                    source: source.clone(),
+                    pos: p.clone(),
+                });
+            }
+        }
+        ExportDecl(swc::ExportDecl { decl, span }) => {
+            let p = Pos::from_swc(source_map, span);
+            let stmt = parse_decl(decl, source_map)?;
+            match &stmt {
+                S::Stmt::VarDecl(decls, _) => {
+                    for d in decls {
+                        let export = S::Export::Local(d.name.clone(), d.name.clone(), p.clone());
+                        module.exports.push(export);
+                    }
+                }
+                S::Stmt::Func(name, ..) => {
+                    let export = S::Export::Local(name.clone(), name.clone(), p);
+                    module.exports.push(export);
+                }
+                _ => unreachable!("parse_decl produced {:?}", stmt),
+            }
+            module.body.push(stmt);
+        }
+        ExportNamed(swc::NamedExport {
+            span,
+            specifiers,
+            src,
+            ..
+        }) => {
+            let p = Pos::from_swc(source_map, span);
+            let source = src.map(|s| s.value.to_string());
+            if let Some(source) = &source {
+                module.requests.push((source.clone(), p.clone()));
+            }
+            for specifier in specifiers {
+                let (name, exported) = match specifier {
+                    swc::ExportSpecifier::Named(swc::ExportNamedSpecifier {
+                        orig,
+                        exported,
+                        ..
+                    }) => {
+                        let exported = parse_id(exported.unwrap_or_else(|| orig.clone()));
+                        (Some(parse_id(orig)), exported)
+                    }
+                    swc::ExportSpecifier::Namespace(swc::ExportNamespaceSpecifier {
+                        name, ..
+                    }) => (None, parse_id(name)),
+                    swc::ExportSpecifier::Default(_) => return unsupported!(span, source_map),
+                };
+                let export = match (&source, name) {
+                    (Some(source), name) => S::Export::Indirect {
+                        exported,
+                        name,
+                        source: source.clone(),
+                        pos: p.clone(),
+                    },
+                    (None, Some(local)) => S::Export::Local(exported, local, p.clone()),
+                    (None, None) => return unsupported!(span, source_map),
+                };
+                module.exports.push(export);
+            }
+        }
+        ExportDefaultDecl(swc::ExportDefaultDecl { span, decl }) => {
+            // a function declaration is hoisted, even if it is anonymous, so
+            // it stays a declaration
+            let stmt = match decl {
+                swc::DefaultDecl::Fn(swc::FnExpr { ident, function }) => {
+                    let name = ident.map(parse_id).unwrap_or_else(default_id);
+                    let span = function.span;
+                    let (params, rest, body) = parse_function(function, source_map)?;
+                    let p = Pos::from_swc(source_map, span);
+                    S::Stmt::Func(name, params, rest, Box::new(body), p)
+                }
+                swc::DefaultDecl::Class(swc::ClassExpr {
+                    ident: Some(ident),
+                    class,
+                }) => parse_decl(
+                    swc::Decl::Class(swc::ClassDecl {
+                        ident,
+                        declare: false,
+                        class,
+                    }),
+                    source_map,
+                )?,
+                swc::DefaultDecl::Class(class) => {
+                    let e = parse_expr(swc::Expr::Class(class), source_map)?;
+                    let p = Pos::from_swc(source_map, span);
+                    const_decl(default_id(), e, p)
+                }
+                swc::DefaultDecl::TsInterfaceDecl(_) => return unsupported!(span, source_map),
+            };
+            let local = match &stmt {
+                S::Stmt::Func(name, ..) => name.clone(),
+                S::Stmt::VarDecl(decls, _) => decls[0].name.clone(),
+                _ => unreachable!("default export produced {:?}", stmt),
+            };
+            let p = Pos::from_swc(source_map, span);
+            let export = S::Export::Local(S::Id::Named("default".to_string()), local, p);
+            module.exports.push(export);
+            module.body.push(stmt);
+        }
+        ExportDefaultExpr(swc::ExportDefaultExpr { span, expr }) => {
+            let e = parse_expr(*expr, source_map)?;
+            let p = Pos::from_swc(source_map, span);
+            module.body.push(const_decl(default_id(), e, p.clone()));
+            let export = S::Export::Local(S::Id::Named("default".to_string()), default_id(), p);
+            module.exports.push(export);
+        }
+        ExportAll(swc::ExportAll { span, src }) => {
+            let source = src.value.to_string();
+            let p = Pos::from_swc(source_map, span);
+            module.requests.push((source.clone(), p));
+            module.exports.push(S::Export::Star(source));
+        }
+        TsImportEquals(swc::TsImportEqualsDecl { span, .. })
+        | TsExportAssignment(swc::TsExportAssignment { span, .. })
+        | TsNamespaceExport(swc::TsNamespaceExportDecl { span, .. }) => {
+            return unsupported!(span, source_map)
+        }
+    }
+    Ok(())
+}
+
+/// `const x = e;`
+fn const_decl(x: S::Id, e: S::Expr, p: Pos) -> S::Stmt {
+    S::Stmt::VarDecl(
+        vec![S::VarDecl {
+            name: x,
+            named: Box::new(e),
+            kind: S::VarKind::Const,
+        }],
+        p,
+    )
+}
+
 /// Parse an entire swc script
 fn parse_script(script: swc::Script, source_map: &Rc<SourceMap>) -> ParseResult<S::Stmt> {
     let mut stmts = parse_stmts(script.body, source_map)?;
@@ -174,18 +391,11 @@ fn parse_stmt(stmt: swc::Stmt, source_map: &Rc<SourceMap>) -> ParseResult<S::Stm
                     declare,
                     decls,
                 })) => {
-                    if kind != swc::VarDeclKind::Var {
-                        return unsupported_message(
-                            "only var-declared variables are supported",
-                            span,
-                            source_map,
-                        );
-                    }
                     let decls: ParseResult<Vec<_>> = decls
                         .into_iter()
-                        .map(|d| parse_var_declarator(d, source_map))
+                        .map(|d| parse_var_declarator(d, kind, source_map))
                         .collect();
-                    S::ForInit::Decl(decls?)
+                    S::ForInit::Decl(decls?.into_iter().flatten().collect())
                 }
             };
             Ok(for_(
@@ -202,70 +412,7 @@ fn parse_stmt(stmt: swc::Stmt, source_map: &Rc<SourceMap>) -> ParseResult<S::Stm
             body,
             span,
         }) => {
-            // figure out if we're declaring a variable as part of this for in,
-            // or if we're reusing an already-bound identifier. if it's neither
-            // of these, we don't support it.
-            let (is_var, id) = match left {
-                // re-using an already-bound identifier
-                swc::VarDeclOrPat::Pat(swc::Pat::Ident(ident)) => (false, ident),
-                swc::VarDeclOrPat::Pat(swc::Pat::Expr(boxed_expr)) => {
-                    // nested match because you can't match inside boxes without
-                    // nightly rust
-                    match *boxed_expr {
-                        swc::Expr::Ident(ident) => (false, ident),
-                        _ => {
-                            return unsupported_message(
-                                "unsupported expression in a for-in loop declaration",
-                                span,
-                                source_map,
-                            );
-                        }
-                    }
-                }
-
-                // var case
-                swc::VarDeclOrPat::VarDecl(swc::VarDecl {
-                    span,
-                    kind: swc::VarDeclKind::Var, // no `let`
-                    declare: _,
-                    mut decls,
-                }) => {
-                    if decls.len() != 1 {
-                        return unsupported_message(
-                            "only a single var decl is allowed",
-                            span,
-                            source_map,
-                        );
-                    }
-                    match decls.remove(0) {
-                        // a single decl
-                        swc::VarDeclarator {
-                            span: _,
-                            init: None,                   // no initializer
-                            name: swc::Pat::Ident(ident), // no obj destructuring
-                            definite: _,
-                        } => (true, ident),
-                        // any other type of decl
-                        _ => {
-                            return unsupported_message(
-                                "only var decls are allowed here",
-                                span,
-                                source_map,
-                            );
-                        }
-                    }
-                }
-
-                // The program may pattern match on the index, which we do not support.
-                other => {
-                    return unsupported_message(
-                        &format!("unsupported index in a for-in loop: {:?}", other),
-                        span,
-                        source_map,
-                    );
-                }
-            };
-
+            let (is_var, id) = parse_for_binding(left, "for-in", span, source_map)?;
             Ok(forin_(
                 is_var,
                 parse_id(id),
@@ -274,7 +421,25 @@ fn parse_stmt(stmt: swc::Stmt, source_map: &Rc<SourceMap>) -> ParseResult<S::Stm
                 Pos::from_swc(source_map, span),
             ))
         }
-        ForOf(for_of_stmt) => unsupported!(for_of_stmt.span, source_map),
+        ForOf(swc::ForOfStmt {
+            left,
+            right,
+            body,
+            span,
+            await_token,
+        }) => {
+            if let Some(span) = await_token {
+                return unsupported_message("for-await not supported", span, source_map);
+            }
+            let (is_var, id) = parse_for_binding(left, "for-of", span, source_map)?;
+            Ok(S::Stmt::ForOf(
+                is_var,
+                parse_id(id),
+                Box::new(parse_expr(*right, source_map)?),
+                Box::new(parse_stmt(*body, source_map)?),
+                Pos::from_swc(source_map, span),
+            ))
+        }
         If(if_stmt) => {
             // test
             let cond_expr = parse_expr(*if_stmt.test, source_map)?;
@@ -393,6 +558,74 @@ fn parse_stmt(stmt: swc::Stmt, source_map: &Rc<SourceMap>) -> ParseResult<S::Stm
     }
 }
 
+/// Parse the binding of a for-in or for-of loop, which is either an
+/// already-bound identifier or a single declaration without an initializer.
+/// Produces the kind of the declaration for the latter.
+fn parse_for_binding(
+    left: swc::VarDeclOrPat,
+    loop_kind: &str,
+    span: Span,
+    source_map: &Rc<SourceMap>,
+) -> ParseResult<(Option<S::VarKind>, swc::Ident)> {
+    // figure out if we're declaring a variable as part of this loop, or if
+    // we're reusing an already-bound identifier. if it's neither of these, we
+    // don't support it.
+    match left {
+        // re-using an already-bound identifier
+        swc::VarDeclOrPat::Pat(swc::Pat::Ident(ident)) => Ok((None, ident)),
+        swc::VarDeclOrPat::Pat(swc::Pat::Expr(boxed_expr)) => {
+            // nested match because you can't match inside boxes without
+            // nightly rust
+            match *boxed_expr {
+                swc::Expr::Ident(ident) => Ok((None, ident)),
+                _ => unsupported_message(
+                    &format!("unsupported expression in a {} loop declaration", loop_kind),
+                    span,
+                    source_map,
+                ),
+            }
+        }
+
+        // declaration case
+        swc::VarDeclOrPat::VarDecl(swc::VarDecl {
+            span,
+            kind,
+            declare: _,
+            mut decls,
+        }) => {
+            if decls.len() != 1 {
+                return unsupported_message(
+                    "only a single declaration is allowed",
+                    span,
+                    source_map,
+                );
+            }
+            match decls.remove(0) {
+                // a single decl
+                swc::VarDeclarator {
+                    span: _,
+                    init: None,                   // no initializer
+                    name: swc::Pat::Ident(ident), // no obj destructuring
+                    definite: _,
+                } => Ok((Some(parse_var_kind(kind)), ident)),
+                // any other type of decl
+                _ => unsupported_message(
+                    "only a declaration without an initializer is allowed here",
+                    span,
+                    source_map,
+                ),
+            }
+        }
+
+        // The program may pattern match on the index, which we do not support.
+        other => unsupported_message(
+            &format!("unsupported index in a {} loop: {:?}", loop_kind, other),
+            span,
+            source_map,
+        ),
+    }
+}
+
 /// Parse an optional swc statement. This function receives Boxed stmts
 /// because optional stmts are boxed in swc.
 fn parse_opt_stmt(
@@ -409,6 +642,9 @@ fn parse_opt_stmt(
 fn parse_expr(expr: swc::Expr, source_map: &Rc<SourceMap>) -> ParseResult<S::Expr> {
     use swc::Expr::*;
     match expr {
+        Array(swc::ArrayLit { elems, span }) if has_spread(elems.iter().map(Option::as_ref)) => {
+            parse_array_with_spread(elems, Pos::from_swc(source_map, span), source_map)
+        }
         Array(swc::ArrayLit { elems, span }) => {
             let elems: ParseResult<Vec<_>> = elems
                 .into_iter()
@@ -416,7 +652,36 @@ fn parse_expr(expr: swc::Expr, source_map: &Rc<SourceMap>) -> ParseResult<S::Exp
                 .collect();
             Ok(S::Expr::Array(elems?, Pos::from_swc(source_map, span)))
         }
-        Arrow(arrow_expr) => unsupported!(arrow_expr.span, source_map),
+        Arrow(swc::ArrowExpr {
+            params,
+            body,
+            is_async,
+            is_generator,
+            span,
+            ..
+        }) => {
+            let mut body = match body {
+                swc::BlockStmtOrExpr::BlockStmt(block) => parse_block(block, source_map)?,
+                // `x => e` is `x => { return e; }`
+                swc::BlockStmtOrExpr::Expr(e) => {
+                    let e = parse_expr(*e, source_map)?;
+                    S::Stmt::Block(
+                        vec![return_(e, Pos::from_swc(source_map, span))],
+                        Pos::from_swc(source_map, span),
+                    )
+                }
+            };
+            if is_async {
+                body = async_body(body, Pos::from_swc(source_map, span));
+            }
+            let (params, rest) = parse_params(params, &mut body, span, source_map)?;
+            Ok(S::Expr::Arrow(
+                params,
+                rest,
+                Box::new(body),
+                Pos::from_swc(source_map, span),
+            ))
+        }
         Assign(swc::AssignExpr {
             left,
             op,
@@ -424,11 +689,34 @@ fn parse_expr(expr: swc::Expr, source_map: &Rc<SourceMap>) -> ParseResult<S::Exp
             span,
         }) => {
             let op = parse_assign_op(op, span, source_map)?;
-            let left = parse_pat_or_expr(left, span, source_map)?;
             let right = parse_expr(*right, source_map)?;
-            Ok(op_assign_(op, left, right, Pos::from_swc(source_map, span)))
+            match left {
+                swc::PatOrExpr::Pat(pat)
+                    if matches!(*pat, swc::Pat::Array(..) | swc::Pat::Object(..)) =>
+                {
+                    if op != S::AssignOp::Equal {
+                        return unsupported_message(
+                            "compound assignment to a pattern",
+                            span,
+                            source_map,
+                        );
+                    }
+                    Ok(S::Expr::Destructure(
+                        Box::new(parse_pattern(*pat, span, source_map)?),
+                        Box::new(right),
+                        Pos::from_swc(source_map, span),
+                    ))
+                }
+                left => {
+                    let left = parse_pat_or_expr(left, span, source_map)?;
+                    Ok(op_assign_(op, left, right, Pos::from_swc(source_map, span)))
+                }
+            }
         }
-        Await(await_expr) => unsupported!(await_expr.span, source_map),
+        Await(swc::AwaitExpr { arg, span }) => Ok(S::Expr::Await(
+            Box::new(parse_expr(*arg, source_map)?),
+            Pos::from_swc(source_map, span),
+        )),
         Bin(swc::BinExpr {
             op,
             left,
@@ -441,19 +729,47 @@ fn parse_expr(expr: swc::Expr, source_map: &Rc<SourceMap>) -> ParseResult<S::Exp
 
             Ok(binary_(op, left, right, Pos::from_swc(source_map, span)))
         }
-        Class(class_expr) => unsupported!(class_expr.class.span, source_map),
+        Class(swc::ClassExpr { ident, class }) => {
+            let span = class.span;
+            let class = parse_class(ident.map(parse_id), class, source_map)?;
+            Ok(S::Expr::Class(
+                Box::new(class),
+                Pos::from_swc(source_map, span),
+            ))
+        }
+        Call(swc::CallExpr {
+            args,
+            callee,
+            span,
+            type_args,
+        }) if has_spread(args.iter().map(Some)) => {
+            // f(...xs) calls f with the elements of an array
+            let p = Pos::from_swc(source_map, span);
+            if continues_chain(&callee) {
+                return unsupported_message("spread in an optional chain", span, source_map);
+            }
+            let callee = parse_expr_or_super(callee, source_map)?;
+            let args = parse_array_with_spread(args.into_iter().map(Some), p.clone(), source_map)?;
+            Ok(S::Expr::Apply(Box::new(callee), None, Box::new(args), p))
+        }
         Call(swc::CallExpr {
             args,
             callee,
             span,
             type_args,
         }) => {
+            let in_chain = continues_chain(&callee);
             let args: ParseResult<Vec<_>> = args
                 .into_iter()
                 .map(|e| parse_expr_or_spread(e, source_map))
                 .collect();
-            let callee = parse_expr_or_super(callee, source_map);
-            Ok(call_(callee?, args?, Pos::from_swc(source_map, span)))
+            let callee = parse_expr_or_super(callee, source_map)?;
+            let p = Pos::from_swc(source_map, span);
+            if in_chain {
+                Ok(extend_chain(callee, false, S::ChainLink::Call(args?), p))
+            } else {
+                Ok(call_(callee, args?, p))
+            }
         }
         Cond(swc::CondExpr {
             test,
@@ -473,15 +789,19 @@ fn parse_expr(expr: swc::Expr, source_map: &Rc<SourceMap>) -> ParseResult<S::Exp
                 None => None,
             };
             let span = function.span;
-            let (params, body) = parse_function(function, source_map)?;
+            // like an arrow, a generator or async function is not a
+            // constructor
+            let constructor = !function.is_generator && !function.is_async;
+            let (params, rest, body) = parse_function(function, source_map)?;
 
             // put it all together
-            Ok(expr_func_(
-                ident,
-                params,
-                body,
-                Pos::from_swc(source_map, span),
-            ))
+            let p = Pos::from_swc(source_map, span);
+            let func = S::Expr::Func(ident, params, rest, Box::new(body), p.clone());
+            Ok(if constructor {
+                func
+            } else {
+                jnks_call_("non_constructor", func, p)
+            })
         }
         Ident(ident) => {
             let span = ident.span;
@@ -499,6 +819,21 @@ fn parse_expr(expr: swc::Expr, source_map: &Rc<SourceMap>) -> ParseResult<S::Exp
             let (lit, span) = parse_lit(lit, source_map)?;
             Ok(S::Expr::Lit(lit, Pos::from_swc(source_map, span)))
         }
+        Member(swc::MemberExpr {
+            obj,
+            prop,
+            computed,
+            span,
+        }) if continues_chain(&obj) => {
+            let obj = parse_expr_or_super(obj, source_map)?;
+            let link = parse_member_link(*prop, computed, span, source_map)?;
+            Ok(extend_chain(
+                obj,
+                false,
+                link,
+                Pos::from_swc(source_map, span),
+            ))
+        }
         Member(swc::MemberExpr {
             obj,
             prop,
@@ -519,10 +854,13 @@ fn parse_expr(expr: swc::Expr, source_map: &Rc<SourceMap>) -> ParseResult<S::Exp
                 }
             }
         }
-        MetaProp(swc::MetaPropExpr {
-            meta: swc::Ident { span, .. },
-            ..
-        }) => unsupported!(span, source_map), // new.target
+        MetaProp(swc::MetaPropExpr { meta, prop }) => {
+            if &meta.sym == "new" && &prop.sym == "target" {
+                Ok(S::Expr::NewTarget)
+            } else {
+                unsupported!(meta.span, source_map)
+            }
+        }
         New(swc::NewExpr {
             callee, args, span, ..
         }) => {
@@ -545,13 +883,108 @@ fn parse_expr(expr: swc::Expr, source_map: &Rc<SourceMap>) -> ParseResult<S::Exp
             Ok(new_(callee?, args?, Pos::from_swc(source_map, span)))
         }
         Object(swc::ObjectLit { props, span }) => {
-            let props: ParseResult<Vec<_>> = props
-                .into_iter()
-                .map(|p| parse_prop_or_spread(p, span, source_map))
-                .collect();
-            Ok(S::Expr::Object(props?, Pos::from_swc(source_map, span)))
+            // `{ a: 1, ...o, b: 2 }` is `__JNKS.object_spread([{ a: 1 }, o, { b: 2 }])`
+            let p = Pos::from_swc(source_map, span);
+            let mut parts = vec![];
+            let mut fields = vec![];
+            let mut accessors: Vec<(S::Key, S::Expr, S::Expr)> = vec![];
+            for prop in props {
+                match prop {
+                    swc::PropOrSpread::Prop(prop) => match *prop {
+                        prop @ swc::Prop::Getter(_) | prop @ swc::Prop::Setter(_) => {
+                            let (key, getter, setter) = parse_accessor(prop, span, source_map)?;
+                            match accessors.iter_mut().find(|(k, _, _)| k == &key) {
+                                Some((_, old_getter, old_setter)) => {
+                                    if let Some(getter) = getter {
+                                        *old_getter = getter;
+                                    }
+                                    if let Some(setter) = setter {
+                                        *old_setter = setter;
+                                    }
+                                }
+                                None => {
+                                    // the field keeps its place among the
+                                    // others until the accessor replaces it
+                                    fields.push((key.clone(), UNDEFINED_));
+                                    accessors.push((
+                                        key,
+                                        getter.unwrap_or(UNDEFINED_),
+                                        setter.unwrap_or(UNDEFINED_),
+                                    ));
+                                }
+                            }
+                        }
+                        prop => fields.push(parse_prop(prop, span, source_map)?),
+                    },
+                    swc::PropOrSpread::Spread(swc::SpreadElement { expr, .. }) => {
+                        if !fields.is_empty() {
+                            parts.push(S::Expr::Object(std::mem::take(&mut fields), p.clone()));
+                        }
+                        parts.push(parse_expr(*expr, source_map)?);
+                    }
+                }
+            }
+            let obj = if parts.is_empty() {
+                S::Expr::Object(fields, p.clone())
+            } else {
+                if !fields.is_empty() {
+                    parts.push(S::Expr::Object(fields, p.clone()));
+                }
+                jnks_call_("object_spread", S::Expr::Array(parts, p.clone()), p.clone())
+            };
+            if accessors.is_empty() {
+                return Ok(obj);
+            }
+            // `{ get x() { body } }` is
+            // `__JNKS.define_accessors({ x: undefined }, ["x", function () { body }, undefined])`
+            let mut triples = vec![];
+            for (key, getter, setter) in accessors {
+                triples.push(match key {
+                    S::Key::Str(x) => str_(x, p.clone()),
+                    S::Key::Int(n) => int_(n, p.clone()),
+                });
+                triples.push(getter);
+                triples.push(setter);
+            }
+            Ok(call_(
+                dot_(id_("__JNKS", p.clone()), "define_accessors", p.clone()),
+                vec![obj, S::Expr::Array(triples, p.clone())],
+                p,
+            ))
+        }
+        // `a?.b`, `a?.[k]` and `f?.(args)`. The rest of the chain is parsed
+        // by the `Member` and `Call` cases above
+        OptChain(swc::OptChainExpr { expr, span, .. }) => {
+            let p = Pos::from_swc(source_map, span);
+            match *expr {
+                Member(swc::MemberExpr {
+                    obj,
+                    prop,
+                    computed,
+                    span,
+                }) => {
+                    let obj = parse_expr_or_super(obj, source_map)?;
+                    let link = parse_member_link(*prop, computed, span, source_map)?;
+                    Ok(extend_chain(obj, true, link, p))
+                }
+                Call(swc::CallExpr { args, callee, .. }) => {
+                    if has_spread(args.iter().map(Some)) {
+                        return unsupported_message(
+                            "spread in an optional chain",
+                            span,
+                            source_map,
+                        );
+                    }
+                    let args: ParseResult<Vec<_>> = args
+                        .into_iter()
+                        .map(|e| parse_expr_or_spread(e, source_map))
+                        .collect();
+                    let callee = parse_expr_or_super(callee, source_map)?;
+                    Ok(extend_chain(callee, true, S::ChainLink::Call(args?), p))
+                }
+                _ => unsupported!(span, source_map),
+            }
         }
-        OptChain(swc::OptChainExpr { span, .. }) => unsupported!(span, source_map),
         Paren(swc::ParenExpr { expr, .. }) => parse_expr(*expr, source_map),
         PrivateName(private_name) => unsupported!(private_name.span, source_map),
         Seq(swc::SeqExpr { exprs, span }) => {
@@ -561,9 +994,55 @@ fn parse_expr(expr: swc::Expr, source_map: &Rc<SourceMap>) -> ParseResult<S::Exp
                 .collect();
             Ok(S::Expr::Seq(exprs?, Pos::from_swc(source_map, span)))
         }
-        TaggedTpl(tagged_tpl) => unsupported!(tagged_tpl.span, source_map),
+        TaggedTpl(swc::TaggedTpl {
+            tag,
+            exprs,
+            quasis,
+            span,
+            ..
+        }) => {
+            // tag`a${x}b` =>
+            // tag(__JNKS.templates[site] ||
+            //     (__JNKS.templates[site] = __JNKS.template(["a", "b"], ["a", "b"])), x)
+            //
+            // where the first array holds the cooked strings and the second
+            // the raw strings. Each evaluation of the template passes the
+            // same strings, so they are cached by `site`, the position of the
+            // template
+            let p = Pos::from_swc(source_map, span);
+            let mut cooked = vec![];
+            let mut raw = vec![];
+            for q in quasis {
+                cooked.push(match q.cooked {
+                    Some(cooked) => str_(cooked.value.to_string(), p.clone()),
+                    None => UNDEFINED_,
+                });
+                raw.push(str_(q.raw.value.to_string(), p.clone()));
+            }
+            let template = call_(
+                dot_(id_("__JNKS", p.clone()), "template", p.clone()),
+                vec![
+                    S::Expr::Array(cooked, p.clone()),
+                    S::Expr::Array(raw, p.clone()),
+                ],
+                p.clone(),
+            );
+            let templates = dot_(id_("__JNKS", p.clone()), "templates", p.clone());
+            let site = str_(p.to_string(), p.clone());
+            let cached = bracket_(templates.clone(), site.clone(), p.clone());
+            let strings = or_(
+                cached,
+                assign_(S::LValue::Bracket(templates, site), template, p.clone()),
+                p.clone(),
+            );
+            let mut args = vec![strings];
+            for e in exprs {
+                args.push(parse_expr(*e, source_map)?);
+            }
+            Ok(call_(parse_expr(*tag, source_map)?, args, p))
+        }
         This(swc::ThisExpr { span }) => Ok(S::Expr::This),
-        Tpl(tpl) => unsupported!(tpl.span, source_map),
+        Tpl(tpl) => parse_template(tpl, source_map),
         TsAs(ts_as_expr) => unsupported!(ts_as_expr.span, source_map),
         TsConstAssertion(ts_const_assertion) => unsupported!(ts_const_assertion.span, source_map),
         TsNonNull(ts_non_null_expr) => unsupported!(ts_non_null_expr.span, source_map),
@@ -585,7 +1064,21 @@ fn parse_expr(expr: swc::Expr, source_map: &Rc<SourceMap>) -> ParseResult<S::Exp
 
             Ok(unaryassign_(op, arg, Pos::from_swc(source_map, span)))
         }
-        Yield(yield_expr) => unsupported!(yield_expr.span, source_map),
+        Yield(swc::YieldExpr {
+            arg,
+            delegate,
+            span,
+        }) => {
+            let arg = match arg {
+                Some(e) => parse_expr(*e, source_map)?,
+                None => UNDEFINED_,
+            };
+            Ok(S::Expr::Yield(
+                Box::new(arg),
+                delegate,
+                Pos::from_swc(source_map, span),
+            ))
+        }
     }
 }
 
@@ -602,6 +1095,41 @@ fn parse_opt_expr(
     }
 }
 
+/// Parse an untagged template literal into string concatenation. Each
+/// substitution is converted with `String`, so that every `+` is between two
+/// strings:
+///
+/// `a${x}b${y}` => "a" + String(x) + "b" + String(y)
+fn parse_template(tpl: swc::Tpl, source_map: &Rc<SourceMap>) -> ParseResult<S::Expr> {
+    let swc::Tpl {
+        exprs,
+        quasis,
+        span,
+    } = tpl;
+    let p = Pos::from_swc(source_map, span);
+    let mut quasis = quasis.into_iter().map(|q| match q.cooked {
+        Some(cooked) => Ok(cooked.value.to_string()),
+        None => unsupported_message("invalid escape in template", q.span, source_map),
+    });
+    // there is always one more quasi than there are substitutions
+    let mut result = str_(quasis.next().unwrap()?, p.clone());
+    for (e, quasi) in exprs.into_iter().zip(quasis) {
+        let e = parse_expr(*e, source_map)?;
+        let e = call_(id_("String", p.clone()), vec![e], p.clone());
+        result = binary_(S::BinOp::BinaryOp(S::BinaryOp::Plus), result, e, p.clone());
+        let quasi = quasi?;
+        if !quasi.is_empty() {
+            result = binary_(
+                S::BinOp::BinaryOp(S::BinaryOp::Plus),
+                result,
+                str_(quasi, p.clone()),
+                p.clone(),
+            );
+        }
+    }
+    Ok(result)
+}
+
 /// Parse an swc block statement.
 fn parse_block(block: swc::BlockStmt, source_map: &Rc<SourceMap>) -> ParseResult<S::Stmt> {
     Ok(S::Stmt::Block(
@@ -639,14 +1167,132 @@ fn parse_lvalue_from_pattern(
     }
 }
 
+/// Parse a declarator. A destructuring declarator declares every variable
+/// that it binds: `var [x, y] = e` becomes `var x = ([x, y] = e, x), y = y`.
 fn parse_var_declarator(
     var_decl: swc::VarDeclarator,
+    kind: swc::VarDeclKind,
     source_map: &Rc<SourceMap>,
-) -> ParseResult<S::VarDecl> {
-    Ok(S::VarDecl {
-        name: parse_id_from_pattern(var_decl.name, var_decl.span, source_map)?,
-        named: Box::new(parse_opt_expr(var_decl.init, source_map)?),
-    })
+) -> ParseResult<Vec<S::VarDecl>> {
+    let swc::VarDeclarator {
+        span, name, init, ..
+    } = var_decl;
+    let kind = parse_var_kind(kind);
+    match name {
+        swc::Pat::Ident(ident) => Ok(vec![S::VarDecl {
+            name: parse_id(ident),
+            named: Box::new(parse_opt_expr(init, source_map)?),
+            kind,
+        }]),
+        pat => {
+            let p = Pos::from_swc(source_map, span);
+            let pattern = parse_pattern(pat, span, source_map)?;
+            let init = match init {
+                Some(e) => parse_expr(*e, source_map)?,
+                None => {
+                    return unsupported_message(
+                        "destructuring declaration without an initializer",
+                        span,
+                        source_map,
+                    )
+                }
+            };
+            let mut ids = pattern.bound_ids().into_iter();
+            let first = match ids.next() {
+                Some(x) => x,
+                None => return unsupported_message("pattern binds no variables", span, source_map),
+            };
+            let destructure = S::Expr::Destructure(Box::new(pattern), Box::new(init), p.clone());
+            let mut decls = vec![S::VarDecl {
+                name: first.clone(),
+                named: Box::new(S::Expr::Seq(
+                    vec![destructure, id_(first, p.clone())],
+                    p.clone(),
+                )),
+                kind,
+            }];
+            decls.extend(ids.map(|x| S::VarDecl {
+                name: x.clone(),
+                named: Box::new(id_(x, p.clone())),
+                kind,
+            }));
+            Ok(decls)
+        }
+    }
+}
+
+fn parse_var_kind(kind: swc::VarDeclKind) -> S::VarKind {
+    match kind {
+        swc::VarDeclKind::Var => S::VarKind::Var,
+        swc::VarDeclKind::Let => S::VarKind::Let,
+        swc::VarDeclKind::Const => S::VarKind::Const,
+    }
+}
+
+/// Parse a destructuring pattern. `span` should be the source location of the
+/// surrounding expr/stmt. `span` is used for error reporting purposes.
+fn parse_pattern(
+    pattern: swc::Pat,
+    span: Span,
+    source_map: &Rc<SourceMap>,
+) -> ParseResult<S::Pattern> {
+    use swc::Pat::*;
+    match pattern {
+        Array(swc::ArrayPat { elems, span, .. }) => {
+            let mut pats = vec![];
+            let mut rest = None;
+            for elem in elems {
+                match elem {
+                    Some(Rest(swc::RestPat { arg, .. })) => {
+                        rest = Some(Box::new(parse_pattern(*arg, span, source_map)?))
+                    }
+                    Some(p) => pats.push(Some(parse_pattern(p, span, source_map)?)),
+                    None => pats.push(None),
+                }
+            }
+            Ok(S::Pattern::Array(pats, rest))
+        }
+        Object(swc::ObjectPat { props, span, .. }) => {
+            let mut pats = vec![];
+            let mut rest = None;
+            for prop in props {
+                match prop {
+                    swc::ObjectPatProp::KeyValue(swc::KeyValuePatProp { key, value }) => {
+                        let key = parse_prop_name(key, span, source_map)?;
+                        pats.push((key, parse_pattern(*value, span, source_map)?));
+                    }
+                    // `{x}` is `{x: x}` and `{x = e}` is `{x: x = e}`
+                    swc::ObjectPatProp::Assign(swc::AssignPatProp { key, value, .. }) => {
+                        let x = parse_id(key);
+                        let key = S::Key::Str(x.name().to_string());
+                        let pat = S::Pattern::LValue(S::LValue::Id(x));
+                        let pat = match value {
+                            Some(e) => S::Pattern::Default(
+                                Box::new(pat),
+                                Box::new(parse_expr(*e, source_map)?),
+                            ),
+                            None => pat,
+                        };
+                        pats.push((key, pat));
+                    }
+                    swc::ObjectPatProp::Rest(swc::RestPat { arg, .. }) => {
+                        rest = Some(Box::new(parse_pattern(*arg, span, source_map)?))
+                    }
+                }
+            }
+            Ok(S::Pattern::Object(pats, rest))
+        }
+        Assign(swc::AssignPat { left, right, .. }) => Ok(S::Pattern::Default(
+            Box::new(parse_pattern(*left, span, source_map)?),
+            Box::new(parse_expr(*right, source_map)?),
+        )),
+        Rest(swc::RestPat { span, .. }) => {
+            unsupported_message("rest element outside of a pattern", span, source_map)
+        }
+        other => Ok(S::Pattern::LValue(parse_lvalue_from_pattern(
+            other, span, source_map,
+        )?)),
+    }
 }
 
 fn parse_switch_case(
@@ -670,7 +1316,7 @@ fn parse_expr_or_super(eos: swc::ExprOrSuper, source_map: &Rc<SourceMap>) -> Par
     use swc::ExprOrSuper::*;
     match eos {
         Expr(expr) => parse_expr(*expr, source_map),
-        Super(swc::Super { span }) => unsupported!(span, source_map),
+        Super(swc::Super { .. }) => Ok(S::Expr::Super),
     }
 }
 
@@ -680,10 +1326,101 @@ fn parse_expr_or_spread(
 ) -> ParseResult<S::Expr> {
     match eos.spread {
         None => parse_expr(*eos.expr, source_map),
-        Some(span) => unsupported!(span, source_map),
+        Some(span) => unsupported_message("spread is not supported here", span, source_map),
+    }
+}
+
+/// Whether `e` is an optional chain that a member access or call on `e`
+/// continues. Parentheses end a chain, so `(a?.b).c` reads `c` even when
+/// `a` is `undefined`.
+fn continues_chain(e: &swc::ExprOrSuper) -> bool {
+    match e {
+        swc::ExprOrSuper::Expr(e) => match &**e {
+            swc::Expr::OptChain(..) => true,
+            swc::Expr::Member(swc::MemberExpr { obj, .. }) => continues_chain(obj),
+            swc::Expr::Call(swc::CallExpr { callee, .. }) => continues_chain(callee),
+            _ => false,
+        },
+        swc::ExprOrSuper::Super(..) => false,
+    }
+}
+
+/// Add `link` to the end of the optional chain `obj`. If `obj` is not a
+/// chain, starts a new chain, which only makes sense when `optional` is true.
+fn extend_chain(obj: S::Expr, optional: bool, link: S::ChainLink, p: Pos) -> S::Expr {
+    match obj {
+        S::Expr::OptChain(e, mut links, _) => {
+            links.push((optional, link));
+            S::Expr::OptChain(e, links, p)
+        }
+        obj => S::Expr::OptChain(Box::new(obj), vec![(optional, link)], p),
+    }
+}
+
+/// `.x` or `[e]` within an optional chain
+fn parse_member_link(
+    prop: swc::Expr,
+    computed: bool,
+    span: Span,
+    source_map: &Rc<SourceMap>,
+) -> ParseResult<S::ChainLink> {
+    match prop {
+        prop if computed => Ok(S::ChainLink::Bracket(parse_expr(prop, source_map)?)),
+        swc::Expr::Ident(id) => Ok(S::ChainLink::Dot(parse_id(id))),
+        _ => unsupported!(span, source_map),
     }
 }
 
+fn has_spread<'a>(mut elems: impl Iterator<Item = Option<&'a swc::ExprOrSpread>>) -> bool {
+    elems.any(|e| {
+        matches!(
+            e,
+            Some(swc::ExprOrSpread {
+                spread: Some(_),
+                ..
+            })
+        )
+    })
+}
+
+/// Parse array elements that contain spread elements into an expression that
+/// builds the array: `[x, ...ys, z]` is `__JNKS.array_spread([[x], ys, [z]])`.
+fn parse_array_with_spread(
+    elems: impl IntoIterator<Item = Option<swc::ExprOrSpread>>,
+    p: Pos,
+    source_map: &Rc<SourceMap>,
+) -> ParseResult<S::Expr> {
+    let mut parts = vec![];
+    let mut run = vec![];
+    for elem in elems {
+        match elem {
+            Some(swc::ExprOrSpread {
+                spread: Some(_),
+                expr,
+            }) => {
+                if !run.is_empty() {
+                    parts.push(S::Expr::Array(std::mem::take(&mut run), p.clone()));
+                }
+                parts.push(parse_expr(*expr, source_map)?);
+            }
+            elem => run.push(parse_opt_expr_or_spread(elem, source_map)?),
+        }
+    }
+    if !run.is_empty() {
+        parts.push(S::Expr::Array(run, p.clone()));
+    }
+    Ok(jnks_call_(
+        "array_spread",
+        S::Expr::Array(parts, p.clone()),
+        p,
+    ))
+}
+
+/// `__JNKS.f(arg)`, a call to a function that only jankscripten provides
+fn jnks_call_(f: &str, arg: S::Expr, p: Pos) -> S::Expr {
+    call_(dot_(id_("__JNKS", p.clone()), f, p.clone()), vec![arg], p)
+}
+
 fn parse_opt_expr_or_spread(
     oeos: Option<swc::ExprOrSpread>,
     source_map: &Rc<SourceMap>,
@@ -694,8 +1431,105 @@ fn parse_opt_expr_or_spread(
     }
 }
 
-fn parse_func_arg(arg: swc::Param, source_map: &Rc<SourceMap>) -> ParseResult<S::Id> {
-    Ok(parse_id_from_pattern(arg.pat, arg.span, source_map)?)
+/// Parse the parameters of a function with the given body, and whether the
+/// last parameter is a rest parameter. A destructuring parameter is named
+/// after the first variable that it binds, and is destructured at the top of
+/// `body`: `function(x, [y, z]) { body }` becomes
+/// `function(x, y) { var z; [y, z] = y; body }`. A default value is assigned
+/// at the top of `body` when the argument is `undefined`:
+/// `function(x = e) { body }` becomes
+/// `function(x) { if (x === undefined) { x = e; } body }`.
+fn parse_params(
+    params: Vec<swc::Pat>,
+    body: &mut S::Stmt,
+    span: Span,
+    source_map: &Rc<SourceMap>,
+) -> ParseResult<(Vec<S::Id>, bool)> {
+    let p = Pos::from_swc(source_map, span);
+    let mut prologue = vec![];
+    let mut ids = vec![];
+    let mut rest = false;
+    let num_params = params.len();
+    for (i, param) in params.into_iter().enumerate() {
+        let param = match param {
+            swc::Pat::Rest(swc::RestPat { arg, span, .. }) => {
+                if i + 1 != num_params {
+                    return unsupported_message(
+                        "a rest parameter must be the last parameter",
+                        span,
+                        source_map,
+                    );
+                }
+                rest = true;
+                *arg
+            }
+            param => param,
+        };
+        match param {
+            swc::Pat::Assign(swc::AssignPat { left, right, .. })
+                if matches!(*left, swc::Pat::Ident(..)) =>
+            {
+                let x = parse_id_from_pattern(*left, span, source_map)?;
+                let default = parse_expr(*right, source_map)?;
+                prologue.push(if_(
+                    binary_(
+                        S::BinOp::BinaryOp(S::BinaryOp::StrictEqual),
+                        id_(x.clone(), p.clone()),
+                        UNDEFINED_,
+                        p.clone(),
+                    ),
+                    S::Stmt::Block(
+                        vec![expr_(assign_(x.clone(), default, p.clone()), p.clone())],
+                        p.clone(),
+                    ),
+                    S::Stmt::Block(vec![], p.clone()),
+                    p.clone(),
+                ));
+                ids.push(x);
+            }
+            swc::Pat::Array(..) | swc::Pat::Object(..) | swc::Pat::Assign(..) => {
+                let pattern = parse_pattern(param, span, source_map)?;
+                let mut bound = pattern.bound_ids().into_iter();
+                let x = match bound.next() {
+                    Some(x) => x,
+                    None => {
+                        return unsupported_message("pattern binds no variables", span, source_map)
+                    }
+                };
+                // the other variables are locals of the function
+                let locals: Vec<_> = bound
+                    .map(|y| S::VarDecl {
+                        name: y,
+                        named: Box::new(UNDEFINED_),
+                        kind: S::VarKind::Var,
+                    })
+                    .collect();
+                if !locals.is_empty() {
+                    prologue.push(S::Stmt::VarDecl(locals, p.clone()));
+                }
+                prologue.push(expr_(
+                    S::Expr::Destructure(
+                        Box::new(pattern),
+                        Box::new(id_(x.clone(), p.clone())),
+                        p.clone(),
+                    ),
+                    p.clone(),
+                ));
+                ids.push(x);
+            }
+            _ => ids.push(parse_id_from_pattern(param, span, source_map)?),
+        }
+    }
+    if !prologue.is_empty() {
+        match body {
+            S::Stmt::Block(stmts, _) => {
+                prologue.append(stmts);
+                *stmts = prologue;
+            }
+            _ => *body = S::Stmt::Block(prologue, p),
+        }
+    }
+    Ok((ids, rest))
 }
 
 fn parse_lit(lit: swc::Lit, source_map: &Rc<SourceMap>) -> ParseResult<(S::Lit, Span)> {
@@ -705,9 +1539,7 @@ fn parse_lit(lit: swc::Lit, source_map: &Rc<SourceMap>) -> ParseResult<(S::Lit,
         Bool(swc::Bool { value, span }) => Ok((S::Lit::Bool(value), span)),
         Null(swc::Null { span }) => Ok((S::Lit::Null, span)),
         Num(swc::Number { value, span }) => Ok((S::Lit::Num(parse_num(value)), span)),
-        BigInt(swc::BigInt { value, span }) => {
-            unsupported_message("big int literal", span, source_map)
-        }
+        BigInt(swc::BigInt { value, span }) => Ok((S::Lit::BigInt(value.to_string()), span)),
         Regex(swc::Regex { exp, flags, span }) => {
             Ok((S::Lit::Regex(exp.to_string(), flags.to_string()), span))
         }
@@ -717,18 +1549,6 @@ fn parse_lit(lit: swc::Lit, source_map: &Rc<SourceMap>) -> ParseResult<(S::Lit,
     }
 }
 
-/// `span` is the span of the surrounding object literal.
-fn parse_prop_or_spread(
-    pos: swc::PropOrSpread,
-    span: Span,
-    source_map: &Rc<SourceMap>,
-) -> ParseResult<(S::Key, S::Expr)> {
-    match pos {
-        swc::PropOrSpread::Prop(prop) => parse_prop(*prop, span, source_map),
-        _ => unsupported_message("", span, source_map),
-    }
-}
-
 /// `span` is the span of the surrounding object literal.
 fn parse_prop(
     prop: swc::Prop,
@@ -743,6 +1563,59 @@ fn parse_prop(
 
             Ok((key, value))
         }
+        // `{ f(x) { body } }` is `{ f: function (x) { body } }`, except
+        // that a method is not a constructor
+        Method(swc::MethodProp { key, function }) => {
+            let key = parse_prop_name(key, span, source_map)?;
+            let p = Pos::from_swc(source_map, function.span);
+            let (params, rest, body) = parse_function(function, source_map)?;
+            let func = S::Expr::Func(None, params, rest, Box::new(body), p.clone());
+            Ok((key, jnks_call_("non_constructor", func, p)))
+        }
+        _ => unsupported_message("object literal key type", span, source_map),
+    }
+}
+
+/// The key of `get x() { body }` or `set x(v) { body }` in an object
+/// literal, and its getter or its setter as a function expression. `span` is
+/// the span of the surrounding object literal.
+fn parse_accessor(
+    prop: swc::Prop,
+    span: Span,
+    source_map: &Rc<SourceMap>,
+) -> ParseResult<(S::Key, Option<S::Expr>, Option<S::Expr>)> {
+    match prop {
+        swc::Prop::Getter(swc::GetterProp {
+            key,
+            body,
+            span: getter_span,
+            ..
+        }) => {
+            let key = parse_prop_name(key, span, source_map)?;
+            let body = match body {
+                Some(block) => parse_block(block, source_map)?,
+                None => S::Stmt::Empty,
+            };
+            let p = Pos::from_swc(source_map, getter_span);
+            let getter = S::Expr::Func(None, vec![], false, Box::new(body), p);
+            Ok((key, Some(getter), None))
+        }
+        swc::Prop::Setter(swc::SetterProp {
+            key,
+            param,
+            body,
+            span: setter_span,
+        }) => {
+            let key = parse_prop_name(key, span, source_map)?;
+            let mut body = match body {
+                Some(block) => parse_block(block, source_map)?,
+                None => S::Stmt::Empty,
+            };
+            let (params, rest) = parse_params(vec![param], &mut body, setter_span, source_map)?;
+            let p = Pos::from_swc(source_map, setter_span);
+            let setter = S::Expr::Func(None, params, rest, Box::new(body), p);
+            Ok((key, None, Some(setter)))
+        }
         _ => unsupported_message("object literal key type", span, source_map),
     }
 }
@@ -764,10 +1637,20 @@ fn parse_prop_name(
                 S::Num::Float(_) => unsupported_message("float as prop key", span, source_map),
             }
         }
-        Computed(_) => unsupported_message("computed prop name", span, source_map),
+        Computed(swc::ComputedPropName { expr, span }) => match parse_expr(*expr, source_map)? {
+            // we do not have symbols, see `ITERATOR_KEY`
+            S::Expr::Dot(obj, S::Id::Named(x), _) if x == "iterator" && is_symbol(&obj) => {
+                Ok(S::Key::Str(S::ITERATOR_KEY.to_string()))
+            }
+            _ => unsupported_message("computed prop name", span, source_map),
+        },
     }
 }
 
+fn is_symbol(e: &S::Expr) -> bool {
+    matches!(e, S::Expr::Id(S::Id::Named(x), _) if x == "Symbol")
+}
+
 fn parse_binary_op(
     op: swc::BinaryOp,
     span: Span,
@@ -823,7 +1706,7 @@ fn parse_binary_op(
         // `**`
         Exp => Ok(BinaryOp(B::PowerOf)),
         // `??`
-        NullishCoalescing => unsupported!(span, source_map),
+        NullishCoalescing => Ok(LogicalOp(L::Nullish)),
         // `||`
         LogicalOr => Ok(LogicalOp(L::Or)),
         // `&&`
@@ -988,15 +1871,18 @@ fn parse_decl(decl: swc::Decl, source_map: &Rc<SourceMap>) -> ParseResult<S::Stm
     match decl {
         Var(swc::VarDecl {
             span,
-            kind, //: swc::VarDeclKind::Var,
+            kind,
             declare: _,
             decls,
         }) => {
             let decls: ParseResult<Vec<_>> = decls
                 .into_iter()
-                .map(|d| parse_var_declarator(d, source_map))
+                .map(|d| parse_var_declarator(d, kind, source_map))
                 .collect();
-            Ok(S::Stmt::VarDecl(decls?, Pos::from_swc(source_map, span)))
+            Ok(S::Stmt::VarDecl(
+                decls?.into_iter().flatten().collect(),
+                Pos::from_swc(source_map, span),
+            ))
         }
         Fn(swc::FnDecl {
             ident,
@@ -1005,22 +1891,121 @@ fn parse_decl(decl: swc::Decl, source_map: &Rc<SourceMap>) -> ParseResult<S::Stm
         }) => {
             let ident = parse_id(ident);
             let span = function.span;
-            let (params, body) = parse_function(function, source_map)?;
+            let (params, rest, body) = parse_function(function, source_map)?;
             Ok(S::Stmt::Func(
                 ident,
                 params,
+                rest,
                 Box::new(body),
                 Pos::from_swc(source_map, span),
             ))
         }
+        Class(swc::ClassDecl {
+            ident,
+            declare: _,
+            class,
+        }) => {
+            // class C { ... } => var C = class C { ... };
+            let span = class.span;
+            let name = parse_id(ident);
+            let class = parse_class(Some(name.clone()), class, source_map)?;
+            let p = Pos::from_swc(source_map, span);
+            Ok(vardecl1_(
+                name,
+                S::Expr::Class(Box::new(class), p.clone()),
+                p,
+            ))
+        }
         unsupported_decl => unsupported!(span_from_decl(unsupported_decl), source_map),
     }
 }
 
+fn parse_class(
+    name: Option<S::Id>,
+    class: swc::Class,
+    source_map: &Rc<SourceMap>,
+) -> ParseResult<S::Class> {
+    let swc::Class {
+        span,
+        decorators,
+        body,
+        super_class,
+        ..
+    } = class;
+    if !decorators.is_empty() {
+        return unsupported_message("class decorators not supported", span, source_map);
+    }
+    let extends = match super_class {
+        Some(e) => Some(Box::new(parse_expr(*e, source_map)?)),
+        None => None,
+    };
+    let mut constructor = None;
+    let mut methods = vec![];
+    for member in body {
+        match member {
+            swc::ClassMember::Constructor(swc::Constructor {
+                span, params, body, ..
+            }) => {
+                let params: ParseResult<Vec<_>> = params
+                    .into_iter()
+                    .map(|p| match p {
+                        swc::ParamOrTsParamProp::Param(p) => Ok(p.pat),
+                        swc::ParamOrTsParamProp::TsParamProp(p) => unsupported!(p.span, source_map),
+                    })
+                    .collect();
+                let mut body = match body {
+                    Some(block) => parse_block(block, source_map)?,
+                    None => S::Stmt::Empty,
+                };
+                let (params, rest) = parse_params(params?, &mut body, span, source_map)?;
+                constructor = Some(Box::new(S::Expr::Func(
+                    None,
+                    params,
+                    rest,
+                    Box::new(body),
+                    Pos::from_swc(source_map, span),
+                )));
+            }
+            swc::ClassMember::Method(swc::ClassMethod {
+                span,
+                key,
+                function,
+                kind: swc::MethodKind::Method,
+                is_static,
+                ..
+            }) => {
+                let key = parse_prop_name(key, span, source_map)?;
+                let (params, rest, body) = parse_function(function, source_map)?;
+                methods.push(S::ClassMethod {
+                    is_static,
+                    key,
+                    func: S::Expr::Func(
+                        None,
+                        params,
+                        rest,
+                        Box::new(body),
+                        Pos::from_swc(source_map, span),
+                    ),
+                });
+            }
+            swc::ClassMember::Empty(_) => (),
+            other => {
+                return unsupported_message("unsupported class member", other.span(), source_map)
+            }
+        }
+    }
+    Ok(S::Class {
+        name,
+        extends,
+        constructor,
+        methods,
+    })
+}
+
 fn parse_function(
     function: swc::Function,
     source_map: &Rc<SourceMap>,
-) -> ParseResult<(Vec<S::Id>, S::Stmt)> {
+) -> ParseResult<(Vec<S::Id>, bool, S::Stmt)> {
     let swc::Function {
         params,
         decorators,
@@ -1031,26 +2016,53 @@ fn parse_function(
         ..
     } = function;
     // rule out cases we don't handle
-    if is_generator {
-        return unsupported_message("generators not supported", span, source_map);
-    }
-    if is_async {
-        return unsupported_message("async not supported", span, source_map);
+    if is_async && is_generator {
+        return unsupported_message("async generators not supported", span, source_map);
     }
-    if decorators.len() > 0 {
+    if !decorators.is_empty() {
         return unsupported_message("class decorators not supported", span, source_map);
     }
-    let params: ParseResult<Vec<_>> = params
-        .into_iter()
-        .map(|p| parse_func_arg(p, source_map))
-        .collect();
-    let body = match body {
+    let mut body = match body {
         Some(block) => parse_block(block, source_map)?,
         None => S::Stmt::Empty,
     };
+    if is_generator {
+        // the parameters are bound when the generator function is applied,
+        // and the body runs when the iterator is first resumed
+        let p = Pos::from_swc(source_map, span);
+        let resume = S::Expr::Arrow(vec![], false, Box::new(body), p.clone());
+        body = S::Stmt::Block(
+            vec![return_(
+                S::Expr::Generator(Box::new(resume), p.clone()),
+                p.clone(),
+            )],
+            p,
+        );
+    }
+    if is_async {
+        body = async_body(body, Pos::from_swc(source_map, span));
+    }
+    let (params, rest) = parse_params(
+        params.into_iter().map(|p| p.pat).collect(),
+        &mut body,
+        span,
+        source_map,
+    )?;
 
     // put it all together
-    Ok((params?, body))
+    Ok((params, rest, body))
+}
+
+/// The body of an async function, which produces the promise that `body`
+/// settles when it runs. Like the body of a generator, the parameters are
+/// bound when the function is applied, but unlike it, `body` starts to run
+/// immediately.
+fn async_body(body: S::Stmt, p: Pos) -> S::Stmt {
+    let run = S::Expr::Arrow(vec![], false, Box::new(body), p.clone());
+    S::Stmt::Block(
+        vec![return_(S::Expr::Async(Box::new(run), p.clone()), p.clone())],
+        p,
+    )
 }
 
 /// Convert a numeric value from the parser into our AST's numbers.
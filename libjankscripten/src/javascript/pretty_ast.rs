@@ -72,12 +72,25 @@ impl Stmt {
                 .append(D::text(") "))
                 .append(body.to_doc()),
             ForIn(is_decl, name, container, body, _) => D::text("for (")
-                .append(if *is_decl { D::text("var ") } else { D::nil() })
+                .append(match is_decl {
+                    Some(kind) => kind.to_doc().append(D::space()),
+                    None => D::nil(),
+                })
                 .append(name.to_doc())
                 .append(D::text(" in "))
                 .append(container.to_doc())
                 .append(D::text(") "))
                 .append(body.to_doc()),
+            ForOf(is_decl, name, container, body, _) => D::text("for (")
+                .append(match is_decl {
+                    Some(kind) => kind.to_doc().append(D::space()),
+                    None => D::nil(),
+                })
+                .append(name.to_doc())
+                .append(D::text(" of "))
+                .append(container.to_doc())
+                .append(D::text(") "))
+                .append(body.to_doc()),
             Label(name, stmt, _) => name.to_doc().append(D::text(": ")).append(stmt.to_doc()),
             Break(maybe_lbl, _) => D::text("break")
                 .append(option_label_to_doc(maybe_lbl))
@@ -91,13 +104,16 @@ impl Stmt {
                 .append(bind.to_doc())
                 .append(D::text(") "))
                 .append(catch.to_doc()),
-            Finally(try_catch, final_block, _) => try_catch
-                .to_doc()
-                .append(D::text(" finally "))
-                .append(final_block.to_doc()),
+            Finally(try_catch, final_block, _) => match &**try_catch {
+                Catch(..) => try_catch.to_doc(),
+                // try { } finally { } has no catch
+                _ => D::text("try ").append(try_catch.to_doc()),
+            }
+            .append(D::text(" finally "))
+            .append(final_block.to_doc()),
             Throw(e, _) => D::text("throw ").append(e.to_doc()).append(D::text(";")),
             VarDecl(decls, _) => vardecls_to_doc(decls).append(D::text(";")),
-            Func(name, params, body, _) => func_to_doc(Some(name), params, body),
+            Func(name, params, rest, body, _) => func_to_doc(Some(name), params, *rest, body),
             Return(e, _) => D::text("return ").append(e.to_doc()).append(D::text(";")),
         }
     }
@@ -119,18 +135,14 @@ impl Expr {
                 .append(D::text("]")),
             Object(kes, _) => D::text("{")
                 .append(D::intersperse(
-                    kes.iter().map(|(k, e)| {
-                        (match k {
-                            Key::Int(n) => D::text(format!("{}", n)),
-                            Key::Str(s) => D::text(s),
-                        })
-                        .append(D::text(": "))
-                        .append(e.to_doc())
-                    }),
+                    kes.iter()
+                        .map(|(k, e)| k.to_doc().append(D::text(": ")).append(e.to_doc())),
                     D::text(", "),
                 ))
                 .append("}"),
             This => D::text("this"),
+            Super => D::text("super"),
+            NewTarget => D::text("new.target"),
             Id(x, _) => x.to_doc(),
             Dot(e, id, _) => e.to_doc().append(D::text(".")).append(id.to_doc()),
             Bracket(cont, ind, _) => cont
@@ -168,7 +180,33 @@ impl Expr {
                 .append(assign_op_to_doc(op))
                 .append(D::space())
                 .append(to.to_doc()),
+            Destructure(pat, to, _) => D::text("(")
+                .append(pat.to_doc())
+                .append(D::text(" = "))
+                .append(to.to_doc())
+                .append(D::text(")")),
             Call(clos, args, _) => fn_call_to_doc(clos, args),
+            CallWithThis(f, this_arg, args, _) => f
+                .to_doc()
+                .append(D::text(".call("))
+                .append(D::intersperse(
+                    std::iter::once(this_arg.to_doc()).chain(args.iter().map(|e| e.to_doc())),
+                    D::text(", "),
+                ))
+                .append(D::text(")")),
+            Apply(f, None, args, _) => f
+                .to_doc()
+                .append(D::text("(..."))
+                .append(args.to_doc())
+                .append(D::text(")")),
+            Apply(f, Some(this_arg), args, _) => f
+                .to_doc()
+                .append(D::text(".apply("))
+                .append(this_arg.to_doc())
+                .append(D::text(", "))
+                .append(args.to_doc())
+                .append(D::text(")")),
+            Class(class, _) => class_to_doc(class),
             MethodCall(obj, method, args, _) => obj.to_doc().append(
                 D::text(".").append(D::text(method)).append(
                     D::text("(")
@@ -180,10 +218,62 @@ impl Expr {
                 ),
             ),
             Length(obj, _) => obj.to_doc().append(D::text(".length")),
-            Func(maybe_name, params, body, _) => func_to_doc(maybe_name.as_ref(), params, body),
+            Func(maybe_name, params, rest, body, _) => {
+                func_to_doc(maybe_name.as_ref(), params, *rest, body)
+            }
+            Arrow(params, rest, body, _) => D::text("((")
+                .append(params_to_doc(params, *rest))
+                .append(D::text(") => "))
+                .append(body.to_doc())
+                .append(D::text(")")),
+            // the body is an arrow, but `yield` can only appear in a generator
+            Generator(body, _) => match &**body {
+                Arrow(_, _, body, _) => D::text("(function* () ")
+                    .append(body.to_doc())
+                    .append(D::text(").call(this)")),
+                _ => D::text("(function* () { yield* ")
+                    .append(body.to_doc())
+                    .append(D::text("(); }).call(this)")),
+            },
+            Yield(e, delegate, _) => D::text(if *delegate { "(yield* " } else { "(yield " })
+                .append(e.to_doc())
+                .append(D::text(")")),
+            Async(body, _) => match &**body {
+                Arrow(_, _, body, _) => D::text("(async () => ")
+                    .append(body.to_doc())
+                    .append(D::text(")()")),
+                _ => D::text("(async () => ")
+                    .append(body.to_doc())
+                    .append(D::text("())()")),
+            },
+            Await(e, _) => D::text("(await ").append(e.to_doc()).append(D::text(")")),
             Seq(es, _) => D::text("(")
                 .append(D::intersperse(es.iter().map(Expr::to_doc), D::text(", ")))
                 .append(D::text(")")),
+            OptChain(e, links, _) => links.iter().fold(e.to_doc(), |doc, (optional, link)| {
+                let doc = if *optional {
+                    doc.append(D::text("?."))
+                } else {
+                    doc
+                };
+                match link {
+                    ChainLink::Dot(x) => {
+                        let dot = if *optional { "" } else { "." };
+                        doc.append(D::text(dot)).append(x.to_doc())
+                    }
+                    ChainLink::Bracket(e) => doc
+                        .append(D::text("["))
+                        .append(e.to_doc())
+                        .append(D::text("]")),
+                    ChainLink::Call(args) => doc
+                        .append(D::text("("))
+                        .append(D::intersperse(
+                            args.iter().map(|e| e.to_doc()),
+                            D::text(", "),
+                        ))
+                        .append(D::text(")")),
+                }
+            }),
         }
     }
     pub fn to_pretty(&self, width: usize) -> String {
@@ -221,6 +311,7 @@ impl Lit {
                 .append(D::text(pattern))
                 .append(D::text("/"))
                 .append(D::text(flags)),
+            syntax::Lit::BigInt(digits) => D::text(format!("{}n", digits)),
             syntax::Lit::Bool(b) => match b {
                 true => D::text("true"),
                 false => D::text("false"),
@@ -235,6 +326,16 @@ impl Lit {
     }
 }
 
+impl Key {
+    pub fn to_doc(&self) -> D<'_, ()> {
+        match self {
+            Key::Int(n) => D::text(format!("{}", n)),
+            Key::Str(s) if s == ITERATOR_KEY => D::text("[Symbol.iterator]"),
+            Key::Str(s) => D::text(s),
+        }
+    }
+}
+
 impl Id {
     pub fn to_doc(&self) -> D<()> {
         match self {
@@ -295,11 +396,56 @@ impl BinOp {
             BinOp::LogicalOp(o) => match o {
                 LogicalOp::And => "&&",
                 LogicalOp::Or => "||",
+                LogicalOp::Nullish => "??",
             },
         })
     }
 }
 
+impl VarKind {
+    pub fn to_doc(&self) -> D<'_, ()> {
+        D::text(match self {
+            VarKind::Var => "var",
+            VarKind::Let => "let",
+            VarKind::Const => "const",
+        })
+    }
+}
+
+impl Pattern {
+    pub fn to_doc(&self) -> D<'_, ()> {
+        match self {
+            Pattern::LValue(lv) => lv.to_doc(),
+            Pattern::Array(elems, rest) => D::text("[")
+                .append(D::intersperse(
+                    elems
+                        .iter()
+                        .map(|p| match p {
+                            Some(p) => p.to_doc(),
+                            None => D::nil(),
+                        })
+                        .chain(rest_to_doc(rest)),
+                    D::text(", "),
+                ))
+                .append(D::text("]")),
+            Pattern::Object(props, rest) => D::text("{")
+                .append(D::intersperse(
+                    props
+                        .iter()
+                        .map(|(k, p)| k.to_doc().append(D::text(": ")).append(p.to_doc()))
+                        .chain(rest_to_doc(rest)),
+                    D::text(", "),
+                ))
+                .append(D::text("}")),
+            Pattern::Default(p, e) => p.to_doc().append(D::text(" = ")).append(e.to_doc()),
+        }
+    }
+}
+
+fn rest_to_doc(rest: &Option<Box<Pattern>>) -> Option<D<'_, ()>> {
+    rest.as_ref().map(|p| D::text("...").append(p.to_doc()))
+}
+
 impl LValue {
     pub fn to_doc(&self) -> D<()> {
         match self {
@@ -337,7 +483,10 @@ fn vardecls_to_doc(decls: &[VarDecl]) -> D<()> {
     let first = decls.first().expect("wouldn't exist without one");
     let rest = &decls[1..];
     // first with the let
-    let with_var = D::text("var ")
+    let with_var = first
+        .kind
+        .to_doc()
+        .append(D::space())
         .append(first.name.to_doc())
         .append(D::text(" = "))
         .append(first.named.to_doc());
@@ -374,18 +523,79 @@ fn option_label_to_doc(maybe_lbl: &Option<Id>) -> D<()> {
     }
 }
 
-fn func_to_doc<'a>(maybe_name: Option<&'a Id>, params: &'a [Id], body: &'a Stmt) -> D<'a, ()> {
+fn class_to_doc<'a>(class: &'a Class) -> D<'a, ()> {
+    // a method is printed from its `Expr::Func` without the `function`
+    let method_to_doc = |key: D<'a, ()>, func: &'a Expr| match func {
+        Expr::Func(_, params, rest, body, _) => key
+            .append(D::text("("))
+            .append(params_to_doc(params, *rest))
+            .append(D::text(") "))
+            .append(body.to_doc()),
+        _ => panic!("class method is not a function"),
+    };
+    let mut members = vec![];
+    if let Some(ctor) = &class.constructor {
+        members.push(method_to_doc(D::text("constructor"), ctor));
+    }
+    for m in &class.methods {
+        let key = if m.is_static {
+            D::text("static ").append(m.key.to_doc())
+        } else {
+            m.key.to_doc()
+        };
+        members.push(method_to_doc(key, &m.func));
+    }
+    D::text("(class")
+        .append(match &class.name {
+            Some(name) => D::space().append(name.to_doc()),
+            None => D::nil(),
+        })
+        .append(match &class.extends {
+            Some(e) => D::text(" extends ").append(e.to_doc()),
+            None => D::nil(),
+        })
+        .append(D::text(" {"))
+        .append(
+            D::line()
+                .append(D::intersperse(members, D::line()))
+                .nest(INDENT),
+        )
+        .append(D::line())
+        .append(D::text("})"))
+}
+
+fn func_to_doc<'a>(
+    maybe_name: Option<&'a Id>,
+    params: &'a [Id],
+    rest: bool,
+    body: &'a Stmt,
+) -> D<'a, ()> {
     D::text("function")
         .append(match maybe_name {
             Some(name) => D::space().append(name.to_doc()),
             None => D::nil(),
         })
         .append(D::text("("))
-        .append(D::intersperse(params.iter().map(Id::to_doc), D::text(", ")))
+        .append(params_to_doc(params, rest))
         .append(D::text(") "))
         .append(body.to_doc())
 }
 
+/// `x, y`, or `x, ...y` when the last parameter is a rest parameter
+fn params_to_doc(params: &[Id], rest: bool) -> D<'_, ()> {
+    let last = params.len().wrapping_sub(1);
+    D::intersperse(
+        params.iter().enumerate().map(|(i, x)| {
+            if rest && i == last {
+                D::text("...").append(x.to_doc())
+            } else {
+                x.to_doc()
+            }
+        }),
+        D::text(", "),
+    )
+}
+
 #[cfg(test)]
 mod test {
     use crate::javascript::parse;
@@ -535,4 +745,25 @@ mod test {
         "#,
         );
     }
+
+    #[test]
+    fn rest_params() {
+        parse_pretty_parse(
+            r#"
+            function f(x, ...ys) { return ys; }
+            var g = (...zs) => zs;
+        "#,
+        );
+    }
+
+    #[test]
+    fn optional_chain() {
+        parse_pretty_parse(
+            r#"
+            a?.b.c;
+            a?.[k]?.(1, 2).d;
+            x ?? y;
+        "#,
+        );
+    }
 }
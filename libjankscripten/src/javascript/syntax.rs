@@ -71,6 +71,8 @@ pub enum AssignOp {
 pub enum LogicalOp {
     Or,
     And,
+    /// `??`
+    Nullish,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -91,12 +93,18 @@ pub enum Num {
 pub enum Lit {
     String(String),
     Regex(String, String), // TODO(arjun): The Regex is not properly parsed
+    /// A BigInt literal, such as `10n`, with its decimal digits
+    BigInt(String),
     Bool(bool),
     Null,
     Num(Num),
     Undefined,
 }
 
+/// The key of the `Symbol.iterator` method of an iterable. We do not have
+/// symbols, so `Symbol.iterator` is this string instead.
+pub const ITERATOR_KEY: &str = "@@iterator";
+
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Hash)]
 pub enum Key {
     Int(i32),
@@ -116,6 +124,49 @@ impl<T: Into<Id>> From<T> for LValue {
     }
 }
 
+/// A destructuring pattern. Patterns only appear in `Expr::Destructure`,
+/// which is eliminated by `desugar_destructuring`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Pattern {
+    /// A variable in a declaration, or any lvalue in an assignment
+    LValue(LValue),
+    /// `[p1, , p3, ...rest]`, where `None` is a hole
+    Array(Vec<Option<Pattern>>, Option<Box<Pattern>>),
+    /// `{ k1: p1, k2: p2, ...rest }`
+    Object(Vec<(Key, Pattern)>, Option<Box<Pattern>>),
+    /// `p = e`, where `e` is used when the value is `undefined`
+    Default(Box<Pattern>, Box<Expr>),
+}
+
+/// One link in an optional chain, e.g., `.b`, `[k]` or `(args)` in
+/// `a?.b[k](args)`
+#[derive(Debug, PartialEq, Clone)]
+pub enum ChainLink {
+    Dot(Id),
+    Bracket(Expr),
+    Call(Vec<Expr>),
+}
+
+/// A class body. Classes are desugared into constructor functions by
+/// `desugar_classes`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Class {
+    pub name: Option<Id>,
+    pub extends: Option<Box<Expr>>,
+    /// The `Expr::Func` of the constructor, if it was written explicitly
+    pub constructor: Option<Box<Expr>>,
+    pub methods: Vec<ClassMethod>,
+}
+
+/// `static key(params) { body }` within a class, where `func` is an
+/// `Expr::Func`
+#[derive(Debug, PartialEq, Clone)]
+pub struct ClassMethod {
+    pub is_static: bool,
+    pub key: Key,
+    pub func: Expr,
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum Expr {
     Lit(Lit, Pos),
@@ -131,17 +182,78 @@ pub enum Expr {
     UnaryAssign(UnaryAssignOp, Box<LValue>, Pos),
     If(Box<Expr>, Box<Expr>, Box<Expr>, Pos),
     Assign(AssignOp, Box<LValue>, Box<Expr>, Pos),
+    /// `Destructure(pattern, e, Pos)` is the assignment `pattern = e`, and
+    /// produces the value of `e`
+    Destructure(Box<Pattern>, Box<Expr>, Pos),
     Call(Box<Expr>, Vec<Expr>, Pos),
+    /// `CallWithThis(f, this_arg, args, Pos)` calls the function `f` with
+    /// `this` bound to `this_arg`, i.e. `f.call(this_arg, args...)`. This
+    /// only appears before `desugar_this`, which passes `this_arg` directly.
+    CallWithThis(Box<Expr>, Box<Expr>, Vec<Expr>, Pos),
+    /// `Apply(f, this_arg, args, Pos)` calls `f` with the elements of the
+    /// array `args` as its arguments, i.e. `f(...args)`. When `this_arg` is
+    /// `None`, `this` is determined by `f`, as in an ordinary call. This only
+    /// appears before `desugar_this`.
+    Apply(Box<Expr>, Option<Box<Expr>>, Box<Expr>, Pos),
     MethodCall(Id, String, Vec<Expr>, Pos),
     Length(Box<Expr>, Pos),
-    Func(Option<Id>, Vec<Id>, Box<Stmt>, Pos),
+    /// `Func(name, params, rest, body, Pos)`. When `rest` is true, the last
+    /// parameter is a rest parameter, e.g., `function(x, ...ys) { }`.
+    /// `desugar_params` makes it an ordinary parameter that receives an array.
+    Func(Option<Id>, Vec<Id>, bool, Box<Stmt>, Pos),
+    /// An arrow function, `(x, y) => body`. An expression-bodied arrow is
+    /// parsed into a block that returns the expression. Arrows do not bind
+    /// their own `this`, so they are turned into `Func` by `desugar_this`.
+    Arrow(Vec<Id>, bool, Box<Stmt>, Pos),
+    Class(Box<Class>, Pos),
+    /// `super` in `super(...)`, `super.x` or `super[x]`. Eliminated by
+    /// `desugar_classes`.
+    Super,
+    /// `new.target`. Eliminated by `desugar_this`.
+    NewTarget,
     Seq(Vec<Expr>, Pos),
+    /// `OptChain(e, links, Pos)` is an optional chain, such as `e?.b.c()`.
+    /// Each link is paired with `true` if it was written with `?.`, in which
+    /// case the rest of the chain produces `undefined` when the value before
+    /// the link is `null` or `undefined`. Eliminated by `desugar_logical`.
+    OptChain(Box<Expr>, Vec<(bool, ChainLink)>, Pos),
+    /// The body of a generator function, which produces the iterator that
+    /// runs the body. The parser makes `function* (params) { body }` into
+    /// `function (params) { return Generator(() => { body }); }`, so that
+    /// the generator function is an ordinary function. The arrow resumes the
+    /// body, and `desugar_yield` gives it its parameters. Eliminated by
+    /// `desugar_generators`.
+    Generator(Box<Expr>, Pos),
+    /// `Yield(e, delegate, Pos)` is `yield e`, or `yield* e` when `delegate`
+    /// is true. `desugar_yield` makes every `yield` a statement of its own.
+    Yield(Box<Expr>, bool, Pos),
+    /// The body of an async function, which produces the promise that the
+    /// body settles. Like `Generator`, the parser makes
+    /// `async function (params) { body }` into
+    /// `function (params) { return Async(() => { body }); }`. The body is
+    /// desugared into a state machine like the body of a generator, which
+    /// `await` suspends. Eliminated by `desugar_generators`.
+    Async(Box<Expr>, Pos),
+    /// `await e`. `desugar_yield` makes it a `yield` of its own.
+    Await(Box<Expr>, Pos),
+}
+
+/// The keyword that introduces a declaration. `block_scoping` gives every
+/// `let` and `const` a unique name and eliminates `const`, so only `var` and
+/// `let` remain after it. A `let` within a loop gets a fresh binding each
+/// time it runs, so `lift_vars` does not lift it.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum VarKind {
+    Var,
+    Let,
+    Const,
 }
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct VarDecl {
     pub name: Id,
     pub named: Box<Expr>,
+    pub kind: VarKind,
 }
 
 #[derive(Debug, PartialEq, Clone)]
@@ -160,9 +272,12 @@ pub enum Stmt {
     While(Box<Expr>, Box<Stmt>, Pos),
     DoWhile(Box<Stmt>, Box<Expr>, Pos),
     For(ForInit, Box<Expr>, Box<Expr>, Box<Stmt>, Pos),
-    /// `ForIn(true, x, .., Pos)` indicates `for (var x ...`.
-    /// `ForIn(false, x, .., Pos)` indicates `for (x ...`.
-    ForIn(bool, Id, Box<Expr>, Box<Stmt>, Pos),
+    /// `ForIn(Some(VarKind::Var), x, .., Pos)` indicates `for (var x ...`.
+    /// `ForIn(None, x, .., Pos)` indicates `for (x ...`.
+    ForIn(Option<VarKind>, Id, Box<Expr>, Box<Stmt>, Pos),
+    /// `ForOf(Some(VarKind::Var), x, .., Pos)` indicates `for (var x of ...`.
+    /// `ForOf(None, x, .., Pos)` indicates `for (x of ...`.
+    ForOf(Option<VarKind>, Id, Box<Expr>, Box<Stmt>, Pos),
     Label(Id, Box<Stmt>, Pos),
     Break(Option<Id>, Pos),
     Continue(Option<Id>, Pos),
@@ -176,10 +291,51 @@ pub enum Stmt {
     /// Could be:
     /// `var x = 10, y = 30;`
     VarDecl(Vec<VarDecl>, Pos),
-    Func(Id, Vec<Id>, Box<Stmt>, Pos),
+    /// `Func(name, params, rest, body, Pos)`, as in `Expr::Func`
+    Func(Id, Vec<Id>, bool, Box<Stmt>, Pos),
     Return(Box<Expr>, Pos),
 }
 
+/// An ES module, whose import and export declarations are separated from its
+/// body. An exported declaration stays in the body, and `export default e`
+/// becomes the declaration `const *default* = e`, which is the name that
+/// ECMAScript gives to the variable.
+#[derive(Debug)]
+pub struct Module {
+    pub body: Vec<Stmt>,
+    /// The modules that this one imports from or re-exports, as written, in
+    /// the order they appear
+    pub requests: Vec<(String, Pos)>,
+    pub imports: Vec<Import>,
+    pub exports: Vec<Export>,
+}
+
+/// `import { name as local } from "source"`, where `name` is `None` in
+/// `import * as local from "source"`
+#[derive(Debug)]
+pub struct Import {
+    pub local: Id,
+    pub name: Option<Id>,
+    pub source: String,
+    pub pos: Pos,
+}
+
+#[derive(Debug)]
+pub enum Export {
+    /// `Local(name, local, pos)` is `export { local as name }`
+    Local(Id, Id, Pos),
+    /// `export { name as exported } from "source"`, where `name` is `None`
+    /// in `export * as exported from "source"`
+    Indirect {
+        exported: Id,
+        name: Option<Id>,
+        source: String,
+        pos: Pos,
+    },
+    /// `export * from "source"`
+    Star(String),
+}
+
 impl Expr {
     /// Produces `true` if the expression can be freely copied without altering the order of
     /// effects or invalidating object identities.
@@ -194,11 +350,46 @@ impl Expr {
             Expr::Array(_, _) => false,
             Expr::Object(_, _) => false,
             Expr::Func(..) => false,
+            Expr::Arrow(..) => false,
+            Expr::Class(..) => false,
             _ => false,
         }
     }
 }
 
+impl Pattern {
+    /// The variables that the pattern assigns to, in order
+    pub fn bound_ids(&self) -> Vec<Id> {
+        let mut ids = vec![];
+        self.collect_bound_ids(&mut ids);
+        ids
+    }
+
+    fn collect_bound_ids(&self, ids: &mut Vec<Id>) {
+        match self {
+            Pattern::LValue(LValue::Id(x)) => ids.push(x.clone()),
+            Pattern::LValue(_) => (),
+            Pattern::Array(elems, rest) => {
+                for p in elems.iter().flatten() {
+                    p.collect_bound_ids(ids);
+                }
+                if let Some(p) = rest {
+                    p.collect_bound_ids(ids);
+                }
+            }
+            Pattern::Object(props, rest) => {
+                for (_, p) in props {
+                    p.collect_bound_ids(ids);
+                }
+                if let Some(p) = rest {
+                    p.collect_bound_ids(ids);
+                }
+            }
+            Pattern::Default(p, _) => p.collect_bound_ids(ids),
+        }
+    }
+}
+
 impl LValue {
     pub fn is_essentially_atom(&self) -> bool {
         match self {
@@ -6,6 +6,115 @@ use std::process::{Command, Stdio};
 
 const WIDTH: usize = 80;
 
+/// `print(x)` prints `x`, before and after desugaring, which passes `this`
+/// to it explicitly. Programs that run code after the final expression is
+/// evaluated, such as async functions, can only be compared by what they
+/// print.
+const PRINT: &str = r#"
+function print() {
+    console.log(arguments[arguments.length - 1]);
+}
+"#;
+
+/// The helpers that desugared programs call on `__JNKS`, which are
+/// implemented by the NotWasm runtime. They use our calling convention, so
+/// they receive `this` explicitly, and so do the `Symbol.iterator` and `next`
+/// methods of iterables that were defined in the program.
+const RUNTIME: &str = r#"
+var __JNKS = (function () {
+    var end = {};
+    function iter(_, v) {
+        if (typeof v === "string" || Array.isArray(v)) {
+            return v[Symbol.iterator]();
+        }
+        var it = v[Symbol.iterator](v);
+        return {
+            next: function () { return it.next(it); },
+            return: it.return && function () { return it.return(it); }
+        };
+    }
+    function iter_next(_, it) {
+        it.done = true;
+        var r = it.next();
+        it.done = r.done;
+        return r.done ? end : r.value;
+    }
+    function iter_close(_, it) {
+        if (!it.done && it.return) {
+            it.done = true;
+            it.return();
+        }
+    }
+    function iter_done(_, x) {
+        return x === end;
+    }
+    function iter_take(_, v, n) {
+        if (Array.isArray(v)) {
+            return v;
+        }
+        var it = iter(_, v), taken = [], x;
+        while (taken.length !== n && (x = iter_next(_, it)) !== end) {
+            taken.push(x);
+        }
+        if (taken.length === n) {
+            iter_close(_, it);
+        }
+        return taken;
+    }
+    function for_in(_, v) {
+        return (function* () {
+            for (var k in v) {
+                yield k;
+            }
+        })();
+    }
+    function variadic(_, f) {
+        // a function that takes `new.target` is not called with `new` here
+        if (f.length === 3) {
+            return function (t, ...args) {
+                return f(t, undefined, args);
+            };
+        }
+        return function (t, ...args) {
+            return f(t, args);
+        };
+    }
+    function non_constructor(_, f) {
+        return f;
+    }
+    function rest_args(_, args, n) {
+        return args.slice(n);
+    }
+    function throw_error(_, name, message) {
+        var e = new Error(message);
+        e.name = name;
+        throw e;
+    }
+    function async_run(_, resume) {
+        return new Promise(function (resolve, reject) {
+            function step(mode, sent) {
+                var r;
+                try {
+                    r = resume(undefined, mode, sent);
+                } catch (e) {
+                    return reject(e);
+                }
+                if (r.done) {
+                    return resolve(r.value);
+                }
+                Promise.resolve(r.value).then(function (v) { step(0, v); },
+                                              function (e) { step(1, e); });
+            }
+            step(0, undefined);
+        });
+    }
+    return { iter: iter, iter_next: iter_next, iter_done: iter_done, iter_take: iter_take,
+             iter_close: iter_close,
+             for_in: for_in, variadic: variadic, non_constructor: non_constructor,
+             rest_args: rest_args, throw_error: throw_error, async_run: async_run };
+})();
+"#;
+
 /// expect the result (last expression) to be the same when evaluating the
 /// original block versus the second. only works with blocks because of the
 /// nature of "result" / "value" as understood by V8
@@ -17,11 +126,18 @@ pub fn expect_same(original: &js::Stmt, modified: &js::Stmt) {
         "original script: {}\n desugared: {}",
         original_script, modified_script
     );
-    let original_res = run_script(&original_script);
-    let modified_res = run_script(&modified_script);
+    let original_res = run_script(&format!("{}{}", PRINT, original_script));
+    let modified_res = run_script(&format!("{}{}{}", PRINT, RUNTIME, modified_script));
     assert_eq!(original_res, modified_res);
 }
 
+/// expect the result (last expression) of the program to be `expected`
+pub fn expect_result(program: &js::Stmt, expected: &str) {
+    let script = program.to_pretty(WIDTH);
+    println!("script: {}", script);
+    assert_eq!(run_script(&script).trim(), expected);
+}
+
 fn run_script(script: &str) -> String {
     let mut run = Command::new("node")
         // this prints the final expression
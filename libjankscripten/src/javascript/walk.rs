@@ -129,6 +129,17 @@ impl<'a> Loc<'a> {
         }
     }
 
+    /// Produces 'true' if the current node is within a loop, but not nested inside a function. Thus,
+    /// the node may run more than once each time the function is applied.
+    pub fn in_loop(&self) -> bool {
+        match self {
+            Loc::Top => false,
+            Loc::Node(Context::Loop, _) => true,
+            Loc::Node(Context::FunctionBody, _) => false,
+            Loc::Node(_, rest) => rest.in_loop(),
+        }
+    }
+
     pub fn enclosing_block(&self) -> Option<&'a BlockContext> {
         match self {
             Loc::Top => None,
@@ -192,7 +203,7 @@ where
             }
             // 1x{ .., Stmt }
             VarDecl(vds, _) => {
-                for super::VarDecl { name: _, named } in vds {
+                for super::VarDecl { named, .. } in vds {
                     let loc = Loc::Node(Context::VarDeclRhs, loc);
                     self.walk_expr(named, &loc);
                 }
@@ -203,7 +214,7 @@ where
                 self.walk_expr(a, &loc);
             }
             // 1xExpr, 1xStmt
-            DoWhile(st, e, _) | ForIn(.., e, st, _) | While(e, st, _) => {
+            DoWhile(st, e, _) | ForIn(.., e, st, _) | ForOf(.., e, st, _) | While(e, st, _) => {
                 let loc = Loc::Node(Context::Loop, loc);
                 self.walk_expr(e, &loc);
                 self.walk_stmt(st, &loc);
@@ -241,13 +252,13 @@ where
         self.visitor.enter_expr(expr, loc);
         match expr {
             // 0
-            Lit(_, _) | This | Id(_, _) => (),
+            Lit(_, _) | This | Super | NewTarget | Id(_, _) => (),
             // 1xLValue
             UnaryAssign(.., lv, _) => {
                 let loc = Loc::Node(Context::Expr, loc);
                 self.walk_lval(lv, &loc);
             }
-            Func(.., st, _) => {
+            Func(.., st, _) | Arrow(.., st, _) => {
                 let loc = Loc::Node(Context::FunctionBody, loc);
                 self.walk_stmt(st, &loc);
             }
@@ -258,6 +269,22 @@ where
                     self.walk_expr(e, &loc);
                 }
             }
+            // 1xExpr, 1x[ChainLink]
+            OptChain(e, links, _) => {
+                let loc = Loc::Node(Context::Expr, loc);
+                self.walk_expr(e, &loc);
+                for (_, link) in links {
+                    match link {
+                        ChainLink::Dot(_) => (),
+                        ChainLink::Bracket(e) => self.walk_expr(e, &loc),
+                        ChainLink::Call(es) => {
+                            for e in es {
+                                self.walk_expr(e, &loc);
+                            }
+                        }
+                    }
+                }
+            }
             // 1x[(_, Expr)]
             Object(ks_es, _) => {
                 let loc = Loc::Node(Context::Expr, loc);
@@ -266,7 +293,13 @@ where
                 }
             }
             // 1xExpr
-            Dot(e, .., _) | Unary(.., e, _) | Length(e, _) => {
+            Dot(e, .., _)
+            | Unary(.., e, _)
+            | Length(e, _)
+            | Generator(e, _)
+            | Yield(e, ..)
+            | Async(e, _)
+            | Await(e, _) => {
                 let loc = Loc::Node(Context::Expr, loc);
                 self.walk_expr(e, &loc);
             }
@@ -277,6 +310,14 @@ where
                 let rv_loc = Loc::Node(Context::AssignRhs(*op), loc);
                 self.walk_expr(e, &rv_loc);
             }
+            // 1xExpr, 1xPattern
+            Destructure(pat, e, _) => {
+                // the right-hand side is evaluated first
+                let rv_loc = Loc::Node(Context::AssignRhs(AssignOp::Equal), loc);
+                self.walk_expr(e, &rv_loc);
+                let lv_loc = Loc::Node(Context::LValue, loc);
+                self.walk_pattern(pat, &lv_loc);
+            }
             // 1xExpr, 1x[Expr]
             New(e, es, _) | Call(e, es, _) => {
                 let loc = Loc::Node(Context::Expr, loc);
@@ -285,6 +326,35 @@ where
                     self.walk_expr(e, &loc);
                 }
             }
+            // 2xExpr, 1x[Expr]
+            CallWithThis(ea, eb, es, _) => {
+                let loc = Loc::Node(Context::Expr, loc);
+                self.walk_expr(ea, &loc);
+                self.walk_expr(eb, &loc);
+                for e in es {
+                    self.walk_expr(e, &loc);
+                }
+            }
+            Apply(f, this_arg, args, _) => {
+                let loc = Loc::Node(Context::Expr, loc);
+                self.walk_expr(f, &loc);
+                if let Some(e) = this_arg {
+                    self.walk_expr(e, &loc);
+                }
+                self.walk_expr(args, &loc);
+            }
+            Class(class, _) => {
+                let loc = Loc::Node(Context::Expr, loc);
+                if let Some(e) = &mut class.extends {
+                    self.walk_expr(e, &loc);
+                }
+                if let Some(e) = &mut class.constructor {
+                    self.walk_expr(e, &loc);
+                }
+                for m in class.methods.iter_mut() {
+                    self.walk_expr(&mut m.func, &loc);
+                }
+            }
             // 2xExpr
             Bracket(ea, eb, _) | Binary(.., ea, eb, _) => {
                 let loc = Loc::Node(Context::Expr, loc);
@@ -303,6 +373,33 @@ where
         self.visitor.exit_expr(expr, loc);
     }
 
+    pub fn walk_pattern(&mut self, pat: &mut Pattern, loc: &Loc) {
+        match pat {
+            Pattern::LValue(lv) => self.walk_lval(lv, loc),
+            Pattern::Array(elems, rest) => {
+                for p in elems.iter_mut().flatten() {
+                    self.walk_pattern(p, loc);
+                }
+                if let Some(p) = rest {
+                    self.walk_pattern(p, loc);
+                }
+            }
+            Pattern::Object(props, rest) => {
+                for (_, p) in props {
+                    self.walk_pattern(p, loc);
+                }
+                if let Some(p) = rest {
+                    self.walk_pattern(p, loc);
+                }
+            }
+            Pattern::Default(p, e) => {
+                self.walk_pattern(p, loc);
+                let loc = Loc::Node(Context::Expr, loc);
+                self.walk_expr(e, &loc);
+            }
+        }
+    }
+
     /// like [Stmt::walk], but as a method on LValue. does the *exact*
     /// same thing
     pub fn walk_lval(&mut self, lval: &mut LValue, loc: &Loc) {
@@ -1,6 +1,7 @@
 //! the jankscripten system without IO/main
 
 use std::collections::HashMap;
+use std::path::Path;
 pub mod jankyscript;
 pub mod javascript;
 pub mod notwasm;
@@ -13,22 +14,25 @@ pub mod shared;
 mod string_escaping;
 mod z3ez;
 
-pub fn javascript_to_wasm<F, G>(
+/// Compile the JavaScript program whose entry module is at `entry`. `load`
+/// reads the source code of each module that the program imports.
+pub fn javascript_to_wasm<F, G, L>(
     mut opts: opts::Opts,
-    src_name: &str,
-    js_code: &str,
+    entry: &Path,
+    load: L,
     inspect_janky: F,
     inspect_notwasm: G,
 ) -> Result<(Vec<u8>, HashMap<String, u32>), Box<dyn std::error::Error>>
 where
     F: FnOnce(&jankyscript::syntax::Stmt) -> (),
     G: FnOnce(&notwasm::syntax::Program) -> (),
+    L: FnMut(&Path) -> std::io::Result<String>,
 {
-    let mut js_ast = javascript::parse(src_name, js_code)?;
     let mut ng = shared::NameGen::default();
-    javascript::desugar(&mut js_ast, &mut ng);
+    let mut js_ast = javascript::link_modules(entry, load, &mut ng)?;
+    javascript::desugar(&mut js_ast, &mut ng)?;
     let mut janky_ast = jankyscript::from_js::from_javascript(js_ast);
-    jankyscript::compile(&mut janky_ast, inspect_janky).unwrap();
+    jankyscript::compile(&mut janky_ast, inspect_janky)?;
     let notwasm_ast = notwasm::from_jankyscript(janky_ast);
     notwasm::compile(&mut opts, notwasm_ast, inspect_notwasm)
 }
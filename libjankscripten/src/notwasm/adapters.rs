@@ -0,0 +1,136 @@
+//! generate the functions that call closures of any arity
+//!
+//! A closure is called with exactly as many arguments as it takes, so calling
+//! a function in an any with a different number of arguments, or applying it
+//! to an array, needs a call for each arity. Rather than supporting a fixed
+//! set of arities in stdlib.notwasm, we generate these calls for every arity
+//! that the program uses:
+//!
+//! - `jnks_fit_<n>` is a closure that takes n arguments, including `this`.
+//!   When a function is taken out of an any to be called with n arguments,
+//!   but it takes a different number of arguments, the runtime closes
+//!   `jnks_fit_<n>` over the function instead (see `any_to_closure` in
+//!   runtime/src/any_value.rs), which applies the function to the arguments
+//!   with `jnks_apply`.
+//!
+//! - `jnks_apply_arity(f, this_arg, args, arity)` calls `f`, which takes
+//!   `arity` arguments, with `this_arg` and the elements of `args`. A missing
+//!   argument is `undefined` (`array_index` past the end), and extra
+//!   arguments are dropped. `jnks_apply` in stdlib.notwasm calls it.
+//!
+//! Every closure that is called, taken out of an any, or put into one, is a
+//! function of the program, or a closure of a type in the program, so it is
+//! enough to support the largest arity of these.
+
+use super::parse;
+use super::syntax::*;
+use super::translation::arity;
+use super::walk::*;
+
+pub fn adapters(program: &mut Program) -> Program {
+    let mut v = MaxArity(1);
+    for func in program.functions.values() {
+        v.fn_type(&func.fn_type);
+    }
+    program.walk(&mut v);
+    let mut src = String::new();
+    for n in 1..=v.0 {
+        src.push_str(&fit(n));
+    }
+    src.push_str(&apply_arity(v.0));
+    parse("adapters.notwasm", src)
+}
+
+/// The largest arity of the closures in the program, which does not count
+/// the environment
+struct MaxArity(usize);
+
+impl MaxArity {
+    fn fn_type(&mut self, fn_ty: &FnType) {
+        self.0 = self.0.max(arity(fn_ty));
+    }
+}
+
+impl Visitor for MaxArity {
+    fn enter_expr(&mut self, expr: &mut Expr, _loc: &mut Loc) {
+        match expr {
+            Expr::ClosureCall(_, args, _) | Expr::AnyMethodCall(_, _, args, ..) => {
+                self.0 = self.0.max(args.len());
+            }
+            _ => (),
+        }
+    }
+
+    fn enter_atom(&mut self, atom: &mut Atom, _loc: &mut Loc) {
+        if let Atom::FromAny(_, Type::Closure(fn_ty), _) = atom {
+            self.fn_type(fn_ty);
+        }
+    }
+}
+
+/// `jnks_fit_<n>`, which puts its arguments but `this` in an array
+fn fit(n: usize) -> String {
+    let params = (0..n - 1)
+        .map(|i| format!(", a{}: any", i))
+        .collect::<String>();
+    let pushes = (0..n - 1)
+        .map(|i| format!("    var len{} = push(args, a{});\n", i, i))
+        .collect::<String>();
+    format!(
+        "function jnks_fit_{n}(dummyEnv: env, this: any{params}): any {{
+    var f = env.0: any;
+    var new_array = rt(array_new);
+    var push = rt(array_push);
+    var args = new_array();
+{pushes}    var args_any = any(args);
+    var r = jnks_apply(dummyEnv, undefined, f, this, args_any);
+    return r;
+}}
+
+",
+        n = n,
+        params = params,
+        pushes = pushes
+    )
+}
+
+/// `jnks_apply_arity`, which has one call for each arity up to `max`
+fn apply_arity(max: usize) -> String {
+    let mut src = String::from(
+        "function jnks_apply_arity(f: any, this_arg: any, arr: Array, arity: i32): any {
+    var index = rt(array_index);
+",
+    );
+    for n in 1..=max {
+        let ty = vec!["any"; n].join(", ");
+        let mut args = vec!["this_arg".to_string()];
+        src.push_str(&format!(
+            "    if (arity == {n}) {{
+        var f{n} = f as clos(env, {ty}) -> any;
+",
+            n = n,
+            ty = ty
+        ));
+        for i in 0..n - 1 {
+            src.push_str(&format!(
+                "        var i{n}_{i} = {i};
+        var a{n}_{i} = index(arr, i{n}_{i});
+",
+                n = n,
+                i = i
+            ));
+            args.push(format!("a{}_{}", n, i));
+        }
+        src.push_str(&format!(
+            "        var r{n} = f{n}!({args});
+        return r{n};
+    }} else {{ }}
+",
+            n = n,
+            args = args.join(", ")
+        ));
+    }
+    // unreachable, since no closure takes more than `max` arguments
+    src.push_str("    return undefined;\n}\n");
+    src
+}
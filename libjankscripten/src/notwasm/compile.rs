@@ -18,6 +18,8 @@ where
     std::mem::swap(&mut src, &mut opts.notwasm_stdlib_source_code);
     let notwasm_std_lib = parse("std_lib.notwasm", src);
     program.merge_in(notwasm_std_lib);
+    let adapters = adapters::adapters(&mut program);
+    program.merge_in(adapters);
 
     type_checking::type_check(&mut program)?;
     let inverted_interned_strings = intern(&mut program);
@@ -97,6 +97,7 @@ use super::super::rope::Rope;
 use super::constructors::*;
 use super::syntax::*;
 use crate::pos::Pos;
+use crate::rts_function::RTSFunction;
 use crate::shared::methods::METHODS_TABLE;
 use crate::shared::NameGen;
 use std::collections::HashMap;
@@ -104,7 +105,7 @@ use std::collections::HashMap;
 fn compile_lit(lit: J::Lit) -> Lit {
     match lit {
         J::Lit::String(state) => Lit::String(state),
-        J::Lit::Regex(_, _) => todo!("regex not supported anywhere in toolchain"),
+        J::Lit::Regex(..) | J::Lit::BigInt(..) => unreachable!("{:?} is a runtime call", lit),
         J::Lit::Bool(b) => Lit::Bool(b),
         J::Lit::Null => Lit::Null,
         J::Lit::Undefined => Lit::Undefined,
@@ -125,10 +126,18 @@ impl S {
         self.namegen.fresh("anf")
     }
 
-    fn new_function(&mut self, name: Id, f: Function) {
-        // TODO(arjun): we will have to rename functions if there are two with the same name in
-        // different scopes.
-        self.functions.insert(name, f);
+    /// Adds the function, and returns its name. Functions are named by their
+    /// position, and desugaring can produce several functions at the same
+    /// position, so a name that is taken gets a suffix.
+    fn new_function(&mut self, name: Id, f: Function) -> Id {
+        let mut unique = name.clone();
+        let mut suffix = 1;
+        while self.functions.contains_key(&unique) {
+            unique = format!("{}_{}", name, suffix).into();
+            suffix += 1;
+        }
+        self.functions.insert(unique.clone(), f);
+        unique
     }
 }
 
@@ -216,23 +225,129 @@ fn compile_exprs<'a>(
     return stmts.append(cxt(state, ids));
 }
 
-fn coercion_to_expr(c: J::Coercion, a: Atom, p: Pos) -> Atom {
+/// Coerce `a`, and send the result to the context. A function coercion creates a proxy closure,
+/// which coerces the arguments and the result of each call, and the rest are atoms.
+fn compile_coercion<'a>(
+    state: &'a mut S,
+    c: J::Coercion,
+    a: Atom,
+    p: Pos,
+    cxt: C<'a>,
+) -> Rope<Stmt> {
     use J::Coercion::*;
     match c {
-        FloatToInt => Atom::FloatToInt(Box::new(a), p),
-        IntToFloat => Atom::IntToFloat(Box::new(a), p),
-        Tag(..) => to_any_(a, p),
-        Untag(ty) => from_any_(a, ty.notwasm_typ(true), p),
-        Fun(..) => todo!(), // TODO(michael) needs to call something that proxies the function
-        Id(..) => a,
-        Seq(c1, c2) => coercion_to_expr(*c2, coercion_to_expr(*c1, a, p.clone()), p),
+        FloatToInt => cxt.recv_a(state, Atom::FloatToInt(Box::new(a), p)),
+        IntToFloat => cxt.recv_a(state, Atom::IntToFloat(Box::new(a), p)),
+        Tag(..) => cxt.recv_a(state, to_any_(a, p)),
+        Untag(ty) => cxt.recv_a(state, from_any_(a, ty.notwasm_typ(true), p)),
+        Fun(args, ret) => {
+            let original_ty = Fun(args.clone(), ret.clone()).types().0;
+            let proxy = compile_proxy(state, args, *ret, p.clone());
+            cxt.recv_e(
+                state,
+                Expr::Proxy(proxy, a, original_ty.notwasm_typ(true), p),
+            )
+        }
+        Id(..) => cxt.recv_a(state, a),
+        Seq(c1, c2) => compile_coercion(
+            state,
+            *c1,
+            a,
+            p.clone(),
+            C::a(move |state, a| compile_coercion(state, *c2, a, p, cxt)),
+        ),
         Meta(..) => panic!("Meta coerce remains {}", p),
     }
 }
 
+/// Compile the proxy for the function coercion `Fun(args, ret)`, and return its name. The proxy
+/// is closed over the original function:
+///
+/// ```text
+/// function $proxy(env: env, x_1: S_1, ...): T' {
+///     var f = env.0: clos(S'_1, ...) -> T;
+///     return ret(f!(args_1(x_1), ...));
+/// }
+/// ```
+fn compile_proxy(state: &mut S, args: Vec<J::Coercion>, ret: J::Coercion, p: Pos) -> Id {
+    let (ret_from, ret_to) = ret.types();
+    let (arg_froms, arg_tos): (Vec<_>, Vec<_>) = args.iter().map(|c| c.types()).unzip();
+    let original_ty = J::Type::Function(arg_tos, Box::new(ret_from));
+    let params: Vec<Id> = args.iter().map(|_| state.fresh()).collect();
+    let f = state.fresh();
+    let mut body = Rope::singleton(Stmt::Var(
+        VarStmt::new(
+            f.clone(),
+            Expr::Atom(
+                Atom::EnvGet(0, original_ty.notwasm_typ(true), p.clone()),
+                p.clone(),
+            ),
+        ),
+        p.clone(),
+    ));
+    let mut arg_ids = Vec::new();
+    for (c, x) in args.into_iter().zip(params.iter()) {
+        body = body.append(compile_coercion(
+            state,
+            c,
+            Atom::Id(x.clone(), p.clone()),
+            p.clone(),
+            C::id(|_s, y| {
+                arg_ids.push(y);
+                Rope::nil()
+            }),
+        ));
+    }
+    let result = state.fresh();
+    body = body.append(Rope::singleton(Stmt::Var(
+        VarStmt::new(result.clone(), Expr::ClosureCall(f, arg_ids, p.clone())),
+        p.clone(),
+    )));
+    body = body.append(compile_coercion(
+        state,
+        ret,
+        Atom::Id(result, p.clone()),
+        p.clone(),
+        C::a(|_s, a| Rope::singleton(Stmt::Return(a, p.clone()))),
+    ));
+    let proxy = Function {
+        body: rope_to_block(body, p.clone()),
+        params: std::iter::once(Id::Bogus("env")).chain(params).collect(),
+        fn_type: FnType {
+            args: std::iter::once(Type::Env)
+                .chain(arg_froms.iter().map(|t| t.notwasm_typ(true)))
+                .collect(),
+            result: Some(Box::new(ret_to.notwasm_typ(true))),
+        },
+        span: Default::default(),
+    };
+    state.new_function("$proxy".into(), proxy)
+}
+
 fn compile_expr<'a>(state: &'a mut S, expr: J::Expr, cxt: C<'a>) -> Rope<Stmt> {
     match expr {
         J::Expr::JsOp(..) => panic!("impossible case: cannot compile JsOp to WebAssembly"),
+        // /pattern/flags => regexp_new("pattern", "flags")
+        J::Expr::Lit(J::Lit::Regex(pattern, flags), p) => {
+            let args = vec![
+                J::Expr::Lit(J::Lit::String(pattern), p.clone()),
+                J::Expr::Lit(J::Lit::String(flags), p.clone()),
+            ];
+            compile_expr(
+                state,
+                J::Expr::PrimCall(RTSFunction::NewRegExp, args, p),
+                cxt,
+            )
+        }
+        // 10n => bigint_from_str("10")
+        J::Expr::Lit(J::Lit::BigInt(digits), p) => {
+            let args = vec![J::Expr::Lit(J::Lit::String(digits), p.clone())];
+            compile_expr(
+                state,
+                J::Expr::PrimCall(RTSFunction::NewBigInt, args, p),
+                cxt,
+            )
+        }
         J::Expr::Lit(lit, p) => cxt.recv_a(state, Atom::Lit(compile_lit(lit), p)),
         J::Expr::Array(members, p) => compile_exprs(state, members, move |state, member_ids| {
             let array_name = state.fresh();
@@ -263,7 +378,7 @@ fn compile_expr<'a>(state: &'a mut S, expr: J::Expr, cxt: C<'a>) -> Rope<Stmt> {
                 for (key, id) in keys.into_iter().zip(ids) {
                     let key_str = match key {
                         J::Key::Str(state) => state,
-                        J::Key::Int(_) => todo!(),
+                        J::Key::Int(n) => n.to_string(),
                     };
                     rv = rv.append(Rope::singleton(Stmt::Expression(
                         Expr::ObjectSet(
@@ -305,7 +420,10 @@ fn compile_expr<'a>(state: &'a mut S, expr: J::Expr, cxt: C<'a>) -> Rope<Stmt> {
                             cxt.recv_a(state, prim_app_("array_index", vec![c, f], p))
                         }
                         J::Type::DynObject => cxt.recv_a(state, object_get_(c, f, p)),
-                        J::Type::String => todo!("string index???"),
+                        J::Type::String => {
+                            cxt.recv_a(state, prim_app_("string_index", vec![c, f], p))
+                        }
+                        J::Type::Any => cxt.recv_a(state, prim_app_("any_index", vec![c, f], p)),
                         _ => panic!("non-array non-object index {}", t),
                     }),
                 )
@@ -314,7 +432,7 @@ fn compile_expr<'a>(state: &'a mut S, expr: J::Expr, cxt: C<'a>) -> Rope<Stmt> {
         J::Expr::Coercion(coercion, e, p) => compile_expr(
             state,
             *e,
-            C::a(move |state, a| cxt.recv_a(state, coercion_to_expr(coercion, a, p))),
+            C::a(move |state, a| compile_coercion(state, coercion, a, p, cxt)),
         ),
         J::Expr::Id(x, _, p) => cxt.recv_a(state, Atom::Id(x, p)),
         J::Expr::Func(_, _) => {
@@ -349,7 +467,7 @@ fn compile_expr<'a>(state: &'a mut S, expr: J::Expr, cxt: C<'a>) -> Rope<Stmt> {
             )
             .into();
             let f = compile_function(state, f, p.clone());
-            state.new_function(name.clone(), f);
+            let name = state.new_function(name, f);
             // compile the environment, adapted from compile_exprs
             let mut env_items = Vec::new();
             let mut stmts = Rope::new();
@@ -425,6 +543,13 @@ fn compile_expr<'a>(state: &'a mut S, expr: J::Expr, cxt: C<'a>) -> Rope<Stmt> {
                                 J::Type::DynObject => {
                                     cxt.recv_e(state, Expr::ObjectSet(cont, f, a, p))
                                 }
+                                J::Type::Any => cxt.recv_e(
+                                    state,
+                                    Expr::Atom(
+                                        prim_app_("any_set_index", vec![cont, f, a], p.clone()),
+                                        p,
+                                    ),
+                                ),
                                 _ => panic!("bad bracket lvalue type"),
                             }),
                         )
@@ -544,8 +669,21 @@ fn compile_stmt<'a>(state: &'a mut S, stmt: J::Stmt) -> Rope<Stmt> {
             *e,
             // We could use a C::e context. However, the C::a context will make generated code
             // easier to understand in trivial examples. A C::e context would discard useless
-            // binary operations.
-            C::a(|_s, _a_notwasm| Rope::nil()),
+            // binary operations. An atom that may have an effect, such as reading a property
+            // that has a getter, must still be evaluated, so we name it.
+            C::a(|s, a_notwasm| match a_notwasm {
+                Atom::Lit(..) | Atom::Id(..) | Atom::GetPrimFunc(..) | Atom::EnvGet(..) => {
+                    Rope::nil()
+                }
+                a_notwasm => {
+                    let x = s.fresh();
+                    let p = a_notwasm.pos().clone();
+                    Rope::singleton(Stmt::Var(
+                        VarStmt::new(x, Expr::Atom(a_notwasm, p.clone())),
+                        p,
+                    ))
+                }
+            }),
         ),
         S::If(cond, then_branch, else_branch, p) => compile_expr(
             state,
@@ -563,21 +701,20 @@ fn compile_stmt<'a>(state: &'a mut S, stmt: J::Stmt) -> Rope<Stmt> {
             Stmt::Block(compile_stmt(state, *body).into_iter().collect(), p.clone()),
             p,
         )),
-        S::ForIn(..) => todo!("for..in in notwasm"),
         S::Label(x, body, p) => Rope::singleton(label_(
             Label::Named(x.to_pretty(80)),
             Stmt::Block(compile_stmt(state, *body).into_iter().collect(), p.clone()),
             p,
         )),
         S::Break(x, p) => Rope::singleton(Stmt::Break(Label::Named(x.to_pretty(80)), p)),
-        // TODO(luna): notwasm needs to support exceptions
-        // (this just executes the statement with no continuation; in jankyp
-        // we discovered that in most benchmarks, even if they use try/catch, no
-        // error is thrown)
-        S::Catch(try_stmt, _, _, _) => compile_stmt(state, *try_stmt),
-        S::Finally(_, _, _) => todo!("NotWasm needs to support exceptions"),
-        // TODO(luna): notwasm needs to support exceptions
-        S::Throw(_, _) => Rope::singleton(Stmt::Trap),
+        S::Catch(try_stmt, x, catch_stmt, p) => Rope::singleton(Stmt::Try(
+            Box::new(compile_stmt_block(state, *try_stmt, p.clone())),
+            x,
+            Box::new(compile_stmt_block(state, *catch_stmt, p.clone())),
+            p,
+        )),
+        S::Finally(..) => unreachable!("finally is eliminated by desugar_finally"),
+        S::Throw(e, p) => compile_expr(state, *e, C::a(|_s, a| Rope::singleton(Stmt::Throw(a, p)))),
         S::Return(e, p) => {
             compile_expr(state, *e, C::a(|_s, a| Rope::singleton(Stmt::Return(a, p))))
         }
@@ -1,4 +1,5 @@
 //! NotWasm: It is not WebAssembly, but quite close to it.
+mod adapters;
 mod compile;
 #[allow(dead_code)]
 mod constructors;
@@ -65,6 +65,7 @@ Type -> Type :
   | 'str'                 { Type::String }
   | 'Array'               { Type::Array }
   | 'DynObject'           { Type::DynObject }
+  | 'bigint'              { Type::BigInt }
   | FnType                { Type::Fn($1) }
   | 'clos' FnType         { Type::Closure($2) }
   | 'ptr'                 { Type::Ptr }
@@ -134,6 +135,7 @@ Expr -> Expr :
     '{' '}'                             { Expr::ObjectEmpty }
   | '!' Id '(' IdSeq ')'                { Expr::prim_call($2.into_name(), $4, pos($1)) } 
   | 'clos' '(' Id ',' IdAtomTypeSeq ')' { Expr::Closure($3, $5, pos($1)) }
+  | 'proxy' '(' Id ',' IdAtom ':' Type ')' { Expr::Proxy($3, $5, $7, pos($1)) }
   // TODO(arjun): We can infer the type annotation.
   | 'newRef' '(' Atom ',' Type ')'      { Expr::NewRef($3, $5, pos($1)) }
   | Id '!' '(' IdSeq ')'                { Expr::ClosureCall($1, $4, pos($2)) }
@@ -169,6 +171,9 @@ Stmt -> Stmt :
   | 'return' AtomAdd ';'                    { Stmt::Return($2, pos($1)) }
   | 'break' IdString ';'                    { Stmt::Break(Label::Named($2), pos($1)) }
   | 'while' '(' AtomAdd ')' Block           { while_($3, $5, pos($1)) }
+  | 'try' Block 'catch' '(' Id ')' Block
+    { Stmt::Try(Box::new($2), $5, Box::new($7), pos($1)) }
+  | 'throw' AtomAdd ';'                     { Stmt::Throw($2, pos($1)) }
   | '*' Id '=' Expr ';'                     { Stmt::Store($2, $4, pos($1)) }
   | Expr ';'                                { Stmt::Expression($1, pos($2)) }
   ; 
@@ -18,6 +18,7 @@ impl Pretty for Type {
             Type::Array => pp.text("array"),
             Type::Bool => pp.text("bool"),
             Type::DynObject => pp.text("DynObject"),
+            Type::BigInt => pp.text("bigint"),
             Type::Any => pp.text("any"),
             Type::Ref(t) => pp.concat(vec![pp.text("ref"), t.pretty(pp).parens()]),
             Type::Fn(fn_t) => fn_t.pretty(pp),
@@ -266,6 +267,9 @@ impl Pretty for Expr {
                 let no_tys: Vec<_> = env.iter().map(|(a, _)| a).collect();
                 prettyp!(pp, (seq "clos(" (id id) ", " (comma_sep no_tys) ")"))
             }
+            Expr::Proxy(id, clos, _, _) => {
+                prettyp!(pp, (seq "proxy(" (id id) ", " (id clos) ")"))
+            }
         }
     }
 }
@@ -320,6 +324,10 @@ impl Pretty for Stmt {
             Stmt::Block(stmts, _) => {
                 prettyp!(pp, (braces (seq (nest (seq line (line_sep stmts))) line)))
             }
+            Stmt::Try(body, x, handler, _) => {
+                prettyp!(pp, (seq "try" space (id body) space "catch" space (parens (id x)) space (id handler)))
+            }
+            Stmt::Throw(a, _) => prettyp!(pp, (seq "throw" space (id a) ";")),
             Stmt::Trap => pp.text("trap"),
             Stmt::Goto(lbl, _) => prettyp!(pp, (seq "goto" space (id lbl) ";")),
         }
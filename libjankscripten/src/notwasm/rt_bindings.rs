@@ -25,20 +25,20 @@ pub fn get_rt_bindings() -> BindMap {
     let a_clos = clos_ty_(vec![], None);
 
     // Step 1: Manually insert runtime functions for NotWasm.
-    insert_mono(
-        m,
-        "any_from",
-        vec![&mono],
-        &|_| Any,
-        vec![I32, Bool, a_clos.clone(), a_fn.clone()],
+    insert_mono(m, "any_from", vec![&mono], &|_| Any, vec![I32, Bool]);
+    insert_mono(m, "any_to", vec![&|_| Any], &mono, vec![I32, Bool]);
+    // a function in an any holds its arity, and is fitted to the arity that
+    // it is called with when it is taken out. see runtime/src/any_value.rs
+    m.insert("any_from_fn".into(), fn_ty_(vec![a_fn.clone(), I32], Any));
+    m.insert(
+        "any_from_closure".into(),
+        fn_ty_(vec![a_clos.clone(), I32], Any),
     );
-    insert_mono(
-        m,
-        "any_to",
-        vec![&|_| Any],
-        &mono,
-        vec![I32, Bool, a_clos.clone()],
+    m.insert(
+        "any_to_closure".into(),
+        fn_ty_(vec![Any, I32, I32], a_clos.clone()),
     );
+    m.insert("closure_proxy".into(), fn_ty_(vec![Any, a_fn], a_clos));
     // Step 2: automatically insert runtime functions from RTSFunction.
     let mut insert_rts_fn = |rts: &RTSFunction| {
         if let RTSFunctionImpl::Rust(name) = rts.name() {
@@ -52,6 +52,9 @@ pub enum Type {
     /// TODO(arjun): We do not have a type_tag called class. What is this
     /// really supposed to be? I think it is ObjectPtrPtr.
     DynObject,
+    /// If `v : BigInt` then `v` is a `*const Tag`, where
+    /// `v.type_tag == BigInt`.
+    BigInt,
     /// If `v : Fn(fn_type)` then `v` is an `i32`, which is an index of a
     /// function with the type `fn_type`.
     Fn(FnType),
@@ -102,6 +105,7 @@ impl Type {
             Type::Array => true,
             Type::Bool => false,
             Type::DynObject => true,
+            Type::BigInt => true,
             Type::Fn(_) => false,
             Type::Closure(_) => true,
             Type::Ref(_) => true,
@@ -325,6 +329,11 @@ pub enum Expr {
     /// this has to be atom, not id, because what if we need to store {x:
     /// env.x} in a nested closure
     Closure(Id, Vec<(Atom, Type)>, Pos),
+    /// `Proxy(f, clos, ty, _)` creates a closure of the function named `f`,
+    /// whose environment holds only `clos : ty`. Unlike `Closure`, the new
+    /// closure shares the function object of `clos`, so `f` can stand in for
+    /// `clos`, e.g., to coerce its arguments and result
+    Proxy(Id, Atom, Type, Pos),
 }
 
 #[derive(Debug, PartialEq)]
@@ -383,6 +392,12 @@ pub enum Stmt {
     // Break value as return?
     Return(Atom, Pos),
     Block(Vec<Stmt>, Pos),
+    /// `Try(body, x, handler, Pos)` runs `handler` with the thrown value bound
+    /// to `x : any` if `body` throws.
+    /// Concrete syntax: `try { ... } catch (<id>) { ... }`
+    Try(Box<Stmt>, Id, Box<Stmt>, Pos),
+    /// Concrete syntax: `throw <atom>;`, where the atom is an `any`
+    Throw(Atom, Pos),
     Trap,
     /// these don't exist in NotWasm, only GotoWasm. if you try to [translate]
     /// a goto, it will panic
@@ -456,6 +471,7 @@ impl std::fmt::Display for Type {
                 Ref(..) => "ref",
                 Bool => "bool",
                 DynObject => "DynObject",
+                BigInt => "bigint",
                 Fn(..) => "fn",
                 Closure(..) => "closure",
                 Any => "any",
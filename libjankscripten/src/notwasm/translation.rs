@@ -10,10 +10,12 @@ use super::super::rts_function::*;
 use super::constructors::*;
 use super::rt_bindings::get_rt_bindings;
 use super::syntax as N;
+use super::walk::Visitor;
 use crate::opts::Opts;
 use parity_wasm::builder::*;
 use parity_wasm::elements::*;
 use parity_wasm::serialize;
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::convert::TryInto;
 use Instruction::*;
@@ -133,14 +135,31 @@ pub fn translate_parity(opts: &Opts, mut program: N::Program) -> Module {
         .build();
     // Create a WebAssembly function type for each function in NotWasm. These
     // go in the table of types (type_indexes).
-    for func in program.functions.values() {
+    let mut fn_types: Vec<N::FnType> = program
+        .functions
+        .values()
+        .map(|func| func.fn_type.clone())
+        .collect();
+    // A closure may be called at a type that no function in this module has,
+    // e.g., when it came from another module, so its type needs an index too
+    let mut closure_types = ClosureTypes::default();
+    for func in program.functions.values_mut() {
+        func.body.walk(&mut closure_types);
+    }
+    fn_types.extend(closure_types.0);
+    for fn_ty in fn_types {
         // has to be wasm types to dedup properly
-        let func_ty = (
-            types_as_wasm(&func.fn_type.args.clone()),
-            option_as_wasm(&func.fn_type.result),
-        );
-        let next_index = type_indexes.len() as u32;
-        type_indexes.entry(func_ty).or_insert(next_index);
+        let func_ty = (types_as_wasm(&fn_ty.args), option_as_wasm(&fn_ty.result));
+        if let Entry::Vacant(entry) = type_indexes.entry(func_ty) {
+            let func_ty = entry.key();
+            let index = module.push_signature(
+                signature()
+                    .with_params(func_ty.0.clone())
+                    .with_results(func_ty.1.iter().cloned().collect())
+                    .build_sig(),
+            );
+            entry.insert(index);
+        }
     }
     // data segment
     for global in program.globals.values_mut() {
@@ -157,18 +176,7 @@ pub fn translate_parity(opts: &Opts, mut program: N::Program) -> Module {
             // This global var is initialized lazily. it's default value will
             // be 0. We just need to figure out if wasm is expecting an i32 or
             // i64.
-            let zero = match global.ty.as_wasm() {
-                // 32-bit signed integer
-                ValueType::I32 => I32Const(0),
-                // 64-bit signed integer
-                ValueType::I64 => I64Const(0),
-                // 32-bit float
-                ValueType::F32 => F32Const(0),
-                // 64-bit float
-                ValueType::F64 => F64Const(0),
-            };
-
-            visitor.out.push(zero);
+            visitor.out.push(zero(global.ty.as_wasm()));
         }
         let mut insts = visitor.out;
         assert_eq!(
@@ -232,7 +240,9 @@ pub fn translate_parity(opts: &Opts, mut program: N::Program) -> Module {
         rt_globals_len,
         &mut module,
     );
+    insert_generated_run_timers(&global_env, &rt_indexes, &mut module);
     let main_index = num_functions as u32;
+    let run_timers_index = main_index + 1;
     let module = module
         .data()
         .offset(GetGlobal(JNKS_STRINGS_IDX))
@@ -252,6 +262,22 @@ pub fn translate_parity(opts: &Opts, mut program: N::Program) -> Module {
         .internal()
         .func(main_index)
         .build();
+    let mut module = module
+        .export()
+        .field("jnks_run_timers")
+        .internal()
+        .func(run_timers_index)
+        .build();
+    // the host forwards the runtime's calls to getters and setters, and its
+    // requests for error objects, to these
+    for name in &["jnks_call_getter", "jnks_call_setter", "jnks_new_error"] {
+        module = module
+            .export()
+            .field(name)
+            .internal()
+            .func(stdlib_function_index(&global_env, &rt_indexes, name))
+            .build();
+    }
     module.build()
 }
 
@@ -322,6 +348,20 @@ fn translate_func(
     (func, local_map)
 }
 
+/// The zero of a WebAssembly type
+fn zero(ty: ValueType) -> Instruction {
+    match ty {
+        // 32-bit signed integer
+        ValueType::I32 => I32Const(0),
+        // 64-bit signed integer
+        ValueType::I64 => I64Const(0),
+        // 32-bit float
+        ValueType::F32 => F32Const(0),
+        // 64-bit float
+        ValueType::F64 => F64Const(0),
+    }
+}
+
 fn types_as_wasm(types: &[N::Type]) -> Vec<ValueType> {
     types.iter().map(N::Type::as_wasm).collect()
 }
@@ -363,10 +403,14 @@ struct Env {
 /// the index of `TranslateLabel::Label(l)`. When the compiler introduces an
 /// unnamed WebAssembly block, it pushes a `TranslateLabel::Unused` onto the
 /// `LabelEnv`, which ensures that indices shift correctly.
+///
+/// The body of a `try` is in a block labelled `TranslateLabel::Catch`, and
+/// breaking to that block runs the handler.
 #[derive(Clone, PartialEq, Debug)]
 enum TranslateLabel {
     Unused,
     Label(N::Label),
+    Catch,
 }
 
 /// We use `IdIndex` to resolve identifiers that appear in a NotWasm program
@@ -430,6 +474,7 @@ impl<'a> Translate<'a> {
                     panic!("tried to store into non-ref");
                 };
                 self.translate_expr(expr);
+                self.propagate_exception(env, expr);
                 self.store(ty, TAG_SIZE);
             }
             N::Stmt::Empty => (),
@@ -460,29 +505,16 @@ impl<'a> Translate<'a> {
                     // Binds variable in env after compiling expr (prevents
                     // circularity).
                     self.translate_expr(&mut var_stmt.named);
+                    self.propagate_exception(env, &var_stmt.named);
                 }
-
-                let index = self.next_id;
-                self.next_id += 1;
-                self.locals.push(var_stmt.ty().as_wasm());
-                self.id_env.insert(
-                    var_stmt.id.clone(),
-                    IdIndex::Local(index, var_stmt.ty().clone()),
-                );
-
-                // Eager shadow stack: (and setting the actual value)
+                let index = self.bind_local(&var_stmt.id, var_stmt.ty());
                 if !is_init {
-                    if self.opts.disable_gc == true || var_stmt.ty().is_gc_root() == false {
-                        self.out.push(SetLocal(index));
-                    } else {
-                        self.out.push(TeeLocal(index));
-                        self.out.push(I32Const(index.try_into().unwrap()));
-                        self.set_in_current_shadow_frame_slot(var_stmt.ty());
-                    }
+                    self.set_local(index, var_stmt.ty());
                 }
             }
             N::Stmt::Expression(expr, _) => {
                 self.translate_expr(expr);
+                self.propagate_exception(env, expr);
                 self.out.push(Drop); // side-effects only, please
             }
             N::Stmt::Assign(id, expr, _) => {
@@ -494,16 +526,12 @@ impl<'a> Translate<'a> {
                 {
                     IdIndex::Local(n, ty) => {
                         self.translate_expr(expr);
-                        if self.opts.disable_gc == true || ty.is_gc_root() == false {
-                            self.out.push(SetLocal(n));
-                        } else {
-                            self.out.push(TeeLocal(n));
-                            self.out.push(I32Const((n).try_into().unwrap()));
-                            self.set_in_current_shadow_frame_slot(&ty);
-                        }
+                        self.propagate_exception(env, expr);
+                        self.set_local(n, &ty);
                     }
                     IdIndex::Global(n, ty) => {
                         self.translate_expr(expr);
+                        self.propagate_exception(env, expr);
                         // no tee for globals
                         self.out.push(SetGlobal(n));
                         if self.opts.disable_gc == false && ty.is_gc_root() {
@@ -520,6 +548,7 @@ impl<'a> Translate<'a> {
                         // reasoning through this
                         self.out.push(GetGlobal(n));
                         self.translate_expr(expr);
+                        self.propagate_exception(env, expr);
                         self.store(ty, 0);
                     }
                     IdIndex::Fun(..) => panic!("cannot set function"),
@@ -527,6 +556,9 @@ impl<'a> Translate<'a> {
             }
             N::Stmt::If(cond, conseq, alt, _) => {
                 self.translate_atom(cond);
+                if cond.may_throw() {
+                    self.propagate_pending_exception(env);
+                }
                 let block_type = if tail_position {
                     opt_valuetype_to_blocktype(&env.result_type)
                 } else {
@@ -536,8 +568,17 @@ impl<'a> Translate<'a> {
                 let mut env1 = env.clone();
                 env1.labels.push_front(TranslateLabel::Unused);
                 self.translate_rec(&env1, tail_position, conseq);
+                // A branch in tail position returns, unless it is dead code
+                // after a return (e.g., from desugaring `finally`). Either
+                // way, it never falls through, but Wasm needs to know that.
+                if block_type != BlockType::NoResult {
+                    self.out.push(Unreachable);
+                }
                 self.out.push(Else);
                 self.translate_rec(&env1, tail_position, alt);
+                if block_type != BlockType::NoResult {
+                    self.out.push(Unreachable);
+                }
                 self.out.push(End);
             }
             N::Stmt::Loop(body, _) => {
@@ -575,6 +616,32 @@ impl<'a> Translate<'a> {
                 self.translate_atom(atom);
                 self.out.push(Return);
             }
+            N::Stmt::Try(body, x, handler, _) => {
+                // block {
+                //   block { body; br 1 }
+                //   x = exn_catch();
+                //   handler
+                // }
+                self.out.push(Block(BlockType::NoResult));
+                let mut env1 = env.clone();
+                env1.labels.push_front(TranslateLabel::Unused);
+                self.out.push(Block(BlockType::NoResult));
+                let mut env2 = env1.clone();
+                env2.labels.push_front(TranslateLabel::Catch);
+                self.translate_rec(&env2, false, body);
+                self.out.push(Br(1));
+                self.out.push(End);
+                self.rt_call("exn_catch");
+                let index = self.bind_local(x, &N::Type::Any);
+                self.set_local(index, &N::Type::Any);
+                self.translate_rec(&env1, false, handler);
+                self.out.push(End);
+            }
+            N::Stmt::Throw(atom, _) => {
+                self.translate_atom(atom);
+                self.rt_call("exn_throw");
+                self.unwind(env);
+            }
             N::Stmt::Trap => {
                 self.out.push(Unreachable);
             }
@@ -586,6 +653,70 @@ impl<'a> Translate<'a> {
         }
     }
 
+    /// Allocates a local for a new variable and binds it in the environment.
+    fn bind_local(&mut self, id: &N::Id, ty: &N::Type) -> u32 {
+        let index = self.next_id;
+        self.next_id += 1;
+        self.locals.push(ty.as_wasm());
+        self.id_env
+            .insert(id.clone(), IdIndex::Local(index, ty.clone()));
+        index
+    }
+
+    /// Pops a value into a local, and into its shadow stack slot if it is a
+    /// GC root.
+    fn set_local(&mut self, index: u32, ty: &N::Type) {
+        if self.opts.disable_gc || !ty.is_gc_root() {
+            self.out.push(SetLocal(index));
+        } else {
+            self.out.push(TeeLocal(index));
+            self.out.push(I32Const(index.try_into().unwrap()));
+            self.set_in_current_shadow_frame_slot(ty);
+        }
+    }
+
+    /// Continues throwing if `expr`, which was just translated, threw an
+    /// exception. Its result is left on the stack when it did not.
+    fn propagate_exception(&mut self, env: &Env, expr: &N::Expr) {
+        if expr.may_throw() {
+            self.propagate_pending_exception(env);
+        }
+    }
+
+    /// Continues throwing if there is a pending exception.
+    fn propagate_pending_exception(&mut self, env: &Env) {
+        self.rt_call("exn_pending");
+        match env.labels.index_of(&TranslateLabel::Catch) {
+            Some(i) => self.out.push(BrIf(i as u32)),
+            None => {
+                self.out.push(If(BlockType::NoResult));
+                self.return_early(env);
+                self.out.push(End);
+            }
+        }
+    }
+
+    /// Goes to the innermost handler with the pending exception.
+    fn unwind(&mut self, env: &Env) {
+        match env.labels.index_of(&TranslateLabel::Catch) {
+            Some(i) => self.out.push(Br(i as u32)),
+            None => self.return_early(env),
+        }
+    }
+
+    /// Returns from a function that has no handler for the pending exception.
+    /// The result is garbage, since the caller continues unwinding without
+    /// looking at it.
+    fn return_early(&mut self, env: &Env) {
+        if !self.opts.disable_gc {
+            self.rt_call("gc_exit_fn");
+        }
+        if let Some(ty) = env.result_type {
+            self.out.push(zero(ty));
+        }
+        self.out.push(Return);
+    }
+
     fn translate_binop(&mut self, op: &N::BinaryOp) {
         use N::BinaryOp as NO;
         match op {
@@ -785,6 +916,12 @@ impl<'a> Translate<'a> {
                 self.get_id(id);
                 self.rt_call("closure_new");
             }
+            N::Expr::Proxy(id, clos, ty, _) => {
+                self.translate_atom(clos);
+                self.to_any(ty);
+                self.get_id(id);
+                self.rt_call("closure_proxy");
+            }
         }
     }
 
@@ -888,17 +1025,19 @@ impl<'a> Translate<'a> {
                 // functions in the runtime since we know the type already
                 if let N::Type::Closure(_) = ty {
                     // the closure is the only "special" type in an any: it is
-                    // stored is the most significant 48 bits of the AnyValue,
-                    // however, the closure is supposed to be stored in the
-                    // *least* significant 48 bits. one might load with an offset
-                    // of 2 and only load 48 bits, but it's not supported by
-                    // wasm. or, you could load the full 64 bits and let
-                    // the garbage be the padding. except, that is memory
-                    // unsafe. it could be tried as an optimization, but this will
-                    // work for now
+                    // stored in the 48 bits after the 8-bit discriminant of the
+                    // AnyValue, followed by its 8-bit arity. however, the
+                    // closure is supposed to be stored in the *least*
+                    // significant 48 bits. one might load with an offset of 1
+                    // and only load 48 bits, but it's not supported by wasm.
+                    // or, you could load the full 64 bits and let the garbage
+                    // be the padding. except, that is memory unsafe. it could
+                    // be tried as an optimization, but this will work for now
                     self.out.push(I64Load(2, offset));
-                    self.out.push(I64Const(16));
+                    self.out.push(I64Const(8));
                     self.out.push(I64ShrU);
+                    self.out.push(I64Const(0xffff_ffff_ffff));
+                    self.out.push(I64And);
                 } else {
                     if ty.as_wasm() == ValueType::I64 {
                         self.out.push(I64Load(2, offset));
@@ -1054,7 +1193,9 @@ impl<'a> Translate<'a> {
         // String = 1
         // HT(still not used by JankyScript!) = 2
         // Object = 3
+        // RegExp = 11
         // We don't need an outer block to break to because we're already in a block!
+        self.out.push(Block(BlockType::NoResult)); // 4
         self.out.push(Block(BlockType::NoResult)); // 3
         self.out.push(Block(BlockType::NoResult)); // 2
         self.out.push(Block(BlockType::NoResult)); // 1
@@ -1072,27 +1213,32 @@ impl<'a> Translate<'a> {
         self.out.push(I32Load8U(0, 1));
         // And break
         self.out.push(BrTable(Box::new(BrTableData {
-            table: Box::new([0, 1, 2, 3]),
+            // the tags in between are never values
+            table: Box::new([0, 1, 2, 3, 0, 0, 0, 0, 0, 0, 0, 4]),
             // Again, default is just UB
             default: 0,
         })));
         self.out.push(End);
         // Array, 0
         typed_call(self, N::Type::Array);
-        self.out.push(Br(4));
+        self.out.push(Br(5));
         self.out.push(End);
         // String, 1
         typed_call(self, N::Type::String);
-        self.out.push(Br(3));
+        self.out.push(Br(4));
         self.out.push(End);
         // HT, 2
         // TODO(luna)
         // blah blah blah HT stuff
-        self.out.push(Br(2));
+        self.out.push(Br(3));
         self.out.push(End);
         // Object, 3
         self.translate_object_method(any, method_lit, args, s, do_call);
         self.out.push(SetLocal(index));
+        self.out.push(Br(2));
+        self.out.push(End);
+        // RegExp, 11. Its methods take the regular expression as an any
+        typed_call(self, N::Type::Any);
         // No need for an outer block because we are already in an outer block
         // We break 1 here which means breaking all the way out to GetLocal
         self.out.push(Br(1));
@@ -1116,12 +1262,12 @@ impl<'a> Translate<'a> {
                 .collect(),
             result: Some(Box::new(N::Type::Any)),
         });
+        // Now we need to make a ClosureCall, but ClosureCall requires an
+        // id. It isn't any so we can't use the match one (we could, but
+        // NotWasm would whine. Wasm would be fine with it. But wasm-opt should
+        // take care of this for us)
+        let cl_call_idx = self.next_id;
         if do_call {
-            // Now we need to make a ClosureCall, but ClosureCall requires an
-            // id. It isn't any so we can't use the match one (we could, but
-            // NotWasm would whine. Wasm would be fine with it. But wasm-opt should
-            // take care of this for us)
-            let cl_call_idx = self.next_id;
             self.next_id += 1;
             self.locals.push(ValueType::I64);
             // I don't want to bring in namegen. This id can be
@@ -1142,12 +1288,22 @@ impl<'a> Translate<'a> {
             p(),
         );
         if do_call {
-            let typed_dot = from_any_(dot_atom, closure_type.clone(), p());
-            let mut dot_stmt = N::Stmt::Assign("%mfn".into(), atom_(typed_dot, p()), p());
-            // We know this is just a straight-line stmt so no need for env
-            self.translate_rec(&Env::default(), false, &mut dot_stmt);
+            // Reading the method may apply a getter that throws. We have no
+            // env to propagate the exception with here, so we skip the call
+            // and produce garbage, which the caller ignores when it
+            // propagates the exception
+            self.translate_atom(&mut dot_atom);
+            self.out.push(SetLocal(cl_call_idx));
+            self.rt_call("exn_pending");
+            self.out.push(If(BlockType::Value(ValueType::I64)));
+            self.out.push(I64Const(0));
+            self.out.push(Else);
+            self.out.push(GetLocal(cl_call_idx));
+            self.from_any(&closure_type);
+            self.set_local(cl_call_idx, &closure_type);
             let mut call_expr = N::Expr::ClosureCall("%mfn".into(), args.clone(), s.clone());
             self.translate_expr(&mut call_expr);
+            self.out.push(End);
         } else {
             self.translate_atom(&mut dot_atom);
         }
@@ -1203,8 +1359,14 @@ impl<'a> Translate<'a> {
             N::Type::I32 => self.rt_call("any_from_i32"),
             N::Type::Bool => self.rt_call("any_from_bool"),
             N::Type::F64 => self.rt_call("f64_to_any"),
-            N::Type::Fn(..) => self.rt_call("any_from_fn"),
-            N::Type::Closure(..) => self.rt_call("any_from_closure"),
+            N::Type::Fn(fn_ty) => {
+                self.out.push(I32Const(arity(fn_ty) as i32));
+                self.rt_call("any_from_fn");
+            }
+            N::Type::Closure(fn_ty) => {
+                self.out.push(I32Const(arity(fn_ty) as i32));
+                self.rt_call("any_from_closure");
+            }
             N::Type::Any => (),
             _ => self.rt_call("any_from_ptr"),
         }
@@ -1215,8 +1377,24 @@ impl<'a> Translate<'a> {
             N::Type::Bool => self.rt_call("any_to_bool"),
             N::Type::F64 => self.rt_call("any_to_f64"),
             N::Type::Fn(..) => panic!("cannot attain function from any"),
-            N::Type::Closure(..) => self.rt_call("any_to_closure"),
+            N::Type::Closure(fn_ty) => {
+                // the closure may take a different number of arguments, so
+                // the runtime needs an adapter that fits the arguments to it
+                let arity = arity(fn_ty);
+                let all_any = fn_ty.args.iter().skip(1).all(|t| t == &N::Type::Any)
+                    && fn_ty.result.as_deref() == Some(&N::Type::Any);
+                let adapter = if all_any {
+                    self.get_notwasm_rt_fn(&format!("jnks_fit_{}", arity))
+                } else {
+                    None
+                };
+                self.out.push(I32Const(arity as i32));
+                self.out.push(I32Const(adapter.map_or(-1, |i| i as i32)));
+                self.rt_call("any_to_closure");
+            }
             N::Type::Any => (),
+            // an array has an object for its other fields
+            N::Type::DynObject => self.rt_call("any_to_object"),
             _ => self.rt_call("any_to_ptr"),
         }
     }
@@ -1265,6 +1443,51 @@ impl<'a> Translate<'a> {
     }
 }
 
+/// `jnks_run_timers(now)`, which the host applies after main, and then
+/// whenever the next timer is due. It reports an exception that a timer threw
+/// like main does
+/// The index of a function that stdlib.notwasm defines, which comes after
+/// the runtime functions
+fn stdlib_function_index(global_env: &IdEnv, rt_indexes: &HashMap<String, u32>, name: &str) -> u32 {
+    match global_env.get(&N::Id::Named(name.to_string())) {
+        Some(IdIndex::Fun(func)) => *func + rt_indexes.len() as u32,
+        _ => panic!("cannot find notwasm runtime function {}", name),
+    }
+}
+
+fn insert_generated_run_timers(
+    global_env: &IdEnv,
+    rt_indexes: &HashMap<String, u32>,
+    module: &mut ModuleBuilder,
+) {
+    let mut insts = vec![GetLocal(0)];
+    insts.push(Call(stdlib_function_index(
+        global_env,
+        rt_indexes,
+        "jnks_run_timers",
+    )));
+    insts.push(Call(
+        *rt_indexes.get("exn_pending").expect("no exn_pending"),
+    ));
+    insts.push(If(BlockType::NoResult));
+    insts.push(Call(
+        *rt_indexes.get("exn_uncaught").expect("no exn_uncaught"),
+    ));
+    insts.push(End);
+    insts.push(End);
+    module.push_function(
+        function()
+            .signature()
+            .with_params(vec![ValueType::F64])
+            .with_results(vec![ValueType::F64])
+            .build()
+            .body()
+            .with_instructions(Instructions::new(insts))
+            .build()
+            .build(),
+    );
+}
+
 fn insert_generated_main(
     opts: &Opts,
     globals: &HashMap<N::Id, N::Global>,
@@ -1314,10 +1537,32 @@ fn insert_generated_main(
     } else {
         panic!("cannot find notwasm main");
     }
-    if opts.disable_gc == false {
-        insts.push(Call(*rt_indexes.get("gc_exit_fn").expect("no gc_exit_fn")));
+    // the microtasks that main queued run after it, unless it threw
+    insts.push(Call(
+        *rt_indexes.get("exn_pending").expect("no exn_pending"),
+    ));
+    insts.push(I32Eqz);
+    insts.push(If(BlockType::NoResult));
+    if let Some(IdIndex::Fun(func)) =
+        global_env.get(&N::Id::Named("jnks_drain_microtasks".to_string()))
+    {
+        insts.push(Call(*func + rt_indexes.len() as u32));
+        insts.push(Drop);
+    } else {
+        panic!("cannot find notwasm runtime function jnks_drain_microtasks");
     }
     insts.push(End);
+    insts.push(Call(
+        *rt_indexes.get("exn_pending").expect("no exn_pending"),
+    ));
+    insts.push(If(BlockType::NoResult));
+    insts.push(Call(
+        *rt_indexes.get("exn_uncaught").expect("no exn_uncaught"),
+    ));
+    insts.push(End);
+    // the shadow frame of the globals is not popped, since they must remain
+    // roots while the host runs timers with jnks_run_timers
+    insts.push(End);
     // this is just the worst hack due to lack of void type. i still
     // don't want to add it because it doesn't exist in from-jankyscript
     // notwasm, but it makes all my tests that return have an extra thing
@@ -1355,6 +1600,7 @@ impl N::Type {
             HT => ValueType::I32,
             Array => ValueType::I32,
             DynObject => ValueType::I32,
+            BigInt => ValueType::I32,
             Fn(..) => ValueType::I32,
             Closure(..) => ValueType::I64,
             Ref(..) => ValueType::I32,
@@ -1364,6 +1610,52 @@ impl N::Type {
     }
 }
 
+impl N::Expr {
+    /// Whether evaluating the expression may run code that throws an
+    /// exception. Most calls into the Rust runtime are safe, except when they
+    /// apply a getter or a setter, or see `RTSFunction::may_throw`.
+    fn may_throw(&self) -> bool {
+        match self {
+            N::Expr::Call(..)
+            | N::Expr::ClosureCall(..)
+            | N::Expr::AnyMethodCall(..)
+            | N::Expr::ObjectSet(..) => true,
+            N::Expr::PrimCall(rts_func, _, _) => rts_func.may_throw(),
+            N::Expr::ArraySet(a, b, c, _) => a.may_throw() || b.may_throw() || c.may_throw(),
+            N::Expr::NewRef(a, _, _) | N::Expr::Atom(a, _) => a.may_throw(),
+            N::Expr::Closure(_, atoms, _) => atoms.iter().any(|(a, _)| a.may_throw()),
+            N::Expr::Proxy(_, a, _, _) => a.may_throw(),
+            N::Expr::ObjectEmpty => false,
+        }
+    }
+}
+
+impl N::Atom {
+    /// Whether reading the atom may apply a getter, which may throw an
+    /// exception.
+    fn may_throw(&self) -> bool {
+        match self {
+            N::Atom::ObjectGet(..) => true,
+            N::Atom::PrimApp(id, args, _) => {
+                matches!(id, N::Id::Named(f) if f == "any_index" || f == "any_set_index")
+                    || args.iter().any(|a| a.may_throw())
+            }
+            N::Atom::ToAny(to_any, _) => to_any.atom.may_throw(),
+            N::Atom::FromAny(a, _, _)
+            | N::Atom::FloatToInt(a, _)
+            | N::Atom::IntToFloat(a, _)
+            | N::Atom::Unary(_, a, _)
+            | N::Atom::Deref(a, _, _) => a.may_throw(),
+            N::Atom::Binary(_, a, b, _) => a.may_throw() || b.may_throw(),
+            N::Atom::Lit(..)
+            | N::Atom::AnyLength(..)
+            | N::Atom::Id(..)
+            | N::Atom::GetPrimFunc(..)
+            | N::Atom::EnvGet(..) => false,
+        }
+    }
+}
+
 /// Like Translate::set_in_current_shadow_frame_slot, but give the name instead
 /// of adding the call to the instructions
 fn shadow_frame_fn(ty: &N::Type) -> &'static str {
@@ -1389,3 +1681,28 @@ fn get_set_in_globals_frame(rt_indexes: &HashMap<String, u32>, ty: &N::Type) ->
         panic!("cannot find rt {}", func);
     }
 }
+
+/// The arity of a function in an any, which does not count the environment
+pub(super) fn arity(fn_ty: &N::FnType) -> usize {
+    fn_ty.args.iter().filter(|t| t != &&N::Type::Env).count()
+}
+
+/// The types of the closures that are bound to variables, which are the
+/// only closures that can be called
+#[derive(Default)]
+struct ClosureTypes(Vec<N::FnType>);
+
+impl Visitor for ClosureTypes {
+    fn enter_stmt(&mut self, stmt: &mut N::Stmt) {
+        if let N::Stmt::Var(
+            N::VarStmt {
+                ty: Some(N::Type::Closure(fn_ty)),
+                ..
+            },
+            _,
+        ) = stmt
+        {
+            self.0.push(fn_ty.clone());
+        }
+    }
+}
@@ -174,9 +174,9 @@ fn type_check_function(mut env: Env, id: &Id, f: &mut Function) -> TypeCheckingR
     if f.fn_type.args.len() != f.params.len() {
         return Err(TypeCheckingError::ArityMismatch(
             id.clone(),
-            f.params.len(),
             f.fn_type.args.len(),
-            Default::default(), // TODO(arjun): Fix
+            f.params.len(),
+            f.span.clone(),
         ));
     }
 
@@ -286,6 +286,16 @@ fn type_check_stmt(env: Env, s: &mut Stmt, ret_ty: &Option<Type>) -> TypeCheckin
 
             Ok(env)
         }
+        Stmt::Try(s_body, x, s_handler, _) => {
+            type_check_stmt(env.clone(), s_body, ret_ty)?;
+            type_check_stmt(env.update(x.clone(), Type::Any), s_handler, ret_ty)?;
+            Ok(env)
+        }
+        Stmt::Throw(a, s) => {
+            let got = type_check_atom(&env, a)?;
+            ensure("throw", Type::Any, got, s)?;
+            Ok(env)
+        }
         Stmt::Trap => Ok(env),
         Stmt::Goto(_lbl, _) => unimplemented!(),
     }
@@ -429,6 +439,17 @@ fn type_check_expr(env: &Env, e: &mut Expr) -> TypeCheckingResult<Type> {
             )),
             Err(e) => Err(e),
         },
+        Expr::Proxy(id, clos, ty, s) => {
+            ensure("proxy", ty.clone(), type_check_atom(env, clos)?, s)?;
+            match lookup(env, id, s)? {
+                Type::Fn(fn_ty) => Ok(Type::Closure(fn_ty)),
+                got => Err(TypeCheckingError::ExpectedFunction(
+                    id.clone(),
+                    got,
+                    s.clone(),
+                )),
+            }
+        }
     }
 }
 
@@ -449,8 +470,8 @@ fn type_check_call(
     if actuals_len != expected_len {
         return Err(TypeCheckingError::ArityMismatch(
             id_f.clone(),
+            expected_len,
             actuals_len,
-            fn_ty.args.len(),
             s.clone(),
         ));
     }
@@ -511,6 +532,7 @@ fn assert_variant_of_any(ty: &Type, s: &Pos) -> TypeCheckingResult<()> {
         Type::HT => Ok(()),
         Type::Array => Ok(()),
         Type::DynObject => Ok(()),
+        Type::BigInt => Ok(()),
         Type::Ref(..) => invalid_in_context("ref should not be stored in Any", &ty, s),
         Type::Env => invalid_in_context("environments are not values", &ty, s),
         Type::Ptr => Ok(()),
@@ -122,7 +122,12 @@ where
             // 1xExpr
             Expression(a, _) | Assign(.., a, _) | Store(.., a, _) => self.walk_expr(a, loc),
             // 1xAtom
-            Return(a, _) => self.walk_atom(a, loc),
+            Return(a, _) | Throw(a, _) => self.walk_atom(a, loc),
+            // 2xStmt
+            Try(a, _, b, _) => {
+                self.walk_stmt(a, loc);
+                self.walk_stmt(b, loc);
+            }
             // 1xExpr, 2xStmt
             If(e, sa, sb, _) => {
                 self.walk_atom(e, loc);
@@ -144,7 +149,7 @@ where
                 self.walk_atom(eb, loc);
                 self.walk_atom(ec, loc);
             }
-            NewRef(a, ..) | Atom(a, ..) => self.walk_atom(a, loc),
+            NewRef(a, ..) | Atom(a, ..) | Proxy(_, a, ..) => self.walk_atom(a, loc),
             Closure(_, has_atoms, _) => {
                 for (a, _) in has_atoms {
                     self.walk_atom(a, loc);
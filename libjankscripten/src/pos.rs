@@ -26,8 +26,8 @@ enum P {
 impl PartialEq for P {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
-            // Ignores filenames, which should be fine since jankscripten only works with a single
-            // JavaScript input file at a time.
+            // Ignores filenames. The modules of a program are parsed with the same source map,
+            // which gives each file its own range of spans, so spans in different files differ.
             (P::SWC(_, span1), P::SWC(_, span2)) => span1 == span2,
             (P::Grmtools(_, span1), P::Grmtools(_, span2)) => span1 == span2,
             (P::Unknown, P::Unknown) => true,
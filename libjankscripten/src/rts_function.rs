@@ -34,6 +34,24 @@ pub enum RTSFunction {
     InstanceOf,
     In,
     BitwiseNot,
+    /// A regular expression literal, which is created from its source and
+    /// flags
+    NewRegExp,
+    /// A BigInt literal, which is created from its decimal digits
+    NewBigInt,
+    // BigInt-typed operators, which are selected when type inference
+    // shows that both operands are BigInts
+    BigIntPlus,
+    BigIntMinus,
+    BigIntTimes,
+    BigIntOver,
+    BigIntMod,
+    BigIntNeg,
+    BigIntStrictEqual,
+    BigIntLessThan,
+    BigIntLessThanEqual,
+    BigIntGreaterThan,
+    BigIntGreaterThanEqual,
     Import(std::string::String),
 }
 
@@ -73,10 +91,40 @@ impl RTSFunction {
             InstanceOf => Rust("instance_of".into()),
             In => Rust("janky_in".into()),
             BitwiseNot => Rust("janky_not".into()),
+            NewRegExp => Rust("regexp_new".into()),
+            NewBigInt => Rust("bigint_from_str".into()),
+            BigIntPlus => Rust("bigint_plus".into()),
+            BigIntMinus => Rust("bigint_minus".into()),
+            BigIntTimes => Rust("bigint_times".into()),
+            BigIntOver => Rust("bigint_over".into()),
+            BigIntMod => Rust("bigint_mod".into()),
+            BigIntNeg => Rust("bigint_neg".into()),
+            BigIntStrictEqual => Rust("bigint_strict_equal".into()),
+            BigIntLessThan => Rust("bigint_less_than".into()),
+            BigIntLessThanEqual => Rust("bigint_less_than_equal".into()),
+            BigIntGreaterThan => Rust("bigint_greater_than".into()),
+            BigIntGreaterThanEqual => Rust("bigint_greater_than_equal".into()),
             Import(name) => Rust(name.clone()),
         }
     }
 
+    /// Whether calling the function may throw an exception. Functions that
+    /// are implemented in NotWasm may call closures. Of the functions that
+    /// are implemented in Rust, arithmetic throws a `TypeError` when it
+    /// mixes BigInts and numbers, and a `RangeError` when it divides a
+    /// BigInt by zero. `in` throws a `TypeError` when its right-hand side is
+    /// not an object.
+    pub fn may_throw(&self) -> bool {
+        use RTSFunction::*;
+        match self {
+            Minus | Times | Over | Mod | Neg | BigIntOver | BigIntMod | In => true,
+            _ => match self.name() {
+                RTSFunctionImpl::Rust(_) => false,
+                RTSFunctionImpl::NotWasm(_) => true,
+            },
+        }
+    }
+
     /// The type of the function, expressed as a JankyScript type. The compiler translates these
     /// types to NotWasm types.
     ///
@@ -110,13 +158,23 @@ impl RTSFunction {
             Delete | InstanceOf => Function(vec![Any, Any], Box::new(Bool)),
             Void => Function(vec![Any], Box::new(Any)),
             Neg => Function(vec![Any], Box::new(Any)),
-            Plus | Minus | Times | Mod => Function(vec![Any, Any], Box::new(Any)),
-            Over => Function(vec![Any, Any], Box::new(Float)),
+            Plus | Minus | Times | Over | Mod => Function(vec![Any, Any], Box::new(Any)),
             ModF64 => Function(vec![Float, Float], Box::new(Float)),
             StrictEqual | Equal | StrictNotEqual | NotEqual | In => {
                 Function(vec![Any, Any], Box::new(Bool))
             }
             BitwiseNot => Function(vec![Int], Box::new(Int)),
+            NewRegExp => Function(vec![String, String], Box::new(Any)),
+            NewBigInt => Function(vec![String], Box::new(BigInt)),
+            BigIntPlus | BigIntMinus | BigIntTimes | BigIntOver | BigIntMod => {
+                Function(vec![BigInt, BigInt], Box::new(BigInt))
+            }
+            BigIntNeg => Function(vec![BigInt], Box::new(BigInt)),
+            BigIntStrictEqual
+            | BigIntLessThan
+            | BigIntLessThanEqual
+            | BigIntGreaterThan
+            | BigIntGreaterThanEqual => Function(vec![BigInt, BigInt], Box::new(Bool)),
             Import(..) => panic!("unimplemented function: {}", self),
         }
     }
@@ -185,6 +243,19 @@ impl std::fmt::Display for RTSFunction {
                 InstanceOf => "instanceof",
                 In => "in",
                 BitwiseNot => "~",
+                NewRegExp => "regexp",
+                NewBigInt => "bigint",
+                BigIntPlus => "+",
+                BigIntMinus => "-",
+                BigIntNeg => "-",
+                BigIntTimes => "*",
+                BigIntOver => "/",
+                BigIntMod => "%",
+                BigIntStrictEqual => "===",
+                BigIntLessThan => "<",
+                BigIntLessThanEqual => "<=",
+                BigIntGreaterThan => ">",
+                BigIntGreaterThanEqual => ">=",
                 Import(_s) => "import",
             }
         )
@@ -43,6 +43,13 @@ impl Coercion {
             match (t1, t2) {
                 (Type::Any, t2) if t2.is_ground() => Coercion::Untag(t2),
                 (t1, Type::Any) if t1.is_ground() => Coercion::Tag(t1),
+                (Type::Function(args, ret), Type::Any) => {
+                    let gf = Type::ground_function(args.len());
+                    Coercion::seq(
+                        Coercion::new(Type::Function(args, ret), gf.clone(), s.clone()),
+                        Coercion::new(gf, Type::Any, s),
+                    )
+                }
                 (t1, Type::Any) => panic!("non-ground {:?} to any {:?}", t1, s),
                 (Type::Any, Type::Function(args, ret)) => {
                     let gf = Type::ground_function(args.len());
@@ -83,6 +90,29 @@ impl Coercion {
         }
     }
 
+    /// The types `S` and `T` of a coercion `S -> T`.
+    pub fn types(&self) -> (Type, Type) {
+        match self {
+            Coercion::Tag(t) => (t.clone(), Type::Any),
+            Coercion::Untag(t) => (Type::Any, t.clone()),
+            Coercion::IntToFloat => (Type::Int, Type::Float),
+            Coercion::FloatToInt => (Type::Float, Type::Int),
+            Coercion::Fun(args, ret) => {
+                // the arguments are coerced from the arguments of the new
+                // function to the arguments of the original
+                let (new_args, original_args) = args.iter().map(|c| c.types()).unzip();
+                let (original_ret, new_ret) = ret.types();
+                (
+                    Type::Function(original_args, Box::new(original_ret)),
+                    Type::Function(new_args, Box::new(new_ret)),
+                )
+            }
+            Coercion::Id(t) => (t.clone(), t.clone()),
+            Coercion::Seq(c1, c2) => (c1.types().0, c2.types().1),
+            Coercion::Meta(t1, t2) => (t1.clone(), t2.clone()),
+        }
+    }
+
     pub fn meta(t1: Type, t2: Type) -> Coercion {
         if t1 == t2 {
             Coercion::Id(t1)
@@ -164,4 +194,21 @@ mod test {
             Coercion::Fun(args, Box::new(Coercion::FloatToInt))
         );
     }
+
+    #[test]
+    fn function_to_any_is_proxied() {
+        let f = Type::Function(vec![Type::Any], Box::new(Type::Int));
+        let c = Coercion::new(f.clone(), Type::Any, Pos::UNKNOWN);
+        assert_eq!(
+            c,
+            cseq_(
+                Coercion::Fun(
+                    vec![Coercion::Id(Type::Any)],
+                    Box::new(Coercion::Tag(Type::Int))
+                ),
+                Coercion::Tag(Type::ground_function(1))
+            )
+        );
+        assert_eq!(c.types(), (f, Type::Any));
+    }
 }
@@ -58,6 +58,10 @@ impl Id {
             _ => panic!("name on Id::Generated"),
         }
     }
+    /// Whether this is a fresh name that was generated for the role `name`
+    pub fn is_generated(&self, name: &str) -> bool {
+        matches!(self, Id::Generated(Generated { base_name, .. }) if *base_name == name)
+    }
 }
 
 impl<T: Into<String>> From<T> for Id {
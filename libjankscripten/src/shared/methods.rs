@@ -21,6 +21,13 @@ fn methods_table() -> HashMap<(&'static str, usize), Vec<Type>> {
         entry!(concat, (array, array) -> array, (string, string) -> string),
         entry!(push, (array, any) -> int),
         entry!(charAt, (string, int) -> string),
+        entry!(split, (string, any) -> array),
+        entry!(replace, (string, any, any) -> string),
+        entry!(match, (string, any) -> any),
+        // Regular expressions are never typed, so their methods are
+        // dispatched on the runtime tag of an any
+        entry!(test, (any, any) -> any),
+        entry!(exec, (any, any) -> any),
         // Source: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Array
         // Array.prototype[@@unscopables] // ??
         // Array.prototype[@@iterator]() // ??
@@ -21,8 +21,6 @@ pub fn get_global_object() -> BindMap {
     // console.log
     insert(m, "log_any", typ!(fun (any, any) -> any));
 
-    // i don't know where this would be documented but i know we need it
-    insert(m, "arguments", Array);
     // TODO(luna): this is even more stopgap: this is dead code that refers
     // to a nonexistant function in the runtime of every dart program. see this
     // issue https://github.com/dart-lang/sdk/issues/33081. we should
@@ -54,11 +52,24 @@ pub fn get_global_object() -> BindMap {
     insert(m, "navigator", Any);
     insert(m, "performance", Any);
     // Methods
-    insert(m, "clearTimeout", Any);
+    // these four are provided by the runtime. they are given precise types
+    // like Error, so javascript::normalize_std_lib_calls gives them the
+    // number of arguments that these types expect
+    insert(m, "clearInterval", Function(vec![Any, Any], Box::new(Any)));
+    insert(m, "clearTimeout", Function(vec![Any, Any], Box::new(Any)));
     insert(m, "close", Any);
     // pyjs
     insert(m, "confirm", Any);
-    insert(m, "setTimeout", Any);
+    insert(
+        m,
+        "setInterval",
+        Function(vec![Any, Any, Any, Any], Box::new(Any)),
+    );
+    insert(
+        m,
+        "setTimeout",
+        Function(vec![Any, Any, Any, Any], Box::new(Any)),
+    );
     insert(m, "stop", Any);
     // pyjs
     insert(m, "alert", Any);
@@ -72,7 +83,7 @@ pub fn get_global_object() -> BindMap {
     // scala
     insert(m, "ArrayBuffer", Any);
     // scala
-    insert(m, "BigInt", Any);
+    insert(m, "BigInt", Function(vec![Any, Any], Box::new(Any)));
     insert(m, "Boolean", Any);
     // maybe elm?
     insert(m, "DataView", Any);
@@ -99,9 +110,11 @@ pub fn get_global_object() -> BindMap {
     insert(m, "RangeError", Any);
     insert(m, "ReferenceError", Any);
     insert(m, "RegExp", Any);
-    insert(m, "String", Any);
+    // String(x) is ToString. template literals are desugared to calls to
+    // String, so it's important that it returns a string
+    insert(m, "String", Function(vec![Any, Any], Box::new(String)));
     // clojurescript
-    insert(m, "Symbol", Any);
+    insert(m, "Symbol", DynObject);
     // ocaml
     insert(m, "SyntaxError", Any);
     insert(m, "TypeError", Any);
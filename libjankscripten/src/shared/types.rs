@@ -14,6 +14,8 @@ pub enum Type {
     String,
     Array,
     DynObject,
+    /// An arbitrary-precision integer
+    BigInt,
     /// Ref(T) is the type of heap-allocated boxes that contain values of type
     /// T.
     Ref(Box<Type>),
@@ -57,6 +59,7 @@ impl Type {
             Type::String => NotWasmType::String,
             Type::Array => NotWasmType::Array,
             Type::DynObject => NotWasmType::DynObject,
+            Type::BigInt => NotWasmType::BigInt,
             Type::Ref(of) => NotWasmType::Ref(Box::new(of.notwasm_typ(allow_closure))),
             Type::Metavar(_) => panic!("Metavar received"),
         }
@@ -109,6 +112,7 @@ impl std::fmt::Display for Type {
                 Type::Array => "array",
                 Type::Bool => "bool",
                 Type::DynObject => "DynObject",
+                Type::BigInt => "bigint",
                 Type::Function(..) => "fn",
                 Type::Any => "any",
                 Type::Ref(..) => "ref",
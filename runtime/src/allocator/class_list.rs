@@ -1,7 +1,7 @@
 //! The global classes with their offsets, transitions, and sizes
 //!
 //! Some terminology:
-//! - Class => A set of offsets and transitions
+//! - Class => A set of offsets, their attributes, and transitions
 //! - ClassList => The singe global list of classes, associated with a heap
 //! - Object => An instance of a class, allocated on the heap with space
 //!   for every field but they may not be occupied
@@ -46,6 +46,79 @@ impl ClassList {
             }
         }
     }
+    /// like `transition`, but for changing the attributes of the field
+    /// `name`, which must be in the class. the field keeps its offset
+    pub fn reconfigure(&mut self, class_tag: u16, name: StringPtr, attributes: Attributes) -> u16 {
+        let new_tag = self.classes.len() as u16;
+        let class = &mut self.classes[class_tag as usize];
+        match class.lookup_reconfiguration(name, attributes) {
+            Some(tag) => tag,
+            None => {
+                let new_class = class.branch_reconfigured(name, attributes, new_tag);
+                self.new_class_type(new_class)
+            }
+        }
+    }
+    /// like `transition`, but for `Object.freeze`: no field can be
+    /// reconfigured, no data field can be written, and no field can be added
+    pub fn freeze(&mut self, class_tag: u16) -> u16 {
+        let new_tag = self.classes.len() as u16;
+        let class = &mut self.classes[class_tag as usize];
+        match class.frozen {
+            Some(tag) => tag,
+            None => {
+                class.frozen = Some(new_tag);
+                let mut new_class = class.clone();
+                for attributes in new_class.attributes.iter_mut() {
+                    attributes.configurable = false;
+                    attributes.writable = false;
+                }
+                new_class.extensible = false;
+                new_class.tag = new_tag;
+                new_class.transitions = Vec::new();
+                new_class.deletions = Vec::new();
+                new_class.reconfigurations = Vec::new();
+                new_class.frozen = Some(new_tag);
+                self.new_class_type(new_class)
+            }
+        }
+    }
+    /// like `transition`, but for deleting the field `name`, which must be
+    /// in the class. the remaining fields keep their order, so their offsets
+    /// may shift. the new class has a new tag, so inline caches that were
+    /// filled in with the old class never match it
+    pub fn delete_transition(&mut self, class_tag: u16, name: StringPtr) -> u16 {
+        let new_tag = self.classes.len() as u16;
+        let class = &mut self.classes[class_tag as usize];
+        match class.lookup_deletion(name) {
+            Some(tag) => tag,
+            None => {
+                let new_class = class.branch_without(name, new_tag);
+                self.new_class_type(new_class)
+            }
+        }
+    }
+}
+
+/// The attributes of a field, which `Object.defineProperty` sets. An accessor
+/// field holds an array of its getter and its setter instead of a value, and
+/// is never writable
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Attributes {
+    pub accessor: bool,
+    pub writable: bool,
+    pub enumerable: bool,
+    pub configurable: bool,
+}
+
+impl Attributes {
+    /// the attributes of a field that is added by assigning to it
+    pub const DATA: Attributes = Attributes {
+        accessor: false,
+        writable: true,
+        enumerable: true,
+        configurable: true,
+    };
 }
 
 #[derive(Clone, Debug)]
@@ -53,7 +126,18 @@ pub struct Class {
     pub size: usize,
     tag: u16,
     offsets: Vec<(StringPtr, usize)>,
+    /// the attributes of the field at each offset
+    attributes: Vec<Attributes>,
+    /// whether fields can be added, which `Object.freeze` prevents
+    pub extensible: bool,
     transitions: Vec<(StringPtr, u16)>,
+    /// transitions taken when a field is deleted, so that objects with the
+    /// same class that delete the same field share a class too
+    deletions: Vec<(StringPtr, u16)>,
+    /// likewise for changing the attributes of a field
+    reconfigurations: Vec<(StringPtr, Attributes, u16)>,
+    /// likewise for `Object.freeze`. a frozen class refers to itself
+    frozen: Option<u16>,
 }
 impl Class {
     /// this is the very base class
@@ -62,7 +146,12 @@ impl Class {
             size: 0,
             tag: 0,
             offsets: Vec::new(),
+            attributes: Vec::new(),
+            extensible: true,
             transitions: Vec::new(),
+            deletions: Vec::new(),
+            reconfigurations: Vec::new(),
+            frozen: None,
         }
     }
     pub fn lookup(&self, name: StringPtr, cache: &mut (u16, u16)) -> Option<usize> {
@@ -85,6 +174,9 @@ impl Class {
     pub fn keys(&self) -> Vec<StringPtr> {
         self.offsets.iter().map(|(s, _)| *s).collect()
     }
+    pub fn attributes(&self, offset: usize) -> Attributes {
+        self.attributes[offset]
+    }
     fn lookup_transition(&self, name: StringPtr) -> Option<u16> {
         self.transitions
             .iter()
@@ -96,11 +188,79 @@ impl Class {
         self.transitions.push((name, new_tag));
         let mut offsets = self.offsets.clone();
         offsets.push((name, self.size));
+        let mut attributes = self.attributes.clone();
+        attributes.push(Attributes::DATA);
         Self {
             size: self.size + 1,
             tag: new_tag,
             offsets,
+            attributes,
+            extensible: self.extensible,
+            transitions: Vec::new(),
+            deletions: Vec::new(),
+            reconfigurations: Vec::new(),
+            frozen: None,
+        }
+    }
+    fn lookup_reconfiguration(&self, name: StringPtr, attributes: Attributes) -> Option<u16> {
+        self.reconfigurations
+            .iter()
+            .find(|(field_name, field_attributes, _)| {
+                field_name == &name && field_attributes == &attributes
+            })
+            .map(|(_, _, index)| *index)
+    }
+    fn branch_reconfigured(
+        &mut self,
+        name: StringPtr,
+        attributes: Attributes,
+        new_tag: u16,
+    ) -> Self {
+        self.reconfigurations.push((name, attributes, new_tag));
+        let mut new_attributes = self.attributes.clone();
+        for (offset_name, offset) in self.offsets.iter() {
+            if offset_name == &name {
+                new_attributes[*offset] = attributes;
+            }
+        }
+        Self {
+            size: self.size,
+            tag: new_tag,
+            offsets: self.offsets.clone(),
+            attributes: new_attributes,
+            extensible: self.extensible,
+            transitions: Vec::new(),
+            deletions: Vec::new(),
+            reconfigurations: Vec::new(),
+            frozen: None,
+        }
+    }
+    fn lookup_deletion(&self, name: StringPtr) -> Option<u16> {
+        self.deletions
+            .iter()
+            .find(|(deleted_name, _)| deleted_name == &name)
+            .map(|(_, index)| *index)
+    }
+    fn branch_without(&mut self, name: StringPtr, new_tag: u16) -> Self {
+        self.deletions.push((name, new_tag));
+        let (offsets, attributes) = self
+            .offsets
+            .iter()
+            .zip(self.attributes.iter())
+            .filter(|((offset_name, _), _)| offset_name != &name)
+            .enumerate()
+            .map(|(index, ((offset_name, _), attributes))| ((*offset_name, index), *attributes))
+            .unzip();
+        Self {
+            size: self.size - 1,
+            tag: new_tag,
+            offsets,
+            attributes,
+            extensible: self.extensible,
             transitions: Vec::new(),
+            deletions: Vec::new(),
+            reconfigurations: Vec::new(),
+            frozen: None,
         }
     }
 }
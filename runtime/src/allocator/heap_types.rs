@@ -2,9 +2,12 @@ pub use super::env::EnvPtr;
 pub use super::object_ptr::{ObjectDataPtr, ObjectPtr};
 pub use super::string::StringPtr;
 use super::{AnyPtr, HeapPtr, Tag, TypePtr, TypeTag};
+use crate::bigint::BigInt;
 use crate::closure::Closure;
+use crate::regexp::RegExp;
 use crate::{AnyEnum, AnyValue, Heap, Key};
 use std::collections::HashMap;
+use std::ops::{Deref, DerefMut};
 
 pub trait HasTag {
     const TYPE_TAG: TypeTag;
@@ -31,14 +34,67 @@ impl HasTag for HashMap<Key, AnyValue> {
     }
 }
 
-pub type ArrayPtr = TypePtr<Vec<AnyValue>>;
-impl HasTag for Vec<AnyValue> {
+/// The elements of an array, which it dereferences to, and the object that
+/// holds its other fields, such as `raw` on the strings of a tagged template.
+/// Most arrays do not have other fields, so they do not have the object.
+#[derive(Debug)]
+pub struct Array {
+    elems: Vec<AnyValue>,
+    pub fields: Option<ObjectPtr>,
+    /// Whether `Object.freeze` has been applied to the array, so that its
+    /// elements cannot be changed
+    pub frozen: bool,
+}
+
+impl From<Vec<AnyValue>> for Array {
+    fn from(elems: Vec<AnyValue>) -> Self {
+        Array {
+            elems,
+            fields: None,
+            frozen: false,
+        }
+    }
+}
+
+impl Deref for Array {
+    type Target = Vec<AnyValue>;
+    fn deref(&self) -> &Vec<AnyValue> {
+        &self.elems
+    }
+}
+
+impl DerefMut for Array {
+    fn deref_mut(&mut self) -> &mut Vec<AnyValue> {
+        &mut self.elems
+    }
+}
+
+pub type ArrayPtr = TypePtr<Array>;
+impl HasTag for Array {
     const TYPE_TAG: TypeTag = TypeTag::Array;
     fn get_data_ptrs(&self, _: &Heap) -> (Vec<*mut Tag>, Vec<*mut *const f64>) {
-        AnyEnum::iter_to_ptrs(self.iter().map(|x| &**x))
+        let (mut tags, f64s) = AnyEnum::iter_to_ptrs(self.iter().map(|x| &**x));
+        tags.extend(self.fields.map(|fields| fields.get_ptr()));
+        (tags, f64s)
     }
 }
 
+pub type RegExpPtr = TypePtr<RegExp>;
+impl HasTag for RegExp {
+    const TYPE_TAG: TypeTag = TypeTag::RegExp;
+    fn get_data_ptrs(&self, _: &Heap) -> (Vec<*mut Tag>, Vec<*mut *const f64>) {
+        (
+            self.fields.iter().map(|fields| fields.get_ptr()).collect(),
+            vec![],
+        )
+    }
+}
+
+pub type BigIntPtr = TypePtr<BigInt>;
+impl HasTag for BigInt {
+    const TYPE_TAG: TypeTag = TypeTag::BigInt;
+}
+
 // REF TYPES
 // =========
 // TODO(luna): Is this necessary? Aren't the immediate values, rather than the
@@ -76,6 +76,11 @@ pub enum TypeTag {
     /// This is a ref of a closure. Closures are usually immediate values (not
     /// their env! which is above), but not when they're in a ref
     Closure,
+    /// A regular expression. We specify a value because the compiler
+    /// dispatches methods on it
+    RegExp = 11,
+    /// An arbitrary-precision integer, whose digits are on the Rust heap
+    BigInt = 12,
 }
 
 /// Every pointer into the heap points to a tag, thus we could build an API
@@ -135,6 +140,8 @@ pub enum HeapRefView {
     MutF64(MutF64Ptr),
     Ptr(PtrPtr),
     Closure(ClosurePtr),
+    RegExp(RegExpPtr),
+    BigInt(BigIntPtr),
 }
 impl HeapRefView {
     /// Return a less specific `HeapPtr` that points to the same heap value,
@@ -152,6 +159,8 @@ impl HeapRefView {
             Self::MutF64(val) => val,
             Self::Ptr(val) => val,
             Self::Closure(val) => val,
+            Self::RegExp(val) => val,
+            Self::BigInt(val) => val,
         }
     }
 }
@@ -202,6 +211,8 @@ impl AnyPtr {
                 TypeTag::MutF64 => HeapRefView::MutF64(MutF64Ptr::new_tag_unchecked(self.ptr)),
                 TypeTag::Ptr => HeapRefView::Ptr(PtrPtr::new_tag_unchecked(self.ptr)),
                 TypeTag::Closure => HeapRefView::Closure(ClosurePtr::new_tag_unchecked(self.ptr)),
+                TypeTag::RegExp => HeapRefView::RegExp(RegExpPtr::new_tag_unchecked(self.ptr)),
+                TypeTag::BigInt => HeapRefView::BigInt(BigIntPtr::new_tag_unchecked(self.ptr)),
             }
         }
     }
@@ -20,6 +20,7 @@ pub use heap_values::HeapPtr;
 pub use heap_values::HeapRefView;
 mod f64_allocator;
 
+pub use class_list::Attributes;
 use class_list::ClassList;
 use constants::*;
 use f64_allocator::F64Allocator;
@@ -27,7 +28,7 @@ use heap_types::*;
 pub use heap_values::Tag;
 pub use heap_values::TypeTag;
 use heap_values::*;
-pub use object_ptr::no_cache;
+pub use object_ptr::{accessor_pair, no_cache};
 
 #[cfg(test)]
 mod tests;
@@ -1,9 +1,10 @@
-use super::class_list::Class;
+use super::class_list::{Attributes, Class};
 use super::constants::DATA_OFFSET;
 use super::heap_values::*;
 use super::{Heap, ALIGNMENT};
 use crate::coercions::match_object;
 use crate::heap_types::StringPtr;
+use crate::object::{call_getter, call_setter};
 use crate::static_strings::static_strings;
 use crate::{AnyEnum, AnyValue};
 use std::ops::{Deref, DerefMut};
@@ -82,41 +83,85 @@ impl ObjectDataPtr {
         }
     }
 
-    /// if name is found, write to it. if not, transition, clone, write, and
-    /// return new pointer. this should be called by ObjectPtr only
+    /// transition, clone, and write the new field `name`, which must not be
+    /// in the class, and return new pointer. this should be called by
+    /// ObjectPtr only
     #[must_use]
-    fn insert(self, heap: &Heap, name: StringPtr, value: AnyValue, cache: &mut (u16, u16)) -> Self {
+    fn add(self, heap: &Heap, name: StringPtr, value: AnyValue) -> Self {
         let class_tag = self.class_tag();
         let mut classes = heap.classes.borrow_mut();
-        let class = classes.get_class(class_tag);
-        match class.lookup(name, cache) {
-            Some(offset) => {
-                drop(class);
-                drop(classes);
-                self.write_at(heap, offset, value);
-                self
+        let size = classes.get_class_size(class_tag);
+        let new_tag = classes.transition(class_tag, name);
+        drop(classes);
+        let new_object = heap.alloc_object_data_or_gc(new_tag);
+        for i in 0..size {
+            if let Some(val) = self.read_at(heap, i) {
+                new_object.write_at(heap, i, val.into());
             }
-            None => {
+        }
+        new_object.write_at(heap, size, value);
+        new_object
+    }
+
+    /// the offset and attributes of the field `name` of this object itself
+    fn lookup_own(
+        &self,
+        heap: &Heap,
+        name: StringPtr,
+        cache: &mut (u16, u16),
+    ) -> Option<(usize, Attributes)> {
+        let class = self.get_class(heap);
+        class
+            .lookup(name, cache)
+            .map(|offset| (offset, class.attributes(offset)))
+    }
+
+    /// moves the object to a class with the same fields, changing the
+    /// attributes of `name`. since the size does not change, the data stays
+    /// where it is
+    fn reconfigure(&self, heap: &Heap, name: StringPtr, attributes: Attributes) {
+        let new_tag = heap
+            .classes
+            .borrow_mut()
+            .reconfigure(self.class_tag(), name, attributes);
+        unsafe { (*self.ptr).class_tag = new_tag };
+    }
+
+    /// if name is found, copy every other field into an object of the class
+    /// without it and return the new pointer. this should be called by
+    /// ObjectPtr only
+    #[must_use]
+    fn delete(self, heap: &Heap, name: StringPtr) -> Self {
+        let class_tag = self.class_tag();
+        let mut classes = heap.classes.borrow_mut();
+        let class = classes.get_class(class_tag);
+        match class.lookup(name, &mut no_cache()) {
+            None => self,
+            Some(deleted) => {
                 let size = class.size;
                 drop(class);
-                let new_tag = classes.transition(class_tag, name);
+                let new_tag = classes.delete_transition(class_tag, name);
                 drop(classes);
                 let new_object = heap.alloc_object_data_or_gc(new_tag);
-                for i in 0..size {
+                for (new_i, i) in (0..size).filter(|i| *i != deleted).enumerate() {
                     if let Some(val) = self.read_at(heap, i) {
-                        new_object.write_at(heap, i, val.into());
+                        new_object.write_at(heap, new_i, val.into());
                     }
                 }
-                new_object.write_at(heap, size, value);
                 new_object
             }
         }
     }
 
-    /// Reads a property from an object, searching up the prototype chain if
-    /// necessary. Returns `undefined` if the property doesn't exist anywhere
-    /// on the prototype chain.
-    pub fn get(&self, heap: &Heap, name: StringPtr, cache: &mut (u16, u16)) -> AnyEnum {
+    /// Finds a property of an object, searching up the prototype chain if
+    /// necessary, and produces what its slot holds along with its attributes.
+    /// Unlike `ObjectPtr::get`, this does not apply getters.
+    pub fn find(
+        &self,
+        heap: &Heap,
+        name: StringPtr,
+        cache: &mut (u16, u16),
+    ) -> Option<(AnyEnum, Attributes)> {
         // Reading a property from an object is a complicated process because
         // of the prototype chain.
         //
@@ -133,39 +178,62 @@ impl ObjectDataPtr {
 
         // Test Case 1: `obj` has a field named `prop`.
         // Check `obj`'s class for `prop`
-        let class = self.get_class(heap);
         // TODO(mark): currently, the field cache is assumed to be valid
         //             without checking the class of the object whose
         //             offsets were cached. we must ensure the class is
         //             identical before using the cache.
-        let maybe_offset = class.lookup(name, cache);
-
-        if let Some(offset) = maybe_offset {
+        if let Some((offset, attributes)) = self.lookup_own(heap, name, cache) {
             // this is Case 1
-            return self
+            let value = self
                 .read_at(heap, offset)
                 .expect("object missing offset spceified by its hidden class");
+            return Some((value, attributes));
         }
 
         // Test Case 2: `obj` has a field named "__proto__".
-        let maybe_proto_offset = class.lookup(static_strings().__proto__, &mut no_cache());
-        if let Some(proto_offset) = maybe_proto_offset {
-            // Get the prototype object
-            let proto_val = self.read_at(heap, proto_offset).unwrap();
-
-            // Is it a real object that we can read from? As opposed to `null`
-            // or any other type of value.
-            if let Some(proto_obj) = match_object(proto_val) {
-                // this is Case 2. Perform the same read on the proto obj.
-
-                return proto_obj.get(heap, name, &mut no_cache());
-            }
+        if let Some(proto_obj) = self.proto(heap) {
+            // this is Case 2. Perform the same read on the proto obj.
+            return proto_obj.find(heap, name, &mut no_cache());
         }
 
         // This is Case 3. `obj` doesn't have `prop`, and `__proto__` is
         // missing or not an object.
+        None
+    }
+
+    /// The prototype of this object, if it is a real object that we can read
+    /// from, as opposed to `null` or any other type of value.
+    fn proto(&self, heap: &Heap) -> Option<ObjectPtr> {
+        let (offset, _) = self.lookup_own(heap, static_strings().__proto__, &mut no_cache())?;
+        match_object(self.read_at(heap, offset)?)
+    }
 
-        return AnyEnum::Undefined;
+    /// Whether the field is set on this object itself, without searching the
+    /// prototype chain
+    pub fn has_own(&self, heap: &Heap, name: StringPtr) -> bool {
+        self.own_property(heap, name).is_some()
+    }
+
+    /// What the field `name` of this object itself holds, and its attributes
+    pub fn own_property(&self, heap: &Heap, name: StringPtr) -> Option<(AnyEnum, Attributes)> {
+        let (offset, attributes) = self.lookup_own(heap, name, &mut no_cache())?;
+        self.read_at(heap, offset).map(|value| (value, attributes))
+    }
+
+    /// The fields that are set on this object itself, in the order that they
+    /// were added. This includes `__proto__`, but not the prototype chain.
+    /// The value of an accessor field is the array of its getter and setter.
+    pub fn own_fields(&self, heap: &Heap) -> Vec<(StringPtr, AnyEnum, Attributes)> {
+        // a class assigns offsets in the same order as its keys
+        let class = self.get_class(heap);
+        let keys = class.keys();
+        keys.into_iter()
+            .enumerate()
+            .filter_map(|(offset, key)| {
+                self.read_at(heap, offset)
+                    .map(|value| (key, value, class.attributes(offset)))
+            })
+            .collect()
     }
 
     fn as_array(&self, heap: &Heap) -> &mut [Option<AnyEnum>] {
@@ -186,8 +254,23 @@ impl ObjectPtr {
     pub const unsafe fn new(ptr: *mut Tag) -> Self {
         Self { ptr }
     }
-    /// if name is found, write to it. if not, transition, clone, write, and
-    /// update pointer
+    /// Reads a property like `ObjectDataPtr::find`, applying the getter of an
+    /// accessor property to this object. Returns `undefined` if the property
+    /// doesn't exist anywhere on the prototype chain.
+    pub fn get(&self, heap: &Heap, name: StringPtr, cache: &mut (u16, u16)) -> AnyEnum {
+        match self.find(heap, name, cache) {
+            Some((accessors, attributes)) if attributes.accessor => {
+                let (getter, _) = accessor_pair(accessors);
+                *call_getter(getter.into(), self.as_any())
+            }
+            Some((value, _)) => value,
+            None => AnyEnum::Undefined,
+        }
+    }
+    /// if name is a field of this object, write to it, or apply its setter.
+    /// if not, apply the setter that it inherits, or transition, clone,
+    /// write, and update pointer. writing to a read-only field, or adding a
+    /// field to a frozen object, does nothing
     /// TODO: updating this pointer in particular isn't enough. i think we
     /// have to have a double-pointer situation
     /// (ObjectPtr -> ArrayPtr -> [u8; n])
@@ -198,10 +281,104 @@ impl ObjectPtr {
         value: AnyValue,
         cache: &mut (u16, u16),
     ) -> AnyValue {
+        let found = match self.lookup_own(heap, name, cache) {
+            Some((offset, attributes)) => {
+                if !attributes.accessor {
+                    if attributes.writable {
+                        self.write_at(heap, offset, value);
+                    }
+                    return value;
+                }
+                Some((self.read_at(heap, offset).unwrap(), attributes))
+            }
+            None => self
+                .proto(heap)
+                .and_then(|proto| proto.find(heap, name, &mut no_cache())),
+        };
+        match found {
+            Some((accessors, attributes)) if attributes.accessor => {
+                let (_, setter) = accessor_pair(accessors);
+                call_setter(setter.into(), self.as_any(), value);
+            }
+            Some((_, attributes)) if !attributes.writable => (),
+            _ => {
+                if self.get_class(heap).extensible {
+                    let data = &mut **self;
+                    let new = data.add(heap, name, value);
+                    unsafe { *(self.ptr.add(DATA_OFFSET) as *mut ObjectDataPtr) = new };
+                }
+            }
+        }
+        value
+    }
+    /// `Object.defineProperty`: sets the own field `name` to `value`, which
+    /// is the array of the getter and the setter of an accessor, and gives it
+    /// `attributes`. Returns false if the field cannot be redefined, because
+    /// it is not configurable or the object is frozen.
+    pub fn define(
+        &mut self,
+        heap: &Heap,
+        name: StringPtr,
+        value: AnyValue,
+        attributes: Attributes,
+    ) -> bool {
+        match self.lookup_own(heap, name, &mut no_cache()) {
+            Some((offset, old)) => {
+                if !old.configurable && (old != attributes || !old.writable) {
+                    return false;
+                }
+                self.write_at(heap, offset, value);
+                if old != attributes {
+                    self.reconfigure(heap, name, attributes);
+                }
+                true
+            }
+            None if !self.get_class(heap).extensible => false,
+            None => {
+                let data = &mut **self;
+                let new = data.add(heap, name, value);
+                unsafe { *(self.ptr.add(DATA_OFFSET) as *mut ObjectDataPtr) = new };
+                if attributes != Attributes::DATA {
+                    self.reconfigure(heap, name, attributes);
+                }
+                true
+            }
+        }
+    }
+    /// remove the own field `name`, if there is one, and update pointer. the
+    /// prototype chain is unaffected. Returns false if the field is not
+    /// configurable
+    pub fn delete(&mut self, heap: &Heap, name: StringPtr) -> bool {
+        if let Some((_, attributes)) = self.lookup_own(heap, name, &mut no_cache()) {
+            if !attributes.configurable {
+                return false;
+            }
+        }
         let data = &mut **self;
-        let new = data.insert(heap, name, value, cache);
+        let new = data.delete(heap, name);
         unsafe { *(self.ptr.add(DATA_OFFSET) as *mut ObjectDataPtr) = new };
-        value
+        true
+    }
+    /// `Object.freeze`. since the size does not change, the data stays where
+    /// it is
+    pub fn freeze(&self, heap: &Heap) {
+        let new_tag = heap.classes.borrow_mut().freeze(self.class_tag());
+        let data: &ObjectDataPtr = &**self;
+        unsafe { (*data.ptr).class_tag = new_tag };
+    }
+    fn as_any(&self) -> AnyValue {
+        AnyEnum::Ptr(self.as_any_ptr()).into()
+    }
+}
+
+/// The getter and the setter that an accessor field holds
+pub fn accessor_pair(accessors: AnyEnum) -> (AnyEnum, AnyEnum) {
+    match accessors {
+        AnyEnum::Ptr(ptr) => match ptr.view() {
+            HeapRefView::Array(arr) => (*arr[0], *arr[1]),
+            _ => panic!("accessor field does not hold an array"),
+        },
+        _ => panic!("accessor field does not hold an array"),
     }
 }
 impl Deref for ObjectPtr {
@@ -244,8 +421,10 @@ impl std::fmt::Debug for ObjectPtr {
     }
 }
 
+/// a cache that never matches a class, like the placeholder in the caches
+/// that the compiler generates. the tag is a u16, so 0xff is a real class
 pub fn no_cache() -> (u16, u16) {
-    (0xff, 0xff)
+    (0xffff, 0xffff)
 }
 
 #[cfg(test)]
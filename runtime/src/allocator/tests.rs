@@ -95,7 +95,7 @@ fn alloc_or_gc_gcs() {
 #[test]
 #[wasm_bindgen_test]
 fn array_members_marked() {
-    let heap = Heap::new((ALIGNMENT * 6) as isize);
+    let heap = Heap::new((ALIGNMENT * 8) as isize);
     // the one root will be the array
     heap.push_shadow_frame(1);
     // i32: ALIGNMENT * 2 (32-bit), OR ALIGNMENT * 1 (64-bit)
@@ -106,8 +106,8 @@ fn array_members_marked() {
         "first value was not written to the heap correctly"
     );
     // x is not a root
-    // Vec: ALIGNMENT * 4 (tag, ptr, len, cap)
-    let mut arr: TypePtr<Vec<AnyValue>> = heap.alloc_or_gc(Vec::new());
+    // Array: ALIGNMENT * 6 (tag, ptr, len, cap, fields, frozen)
+    let mut arr: TypePtr<Array> = heap.alloc_or_gc(Array::from(Vec::new()));
     // arr is
     heap.set_in_current_shadow_frame_slot(0, Some(arr.get_ptr()));
     // but put x into the array
@@ -163,10 +163,10 @@ fn object_members_marked() {
 #[test]
 #[wasm_bindgen_test]
 fn gc_f64s() {
-    let heap = Heap::new((ALIGNMENT * 6) as isize);
+    let heap = Heap::new((ALIGNMENT * 8) as isize);
     heap.push_shadow_frame(1);
     let x = heap.f64_to_any(5.);
-    let mut arr: TypePtr<Vec<AnyValue>> = heap.alloc(Vec::new()).unwrap();
+    let mut arr: TypePtr<Array> = heap.alloc(Array::from(Vec::new())).unwrap();
     heap.set_in_current_shadow_frame_slot(0, Some(arr.get_ptr()));
     arr.push(x);
     let x_copy = arr[0];
@@ -284,3 +284,63 @@ fn string_ptr_drop_read() {
     assert_eq!(&*x, "universe");
     drop(x);
 }
+
+#[test]
+#[wasm_bindgen_test]
+fn delete_object_field() {
+    let heap = Heap::new(256);
+    let mut obj = heap.alloc_object(0).expect("alloc");
+    let x = heap.alloc_str("x").unwrap();
+    let y = heap.alloc_str("y").unwrap();
+    obj.insert(&heap, x, AnyEnum::I32(1).into(), &mut no_cache());
+    obj.insert(&heap, y, AnyEnum::I32(2).into(), &mut no_cache());
+    let mut cache = no_cache();
+    assert!(matches!(obj.get(&heap, y, &mut cache), AnyEnum::I32(2)));
+    assert_eq!(cache.1, 1);
+    obj.delete(&heap, x);
+    // the cache was filled in before y moved
+    assert!(matches!(obj.get(&heap, y, &mut cache), AnyEnum::I32(2)));
+    assert_eq!(cache.1, 0);
+    assert!(matches!(
+        obj.get(&heap, x, &mut no_cache()),
+        AnyEnum::Undefined
+    ));
+    let keys = obj
+        .own_fields(&heap)
+        .into_iter()
+        .map(|(k, _, _)| k)
+        .collect::<Vec<_>>();
+    assert_eq!(keys, vec![y]);
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn define_and_freeze_object_fields() {
+    let heap = Heap::new(256);
+    let mut obj = heap.alloc_object(0).expect("alloc");
+    let x = heap.alloc_str("x").unwrap();
+    let y = heap.alloc_str("y").unwrap();
+    let read_only = Attributes {
+        writable: false,
+        configurable: false,
+        ..Attributes::DATA
+    };
+    assert!(obj.define(&heap, x, AnyEnum::I32(1).into(), read_only));
+    obj.insert(&heap, x, AnyEnum::I32(2).into(), &mut no_cache());
+    assert!(matches!(
+        obj.get(&heap, x, &mut no_cache()),
+        AnyEnum::I32(1)
+    ));
+    assert!(!obj.define(&heap, x, AnyEnum::I32(3).into(), Attributes::DATA));
+    assert!(!obj.delete(&heap, x));
+    obj.insert(&heap, y, AnyEnum::I32(4).into(), &mut no_cache());
+    obj.freeze(&heap);
+    obj.insert(&heap, y, AnyEnum::I32(5).into(), &mut no_cache());
+    assert!(matches!(
+        obj.get(&heap, y, &mut no_cache()),
+        AnyEnum::I32(4)
+    ));
+    let z = heap.alloc_str("z").unwrap();
+    obj.insert(&heap, z, AnyEnum::I32(6).into(), &mut no_cache());
+    assert!(!obj.has_own(&heap, z));
+}
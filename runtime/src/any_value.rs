@@ -1,8 +1,10 @@
 //! An enum that can store any type known to the runtime
 
 pub use crate::allocator::{heap_types::EnvPtr, AnyPtr, HeapRefView};
-use crate::closure::{closure_env, Closure, ClosureVal};
+use crate::array::array_fields;
+use crate::closure::{AnyClosure, Closure, ClosureVal};
 use crate::i64_val::*;
+use crate::regexp::regexp_fields;
 use crate::string::StringPtr;
 use crate::wasm32::heap;
 use crate::HeapPtr;
@@ -22,14 +24,17 @@ pub enum AnyEnum {
     F64(*const f64),
     Bool(bool),
     Ptr(AnyPtr),
-    /// How to hold functions in Any needs to be further discussed. should
-    /// arity be included to arity-mismatch correctly? for now, no
+    /// A function in an Any holds its arity, so that it can be called with
+    /// more or fewer arguments than it takes. See `any_to_closure`
     ///
-    /// also, eventually we will distinguish functions and closures in any,
+    /// eventually we will distinguish functions and closures in any,
     /// because some functions will not be closure-converted. but not yet
-    Closure(Closure),
+    Closure(AnyClosure),
     Undefined,
     Null,
+    /// A missing element of an array, e.g., after `delete a[i]`. It never
+    /// leaves the array: reading it produces `undefined`
+    Hole,
 }
 
 impl Debug for AnyEnum {
@@ -43,6 +48,7 @@ impl Debug for AnyEnum {
             Closure(n) => write!(f, "Closure({:?})", n),
             Undefined => write!(f, "undefined"),
             Null => write!(f, "null"),
+            Hole => write!(f, "hole"),
         }
     }
 }
@@ -59,9 +65,9 @@ impl Display for AnyEnum {
             // TODO(luna): when we get our fancy rust-runtime-interning system,
             // use that here
             Bool(b) => write!(f, "{}", b),
-            Closure(closure) => write!(f, "{}", closure),
+            Closure(closure) => write!(f, "{}", closure.closure()),
             Ptr(p) => write!(f, "{}", p.view()),
-            Undefined => write!(f, "undefined"),
+            Undefined | Hole => write!(f, "undefined"),
             Null => write!(f, "null"),
         }
     }
@@ -78,7 +84,7 @@ impl Display for HeapRefView {
             Any(a) => write!(f, "{}", **a),
             Array(a) => {
                 write!(f, "[")?;
-                for elem in &*a {
+                for elem in a.iter() {
                     write!(f, "{}, ", **elem)?;
                 }
                 write!(f, "]")
@@ -88,6 +94,8 @@ impl Display for HeapRefView {
             NonPtr32(_) | MutF64(_) | Ptr(_) | Closure(_) => log_panic!("ref inside any"),
             Env(_) => log_panic!("not a value"),
             HT(_) => log_panic!("Display trait not implemented"),
+            RegExp(re) => write!(f, "{}", *re),
+            BigInt(n) => write!(f, "{}", *n),
         }
     }
 }
@@ -109,6 +117,8 @@ impl Debug for HeapRefView {
             Ptr(p) => write!(f, "!Ref({:?})", p),
             Env(e) => write!(f, "Env({:?})", e),
             Closure(c) => write!(f, "!Ref({})", *c),
+            RegExp(_) => write!(f, "RegExp({})", self),
+            BigInt(_) => write!(f, "BigInt({})", self),
         }
     }
 }
@@ -127,7 +137,9 @@ pub extern "C" fn any_to_i32(any: AnyValue) -> i32 {
             HeapRefView::String(s) => s.parse().expect("failed string->i32: what to do?"),
             _ => panic!("failed to unwrap {:?} as i32", *any),
         },
-        AnyEnum::Closure(_) | AnyEnum::Undefined => panic!("failed to unwrap {:?} as i32", *any),
+        AnyEnum::Closure(_) | AnyEnum::Undefined | AnyEnum::Hole => {
+            panic!("failed to unwrap {:?} as i32", *any)
+        }
         AnyEnum::Null => 0,
     }
 }
@@ -146,10 +158,13 @@ pub extern "C" fn any_to_f64(any: AnyValue) -> f64 {
         AnyEnum::Ptr(ptr) => match ptr.view() {
             HeapRefView::NonPtr32(_) => panic!("ref is not a value"),
             HeapRefView::String(s) => s.parse().unwrap_or(f64::NAN),
+            // JavaScript throws a TypeError instead, but we also use this
+            // to compare BigInts with numbers
+            HeapRefView::BigInt(n) => n.to_f64(),
             _ => f64::NAN,
         },
         AnyEnum::Closure(_) => f64::NAN,
-        AnyEnum::Undefined => f64::NAN,
+        AnyEnum::Undefined | AnyEnum::Hole => f64::NAN,
         AnyEnum::Null => 0.,
     }
 }
@@ -159,29 +174,120 @@ pub extern "C" fn f64_to_any(x: f64) -> AnyValue {
     return heap().f64_to_any(x);
 }
 
+/// `arity` is the number of arguments of the closure, not counting the
+/// environment
+#[no_mangle]
+pub extern "C" fn any_from_closure<'a>(val: ClosureVal, arity: u32) -> AnyValue {
+    AnyEnum::Closure(AnyClosure::new(*val, arity)).into()
+}
+
+/// the closure in `val`, which is about to be called with `arity` arguments,
+/// not counting the environment
+///
+/// like JavaScript, a function may be called with more or fewer arguments
+/// than it takes. when the arity of the closure is different, the result is
+/// `adapter` closed over `val`. `adapter` is the NotWasm function
+/// `jnks_fit_<arity>`, which passes its arguments on to `val`, padded with
+/// `undefined` or with the extra ones dropped. it is `u32::MAX` when there
+/// is no such function, e.g., when the arguments are not all `any`. a
+/// variadic closure always needs the adapter, which puts the arguments in an
+/// array
 #[no_mangle]
-pub extern "C" fn any_from_closure<'a>(val: ClosureVal) -> AnyValue {
-    AnyEnum::Closure(*val).into()
+pub extern "C" fn any_to_closure<'a>(val: AnyValue, arity: u32, adapter: u32) -> ClosureVal {
+    let clos = match *val {
+        AnyEnum::Closure(clos) => clos,
+        _ => panic!("unwrap incorrect type {}", stringify!(Fn)),
+    };
+    if clos.arity() == arity && !clos.is_variadic() {
+        return clos.closure().into();
+    }
+    if adapter == u32::MAX {
+        log_panic!(
+            "cannot call a function that takes {} arguments with {}",
+            clos.arity(),
+            arity
+        );
+    }
+    closure_proxy(val, adapter)
 }
+
+/// the NotWasm function `proxy` closed over the closure in `val`, which it
+/// reads from `env.0`. the proxy shares the function object of the closure,
+/// so it has the same properties and is the same function to `===`
 #[no_mangle]
-pub extern "C" fn any_to_closure<'a>(val: AnyValue) -> ClosureVal {
-    if let AnyEnum::Closure(inner) = *val {
-        inner.into()
+pub extern "C" fn closure_proxy(val: AnyValue, proxy: u32) -> ClosureVal {
+    let env = match *val {
+        AnyEnum::Closure(clos) => clos.env(),
+        _ => log_panic!("{:?} is not a function", *val),
+    };
+    let heap = heap();
+    // nothing else may refer to the closure or its function object while we
+    // allocate the proxy
+    heap.push_shadow_frame(2);
+    let fn_obj = if env.get_ptr().is_null() {
+        heap.alloc_object_or_gc(0)
     } else {
-        panic!("unwrap incorrect type {}", stringify!(Fn));
+        heap.set_in_current_shadow_frame_slot(0, Some(env.get_ptr()));
+        env.fn_obj()
+    };
+    heap.set_in_current_shadow_frame_slot(1, Some(fn_obj.get_ptr()));
+    let proxy_env = unsafe {
+        let mut proxy_env = heap.alloc_env_or_gc(1, fn_obj);
+        proxy_env.init_at(0, *val);
+        heap.pop_shadow_frame();
+        proxy_env
+    };
+    Closure(proxy_env, proxy as u16).into()
+}
+
+/// the arity of the function in `val`, which includes `this`
+#[no_mangle]
+pub extern "C" fn any_closure_arity(val: AnyValue) -> i32 {
+    match *val {
+        AnyEnum::Closure(clos) => clos.arity() as i32,
+        _ => log_panic!("{:?} is not a function", *val),
+    }
+}
+
+/// the function in `val`, marked as variadic or not. a variadic function
+/// takes `this` and an array of its other arguments, which `jnks_apply`
+/// makes for every call. it then calls the function that is not marked
+#[no_mangle]
+pub extern "C" fn any_closure_variadic(val: AnyValue, variadic: bool) -> AnyValue {
+    match *val {
+        AnyEnum::Closure(clos) => AnyEnum::Closure(clos.with_variadic(variadic)).into(),
+        _ => log_panic!("{:?} is not a function", *val),
     }
 }
 
 #[no_mangle]
-pub extern "C" fn any_from_fn<'a>(val: u32) -> AnyValue {
-    AnyEnum::Closure(Closure(unsafe { EnvPtr::null() }, val as u16)).into()
+pub extern "C" fn any_closure_is_variadic(val: AnyValue) -> bool {
+    match *val {
+        AnyEnum::Closure(clos) => clos.is_variadic(),
+        _ => log_panic!("{:?} is not a function", *val),
+    }
+}
+
+/// the function in `val`, marked as not a constructor
+#[no_mangle]
+pub extern "C" fn any_closure_non_constructor(val: AnyValue) -> AnyValue {
+    match *val {
+        AnyEnum::Closure(clos) => AnyEnum::Closure(clos.with_constructor(false)).into(),
+        _ => log_panic!("{:?} is not a function", *val),
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn any_from_fn<'a>(val: u32, arity: u32) -> AnyValue {
+    let clos = Closure(unsafe { EnvPtr::null() }, val as u16);
+    AnyEnum::Closure(AnyClosure::new(clos, arity)).into()
 }
 
 #[no_mangle]
 pub extern "C" fn any_to_ptr<'a>(val: AnyValue) -> AnyPtr {
     match *val {
         AnyEnum::Ptr(ptr) => ptr.into(),
-        AnyEnum::Closure(clos) => closure_env(clos.into()).fn_obj().as_any_ptr(),
+        AnyEnum::Closure(clos) => clos.env().fn_obj().as_any_ptr(),
         unknown_val => {
             log!("cannot unwrap {:?} as Ptr", unknown_val);
             panic!("");
@@ -189,6 +295,21 @@ pub extern "C" fn any_to_ptr<'a>(val: AnyValue) -> AnyPtr {
     }
 }
 
+/// `val` as an object whose fields we read or write. A function has an
+/// object of its own, and so do an array, for its fields other than its
+/// elements, and a regular expression, for fields such as `lastIndex`
+#[no_mangle]
+pub extern "C" fn any_to_object(val: AnyValue) -> AnyPtr {
+    if let AnyEnum::Ptr(ptr) = *val {
+        match ptr.view() {
+            HeapRefView::Array(array) => return array_fields(array).as_any_ptr(),
+            HeapRefView::RegExp(re) => return regexp_fields(re).as_any_ptr(),
+            _ => (),
+        }
+    }
+    any_to_ptr(val)
+}
+
 #[no_mangle]
 pub extern "C" fn any_from_ptr<'a>(val: AnyPtr) -> AnyValue {
     AnyEnum::Ptr(val).into()
@@ -208,10 +329,12 @@ pub extern "C" fn any_to_bool<'a>(val: AnyValue) -> bool {
             HeapRefView::String(s) => &*s != "",
             HeapRefView::Array(_) => true,
             HeapRefView::ObjectPtrPtr(_) => true,
+            HeapRefView::RegExp(_) => true,
+            HeapRefView::BigInt(n) => !n.is_zero(),
             _ => log_panic!("TODO: any_to_bool {:?}", val),
         },
         AnyEnum::Closure(_) => true,
-        AnyEnum::Undefined => false,
+        AnyEnum::Undefined | AnyEnum::Hole => false,
         AnyEnum::Null => false,
     }
 }
@@ -227,7 +350,7 @@ pub extern "C" fn any_from_bool<'a>(val: bool) -> AnyValue {
 /// `any_to_string` reuses the `fmt::Display` trait on NotWasm structs,
 /// which should be implemented according to the above JS spec.
 #[no_mangle]
-pub fn any_to_string(val: AnyValue) -> StringPtr {
+pub extern "C" fn any_to_string(val: AnyValue) -> StringPtr {
     let string = val.to_string();
     heap().alloc_str_or_gc(string.as_str())
 }
@@ -254,6 +377,25 @@ pub extern "C" fn any_is_object(val: AnyValue) -> bool {
     }
 }
 
+/// Is the given any value a function?
+#[no_mangle]
+pub extern "C" fn any_is_callable(val: AnyValue) -> bool {
+    match *val {
+        AnyEnum::Closure(_) => true,
+        _ => false,
+    }
+}
+
+/// Is the given any value a function that `new` can construct with? Arrows
+/// and methods are functions, but not constructors
+#[no_mangle]
+pub extern "C" fn any_is_constructor(val: AnyValue) -> bool {
+    match *val {
+        AnyEnum::Closure(clos) => clos.is_constructor(),
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -271,9 +413,11 @@ mod test {
         assert_disc(AnyEnum::F64(&0.0 as *const f64), 1);
         assert_disc(AnyEnum::Bool(false), 2);
         assert_disc(AnyEnum::Ptr(heap().alloc_str_or_gc(".").as_any_ptr()), 3);
-        assert_disc(AnyEnum::Closure(Closure(unsafe { EnvPtr::null() }, 0)), 4);
+        let clos = Closure(unsafe { EnvPtr::null() }, 0);
+        assert_disc(AnyEnum::Closure(AnyClosure::new(clos, 0)), 4);
         assert_disc(AnyEnum::Undefined, 5);
         assert_disc(AnyEnum::Null, 6);
+        assert_disc(AnyEnum::Hole, 7);
     }
     #[wasm_bindgen_test]
     fn any_size_is_64() {
@@ -322,7 +466,7 @@ mod test {
     /// an I64Val<Closure> looks like this:
     /// [16-bit padding][48-bit closure]
     /// an I64Val<AnyEnum::Closure> looks like this:
-    /// [48-bit closure][16-bit descriminant/padding]
+    /// [8-bit arity][48-bit closure][8-bit descriminant]
     #[wasm_bindgen_test]
     fn any_value_has_predicted_structure_48() {
         use crate::closure::*;
@@ -335,10 +479,11 @@ mod test {
             fake_env
         };
         let fake_closure = closure_new(fake_env, 13);
-        let into_any = any_from_closure(fake_closure);
+        let into_any = any_from_closure(fake_closure, 2);
         log!("{:064b}", fake_closure.raw_val());
         log!("{:064b}", into_any.raw_val());
-        let raw_data_of_shifted = into_any.raw_val() >> 16;
+        assert_eq!(into_any.raw_val() >> 56, 2);
+        let raw_data_of_shifted = (into_any.raw_val() >> 8) & 0xffff_ffff_ffff;
         let calculated_closure: ClosureVal = unsafe { std::mem::transmute(raw_data_of_shifted) };
         assert_eq!(calculated_closure, fake_closure);
     }
@@ -1,21 +1,47 @@
 //! create managed Arrays
 
-use super::{heap, heap_types::ArrayPtr};
-use crate::AnyValue;
+use super::allocator::no_cache;
+use super::heap;
+use super::heap_types::{Array, ArrayPtr, EnvPtr, ObjectPtr};
+use crate::object::{object_empty, object_freeze};
+use crate::static_strings::static_strings;
+use crate::{AnyEnum, AnyValue, HeapPtr, HeapRefView};
 
 #[no_mangle]
 pub extern "C" fn array_new() -> ArrayPtr {
-    heap().alloc_or_gc(Vec::new())
+    heap().alloc_or_gc(Array::from(Vec::new()))
 }
 
+/// Like JavaScript, reading past the end of an array produces `undefined`,
+/// e.g., an argument that was not passed
 #[no_mangle]
 pub extern "C" fn array_index(array: ArrayPtr, index: i32) -> AnyValue {
-    array[index as usize].clone()
+    if index >= 0 && (index as usize) < array.len() {
+        element(array[index as usize])
+    } else {
+        AnyEnum::Undefined.into()
+    }
 }
 
+/// The value of an element of an array, which is `undefined` for a hole
+pub(crate) fn element(elem: AnyValue) -> AnyValue {
+    match *elem {
+        AnyEnum::Hole => AnyEnum::Undefined.into(),
+        _ => elem,
+    }
+}
+
+/// Whether `index` is an element of the array, and not a hole
+pub(crate) fn has_element(array: ArrayPtr, index: i32) -> bool {
+    index >= 0 && (index as usize) < array.len() && !matches!(*array[index as usize], AnyEnum::Hole)
+}
+
+/// Like JavaScript, assigning to an element of a frozen array does nothing
 #[no_mangle]
 pub extern "C" fn array_set(mut array: ArrayPtr, index: i32, val: AnyValue) -> AnyValue {
-    array[index as usize] = val;
+    if !array.frozen {
+        array[index as usize] = val;
+    }
     val
 }
 
@@ -28,24 +54,119 @@ pub extern "C" fn array_length(array: ArrayPtr) -> i32 {
 pub extern "C" fn array_slice(array: ArrayPtr, a: i32, b: i32) -> ArrayPtr {
     let a = if a < 0 { todo!() } else { a as usize };
     let b = if b < 0 { todo!() } else { b as usize };
-    heap().alloc_or_gc(array[a..b].to_vec())
+    // like JavaScript, indices past the end are clamped
+    let b = b.min(array.len());
+    let a = a.min(b);
+    heap().alloc_or_gc(Array::from(array[a..b].to_vec()))
 }
 
+/// Pushing onto a frozen array does nothing, where JavaScript would throw
 #[no_mangle]
 pub extern "C" fn array_push(mut array: ArrayPtr, value: AnyValue) -> i32 {
-    array.push(value);
+    if !array.frozen {
+        array.push(value);
+    }
     array.len() as i32
 }
 
 #[no_mangle]
 pub extern "C" fn array_concat(a: ArrayPtr, b: ArrayPtr) -> ArrayPtr {
-    heap().alloc_or_gc(a.iter().chain(b.iter()).cloned().collect::<Vec<_>>())
+    heap().alloc_or_gc(Array::from(
+        a.iter().chain(b.iter()).cloned().collect::<Vec<_>>(),
+    ))
+}
+
+/// `[...a, ...b]`, where `parts` is `[a, b]`. Only arrays can be spread, and
+/// their holes are spread as `undefined`
+#[no_mangle]
+pub extern "C" fn array_spread(parts: ArrayPtr) -> ArrayPtr {
+    let result = array_new();
+    for part in parts.iter() {
+        match **part {
+            AnyEnum::Ptr(ptr) => match ptr.view() {
+                HeapRefView::Array(elems) => {
+                    for elem in elems.iter() {
+                        array_push(result, element(*elem));
+                    }
+                }
+                _ => log_panic!("cannot spread {:?}", **part),
+            },
+            _ => log_panic!("cannot spread {:?}", **part),
+        }
+    }
+    result
+}
+
+/// The object that holds the fields of `array` other than its elements,
+/// which is allocated the first time that it is needed. The object of a
+/// frozen array is frozen too.
+pub(crate) fn array_fields(mut array: ArrayPtr) -> ObjectPtr {
+    if let Some(fields) = array.fields {
+        return fields;
+    }
+    let heap = heap();
+    heap.push_shadow_frame(1);
+    heap.set_in_current_shadow_frame_slot(0, Some(array.get_ptr()));
+    let fields = object_empty();
+    unsafe { heap.pop_shadow_frame() };
+    if array.frozen {
+        fields.freeze(heap);
+    }
+    array.fields = Some(fields);
+    fields
+}
+
+/// `__JNKS.template(cooked, raw)`, the strings of a tagged template: the
+/// array `cooked`, whose `raw` field is the array `raw`. Both arrays are
+/// frozen, like the ones that JavaScript passes to the tag.
+#[no_mangle]
+pub extern "C" fn array_template(
+    _env: EnvPtr,
+    _this: AnyValue,
+    cooked: AnyValue,
+    raw: AnyValue,
+) -> AnyValue {
+    let array = match *cooked {
+        AnyEnum::Ptr(ptr) => match ptr.view() {
+            HeapRefView::Array(array) => array,
+            _ => log_panic!("the strings of a template are not an array"),
+        },
+        _ => log_panic!("the strings of a template are not an array"),
+    };
+    let heap = heap();
+    heap.push_shadow_frame(2);
+    heap.set_in_current_shadow_frame_slot(0, Some(array.get_ptr()));
+    if let AnyEnum::Ptr(raw) = *raw {
+        heap.set_in_current_shadow_frame_slot(1, Some(raw.get_ptr()));
+    }
+    let mut fields = array_fields(array);
+    fields.insert(heap, static_strings().raw, raw, &mut no_cache());
+    unsafe { heap.pop_shadow_frame() };
+    object_freeze(raw);
+    object_freeze(cooked)
+}
+
+/// An array of strings, where `None` is `undefined`
+pub(crate) fn alloc_strings(strings: Vec<Option<String>>) -> ArrayPtr {
+    let heap = heap();
+    let mut array = heap.alloc_or_gc(Array::from(Vec::with_capacity(strings.len())));
+    // nothing else refers to the array while we allocate its elements
+    heap.push_shadow_frame(1);
+    heap.set_in_current_shadow_frame_slot(0, Some(array.get_ptr()));
+    for s in strings {
+        let elem = match s {
+            Some(s) => AnyEnum::Ptr(heap.alloc_str_or_gc(&s).as_any_ptr()),
+            None => AnyEnum::Undefined,
+        };
+        array.push(elem.into());
+    }
+    unsafe { heap.pop_shadow_frame() };
+    array
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::AnyEnum;
     use wasm_bindgen_test::wasm_bindgen_test;
     #[test]
     #[wasm_bindgen_test]
@@ -56,5 +177,35 @@ mod test {
         assert_eq!(array_push(arr, AnyEnum::I32(7).into()), 2);
         assert_eq!(array_push(arr, AnyEnum::I32(98).into()), 3);
         assert_eq!(array_index(arr, 2), AnyEnum::I32(98).into());
+        assert_eq!(array_index(arr, 3), AnyEnum::Undefined.into());
+        assert_eq!(array_index(arr, -1), AnyEnum::Undefined.into());
+    }
+    #[test]
+    #[wasm_bindgen_test]
+    fn holes_read_as_undefined() {
+        crate::init();
+        let arr = array_new();
+        array_push(arr, AnyEnum::I32(1).into());
+        array_push(arr, AnyEnum::Hole.into());
+        assert_eq!(array_index(arr, 1), AnyEnum::Undefined.into());
+        assert!(has_element(arr, 0));
+        assert!(!has_element(arr, 1));
+        let parts = array_new();
+        array_push(parts, AnyEnum::Ptr(arr.as_any_ptr()).into());
+        assert_eq!(
+            array_index(array_spread(parts), 1),
+            AnyEnum::Undefined.into()
+        );
+    }
+    #[test]
+    #[wasm_bindgen_test]
+    fn frozen_arrays_do_not_change() {
+        crate::init();
+        let arr = array_new();
+        array_push(arr, AnyEnum::I32(1).into());
+        object_freeze(AnyEnum::Ptr(arr.as_any_ptr()).into());
+        array_set(arr, 0, AnyEnum::I32(2).into());
+        assert_eq!(array_push(arr, AnyEnum::I32(3).into()), 1);
+        assert_eq!(array_index(arr, 0), AnyEnum::I32(1).into());
     }
 }
@@ -0,0 +1,608 @@
+//! arbitrary-precision integers, which are the values of BigInts
+//!
+//! A `BigInt` is a sign and a magnitude. The magnitude is a little-endian
+//! sequence of base 2^32 digits without leading zeros, so zero has no digits
+//! and is never negative. The values are immutable: each operation allocates
+//! a new `BigInt` on the heap.
+//!
+//! Mixing BigInts and numbers in arithmetic throws a `TypeError`, as it does
+//! in JavaScript:
+//! https://tc39.es/ecma262/#sec-applystringornumericbinaryoperator
+
+pub use super::heap_types::BigIntPtr;
+use crate::exceptions::throw_error;
+use crate::heap_types::StringPtr;
+use crate::{heap, AnyEnum, AnyValue, HeapPtr, HeapRefView};
+use std::cmp::Ordering;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+/// The largest power of ten that is a digit, which we use to print and parse
+/// nine decimal digits at a time
+const DECIMAL_DIGIT: u32 = 1_000_000_000;
+
+const MIX_MESSAGE: &str = "Cannot mix BigInt and other types, use explicit conversions";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigInt {
+    negative: bool,
+    digits: Vec<u32>,
+}
+
+fn trim(mut digits: Vec<u32>) -> Vec<u32> {
+    while digits.last() == Some(&0) {
+        digits.pop();
+    }
+    digits
+}
+
+fn cmp_magnitude(a: &[u32], b: &[u32]) -> Ordering {
+    a.len()
+        .cmp(&b.len())
+        .then_with(|| a.iter().rev().cmp(b.iter().rev()))
+}
+
+fn add_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (long, short) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    let mut result = Vec::with_capacity(long.len() + 1);
+    let mut carry = 0u64;
+    for (i, &d) in long.iter().enumerate() {
+        let sum = d as u64 + *short.get(i).unwrap_or(&0) as u64 + carry;
+        result.push(sum as u32);
+        carry = sum >> 32;
+    }
+    if carry != 0 {
+        result.push(carry as u32);
+    }
+    result
+}
+
+/// `a - b`, where `a >= b`
+fn sub_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(a.len());
+    let mut borrow = 0i64;
+    for (i, &d) in a.iter().enumerate() {
+        let diff = d as i64 - *b.get(i).unwrap_or(&0) as i64 - borrow;
+        result.push(diff as u32);
+        borrow = (diff < 0) as i64;
+    }
+    trim(result)
+}
+
+fn mul_magnitude(a: &[u32], b: &[u32]) -> Vec<u32> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let mut result = vec![0u32; a.len() + b.len()];
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let t = x as u64 * y as u64 + result[i + j] as u64 + carry;
+            result[i + j] = t as u32;
+            carry = t >> 32;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+    trim(result)
+}
+
+/// `a * m + c`, in place
+fn mul_small_add(a: &mut Vec<u32>, m: u32, c: u32) {
+    let mut carry = c as u64;
+    for d in a.iter_mut() {
+        let t = *d as u64 * m as u64 + carry;
+        *d = t as u32;
+        carry = t >> 32;
+    }
+    if carry != 0 {
+        a.push(carry as u32);
+    }
+}
+
+/// The quotient and remainder of `a / d`, where `d` is not zero
+fn divmod_small(a: &[u32], d: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0u32; a.len()];
+    let mut rem = 0u64;
+    for i in (0..a.len()).rev() {
+        let t = (rem << 32) | a[i] as u64;
+        quotient[i] = (t / d as u64) as u32;
+        rem = t % d as u64;
+    }
+    (trim(quotient), rem as u32)
+}
+
+fn shl_bits(a: &[u32], shift: u32) -> Vec<u32> {
+    if shift == 0 {
+        return a.to_vec();
+    }
+    let mut result = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0u32;
+    for &d in a {
+        result.push((d << shift) | carry);
+        carry = d >> (32 - shift);
+    }
+    result.push(carry);
+    result
+}
+
+fn shr_bits(a: &[u32], shift: u32) -> Vec<u32> {
+    if shift == 0 {
+        return trim(a.to_vec());
+    }
+    let mut result = vec![0u32; a.len()];
+    for i in 0..a.len() {
+        let high = a.get(i + 1).map_or(0, |d| d << (32 - shift));
+        result[i] = (a[i] >> shift) | high;
+    }
+    trim(result)
+}
+
+/// The quotient and remainder of `a / b`, where `b` is not zero. This is
+/// Algorithm D from Knuth's The Art of Computer Programming, Volume 2,
+/// Section 4.3.1
+fn divmod_magnitude(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if cmp_magnitude(a, b) == Ordering::Less {
+        return (vec![], a.to_vec());
+    }
+    if b.len() == 1 {
+        let (quotient, rem) = divmod_small(a, b[0]);
+        return (quotient, trim(vec![rem]));
+    }
+    // normalize so that the top digit of the divisor has its high bit set,
+    // which makes each estimate of a quotient digit off by at most two
+    let shift = b[b.len() - 1].leading_zeros();
+    let v = trim(shl_bits(b, shift));
+    let mut u = shl_bits(a, shift);
+    u.resize(a.len() + 1, 0);
+    let n = v.len();
+    let m = a.len() - n;
+    let mut quotient = vec![0u32; m + 1];
+    for j in (0..=m).rev() {
+        let top = ((u[j + n] as u64) << 32) | u[j + n - 1] as u64;
+        let mut qhat = std::cmp::min(top / v[n - 1] as u64, u32::MAX as u64);
+        let mut rhat = top - qhat * v[n - 1] as u64;
+        while rhat <= u32::MAX as u64
+            && qhat * v[n - 2] as u64 > ((rhat << 32) | u[j + n - 2] as u64)
+        {
+            qhat -= 1;
+            rhat += v[n - 1] as u64;
+        }
+        // u[j..=j + n] -= qhat * v
+        let mut borrow = 0i64;
+        let mut carry = 0u64;
+        for i in 0..n {
+            let p = qhat * v[i] as u64 + carry;
+            carry = p >> 32;
+            let t = u[i + j] as i64 - borrow - (p & u32::MAX as u64) as i64;
+            u[i + j] = t as u32;
+            borrow = (t < 0) as i64;
+        }
+        let t = u[j + n] as i64 - borrow - carry as i64;
+        u[j + n] = t as u32;
+        if t < 0 {
+            // the estimate was one too large, so add v back
+            qhat -= 1;
+            let mut carry = 0u64;
+            for i in 0..n {
+                let sum = u[i + j] as u64 + v[i] as u64 + carry;
+                u[i + j] = sum as u32;
+                carry = sum >> 32;
+            }
+            u[j + n] = u[j + n].wrapping_add(carry as u32);
+        }
+        quotient[j] = qhat as u32;
+    }
+    (trim(quotient), shr_bits(&u[..n], shift))
+}
+
+impl BigInt {
+    fn new(negative: bool, digits: Vec<u32>) -> Self {
+        let digits = trim(digits);
+        BigInt {
+            negative: negative && !digits.is_empty(),
+            digits,
+        }
+    }
+
+    pub fn zero() -> Self {
+        BigInt::new(false, vec![])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// Parses the contents of a string as `BigInt(string)` does: an optional
+    /// sign and decimal digits, or unsigned digits with a `0x`, `0o` or `0b`
+    /// prefix, surrounded by whitespace. The empty string is zero
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        let lower = s.to_ascii_lowercase();
+        let (negative, radix, body) = if lower.starts_with("0x") {
+            (false, 16, &s[2..])
+        } else if lower.starts_with("0o") {
+            (false, 8, &s[2..])
+        } else if lower.starts_with("0b") {
+            (false, 2, &s[2..])
+        } else if let Some(rest) = s.strip_prefix('-') {
+            (true, 10, rest)
+        } else {
+            (false, 10, s.strip_prefix('+').unwrap_or(s))
+        };
+        if body.is_empty() && !s.is_empty() {
+            return None;
+        }
+        let mut digits = vec![];
+        for c in body.chars() {
+            mul_small_add(&mut digits, radix, c.to_digit(radix)?);
+        }
+        Some(BigInt::new(negative, digits))
+    }
+
+    /// The BigInt with the value of `x`, if it is an integer
+    pub fn from_f64(mut x: f64) -> Option<Self> {
+        if !x.is_finite() || x.trunc() != x {
+            return None;
+        }
+        let negative = x < 0.0;
+        x = x.abs();
+        let base = 4294967296.0;
+        let mut digits = vec![];
+        while x >= 1.0 {
+            let digit = x % base;
+            digits.push(digit as u32);
+            x = (x - digit) / base;
+        }
+        Some(BigInt::new(negative, digits))
+    }
+
+    /// The nearest number, which loses precision beyond 2^53
+    pub fn to_f64(&self) -> f64 {
+        let magnitude = self
+            .digits
+            .iter()
+            .rev()
+            .fold(0.0, |acc, &d| acc * 4294967296.0 + d as f64);
+        if self.negative {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+
+    /// Whether the BigInt has the value of the number `x`
+    pub fn equals_f64(&self, x: f64) -> bool {
+        BigInt::from_f64(x).map_or(false, |x| &x == self)
+    }
+
+    pub fn neg(&self) -> Self {
+        BigInt::new(!self.negative, self.digits.clone())
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        if self.negative == other.negative {
+            return BigInt::new(self.negative, add_magnitude(&self.digits, &other.digits));
+        }
+        match cmp_magnitude(&self.digits, &other.digits) {
+            Ordering::Less => {
+                BigInt::new(other.negative, sub_magnitude(&other.digits, &self.digits))
+            }
+            _ => BigInt::new(self.negative, sub_magnitude(&self.digits, &other.digits)),
+        }
+    }
+
+    pub fn sub(&self, other: &Self) -> Self {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &Self) -> Self {
+        BigInt::new(
+            self.negative != other.negative,
+            mul_magnitude(&self.digits, &other.digits),
+        )
+    }
+
+    /// The quotient rounded towards zero and the remainder, which has the
+    /// sign of `self`, or `None` when `other` is zero
+    pub fn div_rem(&self, other: &Self) -> Option<(Self, Self)> {
+        if other.is_zero() {
+            return None;
+        }
+        let (quotient, rem) = divmod_magnitude(&self.digits, &other.digits);
+        Some((
+            BigInt::new(self.negative != other.negative, quotient),
+            BigInt::new(self.negative, rem),
+        ))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self.negative, other.negative) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_magnitude(&self.digits, &other.digits),
+            (true, true) => cmp_magnitude(&other.digits, &self.digits),
+        }
+    }
+}
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// The decimal digits of the BigInt, which is its `ToString`. Note that
+/// `console.log` prints an `n` after them
+impl Display for BigInt {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        // nine decimal digits at a time, least significant first
+        let mut chunks = vec![];
+        let mut rest = self.digits.clone();
+        while !rest.is_empty() {
+            let (quotient, chunk) = divmod_small(&rest, DECIMAL_DIGIT);
+            chunks.push(chunk);
+            rest = quotient;
+        }
+        if self.negative {
+            write!(f, "-")?;
+        }
+        let mut chunks = chunks.iter().rev();
+        write!(f, "{}", chunks.next().unwrap())?;
+        for chunk in chunks {
+            write!(f, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+/// The `BigInt` that `val` points to, if it is one
+pub fn bigint_of(val: AnyEnum) -> Option<BigIntPtr> {
+    match val {
+        AnyEnum::Ptr(ptr) => match ptr.view() {
+            HeapRefView::BigInt(n) => Some(n),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn alloc(n: BigInt) -> BigIntPtr {
+    heap().alloc_or_gc(n)
+}
+
+fn alloc_any(n: BigInt) -> AnyValue {
+    AnyEnum::Ptr(alloc(n).as_any_ptr()).into()
+}
+
+/// Applies an arithmetic operator to `a` and `b` when either of them is a
+/// BigInt. When only one of them is, this throws a `TypeError` and produces
+/// `undefined`. When neither is, this produces `None`, so that the caller
+/// can treat them as numbers
+pub(crate) fn bigint_arith(
+    a: AnyValue,
+    b: AnyValue,
+    op: fn(&BigInt, &BigInt) -> Option<BigInt>,
+) -> Option<AnyValue> {
+    match (bigint_of(*a), bigint_of(*b)) {
+        (None, None) => None,
+        (Some(a), Some(b)) => Some(match op(&a, &b) {
+            Some(n) => alloc_any(n),
+            None => AnyEnum::Undefined.into(),
+        }),
+        _ => {
+            throw_error("TypeError", MIX_MESSAGE);
+            Some(AnyEnum::Undefined.into())
+        }
+    }
+}
+
+/// `-a` when `a` is a BigInt
+pub(crate) fn bigint_neg_any(a: AnyValue) -> Option<AnyValue> {
+    bigint_of(*a).map(|n| alloc_any(n.neg()))
+}
+
+/// The quotient of `a / b`, which throws a `RangeError` when `b` is zero
+pub(crate) fn checked_div(a: &BigInt, b: &BigInt) -> Option<BigInt> {
+    match a.div_rem(b) {
+        Some((quotient, _)) => Some(quotient),
+        None => {
+            throw_error("RangeError", "Division by zero");
+            None
+        }
+    }
+}
+
+/// The remainder of `a / b`, which throws a `RangeError` when `b` is zero
+pub(crate) fn checked_rem(a: &BigInt, b: &BigInt) -> Option<BigInt> {
+    match a.div_rem(b) {
+        Some((_, rem)) => Some(rem),
+        None => {
+            throw_error("RangeError", "Division by zero");
+            None
+        }
+    }
+}
+
+/// A BigInt literal, whose digits are decimal
+#[no_mangle]
+pub extern "C" fn bigint_from_str(s: StringPtr) -> BigIntPtr {
+    match BigInt::parse(&s) {
+        Some(n) => alloc(n),
+        None => log_panic!("invalid BigInt literal {}", &*s),
+    }
+}
+
+/// `BigInt(value)`, which converts integral numbers, strings and booleans
+#[no_mangle]
+pub extern "C" fn bigint_from_any(value: AnyValue) -> AnyValue {
+    let n = match *value {
+        AnyEnum::I32(i) => Ok(BigInt::from_f64(i as f64).unwrap()),
+        AnyEnum::F64(f) => {
+            let f = unsafe { *f };
+            BigInt::from_f64(f).ok_or_else(|| {
+                let message = format!(
+                    "The number {} cannot be converted to a BigInt because it is not an integer",
+                    *value
+                );
+                ("RangeError", message)
+            })
+        }
+        AnyEnum::Bool(b) => Ok(BigInt::from_f64(b as i32 as f64).unwrap()),
+        AnyEnum::Ptr(ptr) => match ptr.view() {
+            HeapRefView::BigInt(n) => return AnyEnum::Ptr(n.as_any_ptr()).into(),
+            HeapRefView::String(s) => BigInt::parse(&s)
+                .ok_or_else(|| ("SyntaxError", format!("Cannot convert {} to a BigInt", &*s))),
+            _ => Err((
+                "TypeError",
+                format!("Cannot convert {} to a BigInt", *value),
+            )),
+        },
+        _ => Err((
+            "TypeError",
+            format!("Cannot convert {} to a BigInt", *value),
+        )),
+    };
+    match n {
+        Ok(n) => alloc_any(n),
+        Err((name, message)) => {
+            throw_error(name, &message);
+            AnyEnum::Undefined.into()
+        }
+    }
+}
+
+// The operators on BigInt-typed values. Since they must produce a BigInt even
+// when they throw, division and remainder by zero produce their dividend
+
+#[no_mangle]
+pub extern "C" fn bigint_plus(a: BigIntPtr, b: BigIntPtr) -> BigIntPtr {
+    alloc(a.add(&b))
+}
+
+#[no_mangle]
+pub extern "C" fn bigint_minus(a: BigIntPtr, b: BigIntPtr) -> BigIntPtr {
+    alloc(a.sub(&b))
+}
+
+#[no_mangle]
+pub extern "C" fn bigint_times(a: BigIntPtr, b: BigIntPtr) -> BigIntPtr {
+    alloc(a.mul(&b))
+}
+
+#[no_mangle]
+pub extern "C" fn bigint_over(a: BigIntPtr, b: BigIntPtr) -> BigIntPtr {
+    checked_div(&a, &b).map_or(a, alloc)
+}
+
+#[no_mangle]
+pub extern "C" fn bigint_mod(a: BigIntPtr, b: BigIntPtr) -> BigIntPtr {
+    checked_rem(&a, &b).map_or(a, alloc)
+}
+
+#[no_mangle]
+pub extern "C" fn bigint_neg(a: BigIntPtr) -> BigIntPtr {
+    alloc(a.neg())
+}
+
+#[no_mangle]
+pub extern "C" fn bigint_strict_equal(a: BigIntPtr, b: BigIntPtr) -> bool {
+    *a == *b
+}
+
+#[no_mangle]
+pub extern "C" fn bigint_less_than(a: BigIntPtr, b: BigIntPtr) -> bool {
+    *a < *b
+}
+
+#[no_mangle]
+pub extern "C" fn bigint_less_than_equal(a: BigIntPtr, b: BigIntPtr) -> bool {
+    *a <= *b
+}
+
+#[no_mangle]
+pub extern "C" fn bigint_greater_than(a: BigIntPtr, b: BigIntPtr) -> bool {
+    *a > *b
+}
+
+#[no_mangle]
+pub extern "C" fn bigint_greater_than_equal(a: BigIntPtr, b: BigIntPtr) -> bool {
+    *a >= *b
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use wasm_bindgen_test::*;
+
+    fn big(s: &str) -> BigInt {
+        BigInt::parse(s).unwrap()
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn parse_and_print() {
+        assert_eq!(big("0").to_string(), "0");
+        assert_eq!(big("-0").to_string(), "0");
+        assert_eq!(
+            big("123456789012345678901234567890").to_string(),
+            "123456789012345678901234567890"
+        );
+        assert_eq!(
+            big("-1000000000000000000").to_string(),
+            "-1000000000000000000"
+        );
+        assert_eq!(big("0xff").to_string(), "255");
+        assert_eq!(big(" 0b101 ").to_string(), "5");
+        assert_eq!(BigInt::parse("1.5"), None);
+        assert_eq!(BigInt::parse("-0x1"), None);
+        assert_eq!(BigInt::from_f64(2.5), None);
+        assert_eq!(
+            BigInt::from_f64(-1e20).unwrap().to_string(),
+            "-100000000000000000000"
+        );
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn arithmetic() {
+        let a = big("340282366920938463463374607431768211457");
+        let b = big("-18446744073709551615");
+        assert_eq!(
+            a.add(&b).to_string(),
+            "340282366920938463444927863358058659842"
+        );
+        assert_eq!(
+            b.sub(&a).to_string(),
+            "-340282366920938463481821351505477763072"
+        );
+        assert_eq!(
+            a.mul(&b).to_string(),
+            "-6277101735386680763495507056286727952657427581105975853055"
+        );
+        assert_eq!(a.add(&a.neg()), BigInt::zero());
+        assert!(b < a && b.neg() < a);
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn division() {
+        let a = big("340282366920938463463374607431768211457");
+        let b = big("-18446744073709551615");
+        let (q, r) = a.div_rem(&b).unwrap();
+        assert_eq!(q.to_string(), "-18446744073709551617");
+        assert_eq!(r.to_string(), "2");
+        assert_eq!(q.mul(&b).add(&r), a);
+        let (q, r) = big("-7").div_rem(&big("2")).unwrap();
+        assert_eq!((q.to_string(), r.to_string()), ("-3".into(), "-1".into()));
+        let c = big("98765432109876543210987654321098765432109876543210");
+        let d = big("12345678901234567890123");
+        let (q, r) = c.div_rem(&d).unwrap();
+        assert!(r < d);
+        assert_eq!(q.mul(&d).add(&r), c);
+        assert_eq!(c.div_rem(&BigInt::zero()), None);
+    }
+}
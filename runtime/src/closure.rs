@@ -1,6 +1,6 @@
 use super::heap_types::EnvPtr;
 use super::i64_val::*;
-use super::Tag;
+use crate::HeapPtr;
 
 /// this is a closure-converted closure, it represents a cons of an environment
 /// (a fixed array) and a function pointer. these two pointers can be combined
@@ -52,34 +52,75 @@ pub extern "C" fn closure_func(closure: ClosureVal) -> u32 {
 ///
 /// this is to allow arity mismatches to be correctly handled even when the
 /// arity of the closure would otherwise not be known (for example, in an Any,
-/// or if an argument type was specified as AnyFunc)
+/// or if an argument type was specified as AnyFunc). the arity counts every
+/// argument but the environment, so for a JavaScript function, it includes
+/// `this`
+///
+/// the highest bit of the arity marks a variadic function, which takes
+/// `this` and an array of its other arguments, however many there are. a
+/// variadic function of arity 3 also takes `new.target` after `this`. the
+/// next bit marks a function that is not a constructor, such as an arrow
 ///
 /// you must not obtain a reference to any of its fields, since it is
 /// unaligned. https://github.com/rust-lang/rust/issues/27060 it is unaligned
 /// because of the need to fit it in Any
-///
-/// note that this is not being used for now. arity mismatches are not allowed
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy)]
 #[repr(packed)]
-pub struct AnyClosure(*const Tag, u16, u8);
+pub struct AnyClosure(pub EnvPtr, pub u16, pub u8);
 impl AsI64 for AnyClosure {}
 pub type AnyClosureVal = I64Val<AnyClosure>;
 
-#[no_mangle]
-pub extern "C" fn any_closure_new(ptr: *const Tag, func: u16, arity: u8) -> AnyClosureVal {
-    AnyClosure(ptr, func, arity).into()
-}
-#[no_mangle]
-pub extern "C" fn any_closure_ptr(closure: AnyClosureVal) -> *const Tag {
-    closure.0
-}
-#[no_mangle]
-pub extern "C" fn any_closure_func(closure: AnyClosureVal) -> u32 {
-    closure.1 as u32
+const VARIADIC: u8 = 0x80;
+const NOT_CONSTRUCTOR: u8 = 0x40;
+const FLAGS: u8 = VARIADIC | NOT_CONSTRUCTOR;
+
+impl AnyClosure {
+    pub fn new(closure: Closure, arity: u32) -> Self {
+        AnyClosure(closure.0, closure.1, arity as u8)
+    }
+    /// the same closure, marked as variadic or not
+    pub fn with_variadic(&self, variadic: bool) -> Self {
+        self.with_flag(VARIADIC, variadic)
+    }
+    /// the same closure, marked as a constructor or not
+    pub fn with_constructor(&self, constructor: bool) -> Self {
+        self.with_flag(NOT_CONSTRUCTOR, !constructor)
+    }
+    fn with_flag(&self, flag: u8, set: bool) -> Self {
+        let arity = self.2 & !flag;
+        AnyClosure(self.0, self.1, if set { arity | flag } else { arity })
+    }
+    /// the closure with its arity erased
+    pub fn closure(&self) -> Closure {
+        Closure(self.0, self.1)
+    }
+    pub fn env(&self) -> EnvPtr {
+        self.0
+    }
+    pub fn arity(&self) -> u32 {
+        (self.2 & !FLAGS) as u32
+    }
+    pub fn is_variadic(&self) -> bool {
+        self.2 & VARIADIC != 0
+    }
+    pub fn is_constructor(&self) -> bool {
+        self.2 & NOT_CONSTRUCTOR == 0
+    }
 }
-#[no_mangle]
-pub extern "C" fn any_closure_arity(closure: AnyClosureVal) -> u32 {
-    closure.2 as u32
+
+/// two closures are the same function when they share a function object,
+/// since a proxy that coerces the arguments and result of a closure shares
+/// its function object. a function without an environment has no function
+/// object, so it is only the same as itself
+impl PartialEq for AnyClosure {
+    fn eq(&self, other: &Self) -> bool {
+        let (env, other_env) = (self.env(), other.env());
+        if env.get_ptr().is_null() || other_env.get_ptr().is_null() {
+            self.closure() == other.closure()
+        } else {
+            env.fn_obj() == other_env.fn_obj()
+        }
+    }
 }
 
 #[cfg(test)]
@@ -136,7 +177,7 @@ mod test {
         };
         let clos = closure_new(env, 0); // dummy 0
         let mut as_obj: crate::heap_types::ObjectPtr =
-            unsafe { std::mem::transmute(any_to_ptr(any_from_closure(clos))) };
+            unsafe { std::mem::transmute(any_to_ptr(any_from_closure(clos, 1))) };
         as_obj.insert(
             crate::heap(),
             crate::heap().alloc_str_or_gc("x"),
@@ -156,7 +197,7 @@ mod test {
         let back_out = closure_env(clos);
         assert_eq!(env, back_out);
         let as_obj: crate::heap_types::ObjectPtr =
-            unsafe { std::mem::transmute(any_to_ptr(any_from_closure(clos))) };
+            unsafe { std::mem::transmute(any_to_ptr(any_from_closure(clos, 1))) };
         assert_eq!(
             as_obj.get(
                 crate::heap(),
@@ -167,4 +208,71 @@ mod test {
         );
         // this could be finished with an EnvGet, but i found the bug already
     }
+    #[wasm_bindgen_test]
+    fn any_to_closure_fits_arity() {
+        use crate::any_value::*;
+        crate::init();
+        let env = unsafe {
+            let env = env_alloc(1, object_empty());
+            env_init_at(env, 0, AnyEnum::I32(5).into())
+        };
+        let clos = closure_new(env, 7);
+        let any = any_from_closure(clos, 3);
+        assert_eq!(any_closure_arity(any), 3);
+        // called with as many arguments as it takes, it is the closure itself
+        assert_eq!(any_to_closure(any, 3, 9), clos);
+        // otherwise, it is the adapter closed over the closure, which shares
+        // its function object
+        let fitted = any_to_closure(any, 2, 9);
+        assert_eq!(closure_func(fitted), 9);
+        let fitted_env = closure_env(fitted);
+        assert_eq!(fitted_env.len(), 1);
+        assert_eq!(fitted_env.fn_obj(), env.fn_obj());
+    }
+    #[wasm_bindgen_test]
+    fn variadic_is_always_fitted() {
+        use crate::any_value::*;
+        crate::init();
+        let env = unsafe { env_alloc(0, object_empty()) };
+        let clos = closure_new(env, 7);
+        let any = any_closure_variadic(any_from_closure(clos, 2), true);
+        assert!(any_closure_is_variadic(any));
+        assert_eq!(any_closure_arity(any), 2);
+        // even a call with as many arguments as it takes must put them in
+        // an array
+        assert_eq!(closure_func(any_to_closure(any, 2, 9)), 9);
+        assert_eq!(closure_func(any_to_closure(any, 4, 9)), 9);
+        // which then calls the closure itself
+        let unmarked = any_closure_variadic(any, false);
+        assert!(!any_closure_is_variadic(unmarked));
+        assert_eq!(any_to_closure(unmarked, 2, 9), clos);
+    }
+    #[wasm_bindgen_test]
+    fn non_constructor_keeps_arity() {
+        use crate::any_value::*;
+        crate::init();
+        let env = unsafe { env_alloc(0, object_empty()) };
+        let clos = closure_new(env, 7);
+        let any = any_from_closure(clos, 3);
+        assert!(any_is_constructor(any));
+        let arrow = any_closure_non_constructor(any);
+        assert!(!any_is_constructor(arrow));
+        assert!(any_is_callable(arrow));
+        assert_eq!(any_closure_arity(arrow), 3);
+        assert_eq!(any_to_closure(arrow, 3, 9), clos);
+    }
+    #[wasm_bindgen_test]
+    fn proxy_is_same_function() {
+        use crate::any_value::*;
+        use crate::ops::janky_strict_equal;
+        crate::init();
+        let env = unsafe { env_alloc(0, object_empty()) };
+        let any = any_from_closure(closure_new(env, 7), 2);
+        let proxy = closure_proxy(any, 9);
+        assert_eq!(closure_func(proxy), 9);
+        assert!(janky_strict_equal(any_from_closure(proxy, 2), any));
+        let other = unsafe { env_alloc(0, object_empty()) };
+        let other = any_from_closure(closure_new(other, 7), 2);
+        assert!(!janky_strict_equal(other, any));
+    }
 }
@@ -15,6 +15,7 @@
 //!   coercions out that we have in there right now could move here
 
 use crate::any_value::{AnyValue as Any, *};
+use crate::bigint::BigInt;
 use crate::heap_types::*;
 
 pub fn i32s_or_as_f64s<T, F, I>(a: Any, b: Any, floats: F, ints: I) -> Option<T>
@@ -47,8 +48,12 @@ pub fn i32s_or_as_f64s_any(
 
 /// if a is *already* an object (no coercion), return Some(a as
 /// Object). otherwise return None
+///
+/// functions are objects too: a closure matches as its function object
 pub fn match_object(a: AnyEnum) -> Option<ObjectPtr> {
-    if let AnyEnum::Ptr(p) = a {
+    if let AnyEnum::Closure(clos) = a {
+        Some(clos.env().fn_obj())
+    } else if let AnyEnum::Ptr(p) = a {
         if let HeapRefView::ObjectPtrPtr(obj) = p.view() {
             Some(obj)
         } else {
@@ -71,6 +76,11 @@ pub fn abstract_eq(a: AnyEnum, b: AnyEnum) -> bool {
         (AnyEnum::Bool(a), AnyEnum::Bool(b)) => return a == b,
         (AnyEnum::Ptr(a), AnyEnum::Ptr(b)) => match (a.view(), b.view()) {
             (HeapRefView::String(a), HeapRefView::String(b)) => return a == b,
+            (HeapRefView::BigInt(a), HeapRefView::BigInt(b)) => return *a == *b,
+            (HeapRefView::String(s), HeapRefView::BigInt(n))
+            | (HeapRefView::BigInt(n), HeapRefView::String(s)) => {
+                return BigInt::parse(&s).as_ref() == Some(&*n)
+            }
             (HeapRefView::NonPtr32(_), _) | (_, HeapRefView::NonPtr32(_)) => {
                 panic!("ref is not a value")
             }
@@ -112,6 +122,11 @@ fn even_abstract_eq(a: AnyEnum, b: AnyEnum) -> Option<bool> {
                 // presumably will be caught on the right side on the odd pass
                 _ => return None,
             },
+            HeapRefView::BigInt(n) => match a {
+                AnyEnum::I32(i) => n.equals_f64(i as f64),
+                AnyEnum::F64(f) => n.equals_f64(unsafe { *f }),
+                _ => return None,
+            },
             // 8
             HeapRefView::NonPtr32(_) => log_panic!("ref is not a value"),
             // 8
@@ -0,0 +1,84 @@
+//! The state of an exception that is being thrown
+//!
+//! Compiled code does not use WebAssembly exceptions. Instead, `throw` stores
+//! the thrown value here and every function returns early until it reaches a
+//! `catch`, which takes the value back out. Each compiled function calls
+//! `exn_pending` after calls that may throw to decide whether to keep
+//! unwinding. Since unwinding only pops shadow stack frames and never
+//! allocates, the pending value does not need to be a GC root.
+
+use crate::allocator::{no_cache, HeapPtr, HeapRefView};
+use crate::any_value::{AnyEnum, AnyValue};
+use crate::heap_types::StringPtr;
+use crate::static_strings::static_strings;
+use crate::wasm32::heap;
+
+static mut PENDING: Option<AnyValue> = None;
+
+extern "C" {
+    /// Creates an error object with the given `name` and `message`. The
+    /// runtime cannot build objects with prototypes itself, so the host
+    /// forwards this to `jnks_new_error` in the program
+    fn jankscripten_new_error(name: StringPtr, message: StringPtr) -> AnyValue;
+}
+
+/// Start throwing `value`
+#[no_mangle]
+pub extern "C" fn exn_throw(value: AnyValue) {
+    unsafe {
+        PENDING = Some(value);
+    }
+}
+
+/// Start throwing a new error, such as a `TypeError`, from the runtime. The
+/// function that calls this must still return a value to the compiled code,
+/// which ignores it because an exception is pending
+pub fn throw_error(name: &str, message: &str) {
+    let heap = heap();
+    let name = heap.alloc_str_or_gc(name);
+    heap.push_shadow_frame(1);
+    heap.set_in_current_shadow_frame_slot(0, Some(name.get_ptr()));
+    let message = heap.alloc_str_or_gc(message);
+    unsafe { heap.pop_shadow_frame() };
+    exn_throw(unsafe { jankscripten_new_error(name, message) });
+}
+
+/// Whether an exception is being thrown
+#[no_mangle]
+pub extern "C" fn exn_pending() -> bool {
+    unsafe { PENDING.is_some() }
+}
+
+/// Stop throwing, producing the thrown value
+#[no_mangle]
+pub extern "C" fn exn_catch() -> AnyValue {
+    unsafe { PENDING.take() }.expect("no exception to catch")
+}
+
+/// Report an exception that was not caught by the program and stop
+#[no_mangle]
+pub extern "C" fn exn_uncaught() {
+    let value = exn_catch();
+    let obj = match *value {
+        AnyEnum::Ptr(p) => match p.view() {
+            HeapRefView::ObjectPtrPtr(obj) => Some(obj),
+            _ => None,
+        },
+        _ => None,
+    };
+    match obj {
+        Some(obj) => {
+            let strings = static_strings();
+            let name = obj.get(heap(), strings.name, &mut no_cache());
+            let message = obj.get(heap(), strings.message, &mut no_cache());
+            match (name, message) {
+                (AnyEnum::Undefined, _) | (_, AnyEnum::Undefined) => {
+                    error!("Uncaught [object Object]")
+                }
+                (name, message) => error!("Uncaught {}: {}", name, message),
+            }
+        }
+        None => error!("Uncaught {}", *value),
+    }
+    panic!("uncaught exception");
+}
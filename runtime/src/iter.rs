@@ -0,0 +1,60 @@
+//! Support for iteration, which `jnks_iter` and `jnks_iter_next` in the
+//! NotWasm runtime implement
+
+use crate::allocator::no_cache;
+use crate::array::element;
+use crate::coercions::match_object;
+use crate::heap;
+use crate::static_strings::static_strings;
+use crate::string::{string_index, string_length};
+use crate::{AnyEnum, AnyValue, HeapRefView};
+
+/// Iterated by indexing into an array
+const ARRAY: i32 = 0;
+/// Iterated by indexing into a string
+const STRING: i32 = 1;
+/// Iterated with the iteration protocol, i.e. it has an `@@iterator` method
+const PROTOCOL: i32 = 2;
+/// Not iterable
+const NOT_ITERABLE: i32 = 3;
+
+/// How `v` is iterated over
+#[no_mangle]
+pub extern "C" fn iter_kind(v: AnyValue) -> i32 {
+    if let AnyEnum::Ptr(ptr) = *v {
+        match ptr.view() {
+            HeapRefView::Array(_) => return ARRAY,
+            HeapRefView::String(_) => return STRING,
+            _ => (),
+        }
+    }
+    match *iter_method(v) {
+        AnyEnum::Closure(_) => PROTOCOL,
+        _ => NOT_ITERABLE,
+    }
+}
+
+/// `v[Symbol.iterator]`, which is `undefined` when `v` is not an object
+#[no_mangle]
+pub extern "C" fn iter_method(v: AnyValue) -> AnyValue {
+    match match_object(*v) {
+        Some(obj) => obj
+            .get(heap(), static_strings().iterator, &mut no_cache())
+            .into(),
+        None => AnyEnum::Undefined.into(),
+    }
+}
+
+/// The element at index `i` of the array or string `v`, or `end` when `i` is
+/// past its end
+#[no_mangle]
+pub extern "C" fn iter_index(v: AnyValue, i: i32, end: AnyValue) -> AnyValue {
+    match *v {
+        AnyEnum::Ptr(ptr) => match ptr.view() {
+            HeapRefView::Array(arr) if (i as usize) < arr.len() => element(arr[i as usize]),
+            HeapRefView::String(s) if i < string_length(s) => string_index(s, i),
+            _ => end,
+        },
+        _ => end,
+    }
+}
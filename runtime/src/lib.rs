@@ -41,14 +41,20 @@ pub mod any_value;
 #[cfg(all(target_arch = "wasm32"))]
 pub mod array;
 #[cfg(all(target_arch = "wasm32"))]
+pub mod bigint;
+#[cfg(all(target_arch = "wasm32"))]
 pub mod closure;
 #[cfg(all(target_arch = "wasm32"))]
 mod coercions;
 #[cfg(all(target_arch = "wasm32"))]
 pub mod env;
 #[cfg(all(target_arch = "wasm32"))]
+pub mod exceptions;
+#[cfg(all(target_arch = "wasm32"))]
 pub mod ht;
 #[cfg(all(target_arch = "wasm32"))]
+pub mod iter;
+#[cfg(all(target_arch = "wasm32"))]
 pub mod math;
 #[cfg(all(target_arch = "wasm32"))]
 pub mod object;
@@ -57,6 +63,8 @@ pub mod ops;
 #[cfg(all(target_arch = "wasm32"))]
 pub mod r#ref; // Rust raw identifier syntax
 #[cfg(all(target_arch = "wasm32"))]
+pub mod regexp;
+#[cfg(all(target_arch = "wasm32"))]
 pub mod static_strings;
 #[cfg(all(target_arch = "wasm32"))]
 pub mod std_lib;
@@ -70,8 +78,6 @@ mod util;
 #[cfg(all(target_arch = "wasm32"))]
 mod wasm32;
 
-#[cfg(all(target_arch = "wasm32"))]
-use crate::allocator::Tag;
 #[cfg(all(target_arch = "wasm32"))]
 use allocator::*;
 #[cfg(all(target_arch = "wasm32"))]
@@ -1,11 +1,41 @@
 //! Bindings to heap_type's ObjectPtr: instances of hidden classes
 
-use crate::allocator::no_cache;
+use crate::allocator::{accessor_pair, no_cache, Attributes};
+use crate::any_value::any_to_bool;
+use crate::array::{alloc_strings, has_element};
+use crate::coercions::match_object;
 use crate::heap;
 use crate::heap_types::*;
+use crate::ops::{janky_in, property_key};
 use crate::static_strings::static_strings;
+use crate::string::string_length;
 use crate::{AnyEnum, AnyValue, HeapPtr, HeapRefView};
 
+extern "C" {
+    // the host forwards these to the program, which exports them, since the
+    // runtime cannot call the program's closures itself
+    fn jankscripten_call_getter(getter: AnyValue, this: AnyValue) -> AnyValue;
+    fn jankscripten_call_setter(setter: AnyValue, this: AnyValue, value: AnyValue) -> AnyValue;
+}
+
+/// Applies the getter of an accessor property to `this`. An accessor
+/// without a getter produces `undefined`.
+pub(crate) fn call_getter(getter: AnyValue, this: AnyValue) -> AnyValue {
+    match *getter {
+        AnyEnum::Undefined => getter,
+        _ => unsafe { jankscripten_call_getter(getter, this) },
+    }
+}
+
+/// Applies the setter of an accessor property to `this` and `value`. An
+/// accessor without a setter ignores the assignment.
+pub(crate) fn call_setter(setter: AnyValue, this: AnyValue, value: AnyValue) -> AnyValue {
+    match *setter {
+        AnyEnum::Undefined => value,
+        _ => unsafe { jankscripten_call_setter(setter, this, value) },
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn object_empty() -> ObjectPtr {
     heap().alloc_object_or_gc(0)
@@ -31,6 +61,290 @@ pub extern "C" fn object_get(
     object.get(heap(), field, cache).into()
 }
 
+/// Copy the own enumerable fields of `src` into `dst`, except for `__proto__`
+/// and the field names in the array `excluded`. Does nothing if `src` is not
+/// an object.
+#[no_mangle]
+pub extern "C" fn object_copy_own(
+    mut dst: ObjectPtr,
+    src: AnyValue,
+    excluded: AnyValue,
+) -> AnyValue {
+    let excluded = match *excluded {
+        AnyEnum::Ptr(p) => match p.view() {
+            HeapRefView::Array(arr) => arr.iter().map(|k| **k).collect(),
+            _ => vec![],
+        },
+        _ => vec![],
+    };
+    if let Some(src) = match_object(*src) {
+        for (key, _, attributes) in src.own_fields(heap()) {
+            if key == static_strings().__proto__ || !attributes.enumerable {
+                continue;
+            }
+            let is_excluded = excluded.iter().any(|k| match k {
+                AnyEnum::Ptr(p) => match p.view() {
+                    HeapRefView::String(s) => s == key,
+                    _ => false,
+                },
+                _ => false,
+            });
+            if !is_excluded {
+                // applies getters
+                let value = src.get(heap(), key, &mut no_cache());
+                dst.insert(heap(), key, value.into(), &mut no_cache());
+            }
+        }
+    }
+    AnyEnum::Undefined.into()
+}
+
+/// The keys that `for (x in v)` enumerates, as an array of strings. An object
+/// produces its own enumerable fields in the order that they were added,
+/// followed by those of its prototype chain that it does not shadow. The chain
+/// ends at `stop`, which is `Object.prototype`, since the builtin methods on
+/// it are not enumerable in JavaScript. An array produces its indices that
+/// are not holes, a string produces its indices, and other values produce
+/// nothing.
+#[no_mangle]
+pub extern "C" fn object_for_in_keys(v: AnyValue, stop: AnyValue) -> AnyValue {
+    if let AnyEnum::Ptr(ptr) = *v {
+        let indices: Option<Vec<usize>> = match ptr.view() {
+            HeapRefView::Array(arr) => Some(
+                (0..arr.len())
+                    .filter(|&i| has_element(arr, i as i32))
+                    .collect(),
+            ),
+            HeapRefView::String(s) => Some((0..string_length(s) as usize).collect()),
+            _ => None,
+        };
+        if let Some(indices) = indices {
+            let indices = indices.into_iter().map(|i| Some(i.to_string())).collect();
+            return AnyEnum::Ptr(alloc_strings(indices).as_any_ptr()).into();
+        }
+    }
+    let stop = match_object(*stop).map(|o| o.get_ptr());
+    let mut keys: Vec<StringPtr> = vec![];
+    let mut seen: Vec<StringPtr> = vec![];
+    let mut obj = match_object(*v);
+    while let Some(o) = obj {
+        if Some(o.get_ptr()) == stop {
+            break;
+        }
+        obj = None;
+        for (key, value, attributes) in o.own_fields(heap()) {
+            if key == static_strings().__proto__ {
+                obj = match_object(value);
+            } else if !seen.contains(&key) {
+                // a field that is not enumerable still shadows the prototype
+                seen.push(key);
+                if attributes.enumerable {
+                    keys.push(key);
+                }
+            }
+        }
+    }
+    // the keys are already fields of objects, so allocating the array does
+    // not need to root them
+    let keys = keys
+        .into_iter()
+        .map(|key| AnyEnum::Ptr(key.as_any_ptr()).into())
+        .collect::<Vec<AnyValue>>();
+    AnyEnum::Ptr(heap().alloc_or_gc(Array::from(keys)).as_any_ptr()).into()
+}
+
+/// Whether `for (x in v)` still visits `key`, which `object_for_in_keys`
+/// produced. A key that is deleted before the loop reaches it is skipped,
+/// and the indices of a string never change.
+#[no_mangle]
+pub extern "C" fn object_for_in_has(v: AnyValue, key: AnyValue) -> bool {
+    let is_string = match *v {
+        AnyEnum::Ptr(ptr) => matches!(ptr.view(), HeapRefView::String(_)),
+        _ => false,
+    };
+    is_string || janky_in(key, v)
+}
+
+/// `Object.prototype.hasOwnProperty`, which is applied to `this`. the
+/// prototype is not an own field, even though we store it as `__proto__`
+#[no_mangle]
+pub extern "C" fn object_has_own_property(this: AnyValue, key: AnyValue) -> bool {
+    let key = property_key(key);
+    match match_object(*this) {
+        Some(obj) => key != static_strings().__proto__ && obj.has_own(heap(), key),
+        None => false,
+    }
+}
+
+/// `Object.defineProperty`. The status tells stdlib which TypeError to
+/// throw: 0 when the property was defined, 1 when `obj` is not an object, 2
+/// when `desc` is not an object, 3 when the property cannot be redefined and
+/// 4 when `desc` has both accessors and a value. Arrays are not supported.
+#[no_mangle]
+pub extern "C" fn object_define_property(obj: AnyValue, key: AnyValue, desc: AnyValue) -> i32 {
+    let mut obj = match match_object(*obj) {
+        Some(obj) => obj,
+        None => return 1,
+    };
+    let desc = match match_object(*desc) {
+        Some(desc) => desc,
+        None => return 2,
+    };
+    let heap = heap();
+    let key = property_key(key);
+    heap.push_shadow_frame(4);
+    heap.set_in_current_shadow_frame_slot(0, Some(obj.get_ptr()));
+    heap.set_in_current_shadow_frame_slot(1, Some(desc.get_ptr()));
+    heap.set_in_current_shadow_frame_slot(2, Some(key.get_ptr()));
+    let status = define_property(&mut obj, key, desc);
+    unsafe { heap.pop_shadow_frame() };
+    status
+}
+
+fn define_property(obj: &mut ObjectPtr, key: StringPtr, desc: ObjectPtr) -> i32 {
+    let heap = heap();
+    let strings = static_strings();
+    // a descriptor may inherit its fields, and they may be getters
+    let field = |name: StringPtr| {
+        desc.find(heap, name, &mut no_cache())
+            .map(|_| desc.get(heap, name, &mut no_cache()))
+    };
+    let flag = |name: StringPtr| field(name).map(|v| any_to_bool(v.into()));
+    let getter = field(strings.get);
+    let setter = field(strings.set);
+    let has_value = field(strings.value).is_some();
+    let writable = flag(strings.writable);
+    let is_accessor = getter.is_some() || setter.is_some();
+    if is_accessor && (has_value || writable.is_some()) {
+        return 4;
+    }
+    let old = obj.own_property(heap, key);
+    // a descriptor that has neither accessors nor a value keeps the kind of
+    // an existing field, and the attributes that it doesn't mention are
+    // kept too. a new field defaults to none of them
+    let accessor = match old {
+        Some((_, old)) if !is_accessor && !has_value && writable.is_none() => old.accessor,
+        _ => is_accessor,
+    };
+    let kept = match old {
+        Some((_, old)) => old,
+        None => Attributes {
+            accessor,
+            writable: false,
+            enumerable: false,
+            configurable: false,
+        },
+    };
+    let attributes = Attributes {
+        accessor,
+        writable: !accessor && writable.unwrap_or(kept.writable && !kept.accessor),
+        enumerable: flag(strings.enumerable).unwrap_or(kept.enumerable),
+        configurable: flag(strings.configurable).unwrap_or(kept.configurable),
+    };
+    if accessor {
+        let (old_getter, old_setter) = match old {
+            Some((accessors, old)) if old.accessor => accessor_pair(accessors),
+            _ => (AnyEnum::Undefined, AnyEnum::Undefined),
+        };
+        let accessors = heap.alloc_or_gc(Array::from(vec![
+            getter.unwrap_or(old_getter).into(),
+            setter.unwrap_or(old_setter).into(),
+        ]));
+        heap.set_in_current_shadow_frame_slot(3, Some(accessors.get_ptr()));
+        let accessors = AnyEnum::Ptr(accessors.as_any_ptr()).into();
+        if !obj.define(heap, key, accessors, attributes) {
+            return 3;
+        }
+    } else {
+        // adding the field may allocate, which may move an f64 value, so the
+        // value is read afterwards
+        let placeholder = AnyEnum::Undefined.into();
+        if old.is_none() && !obj.define(heap, key, placeholder, Attributes::DATA) {
+            return 3;
+        }
+        let value = match (field(strings.value), old) {
+            (Some(value), _) => value,
+            (None, Some((value, old))) if !old.accessor => value,
+            _ => AnyEnum::Undefined,
+        };
+        if !obj.define(heap, key, value.into(), attributes) {
+            return 3;
+        }
+    }
+    0
+}
+
+/// `Object.getOwnPropertyDescriptor`. Produces `undefined` if `obj` is not
+/// an object, or it does not have the field `key` itself. The descriptor
+/// inherits from `object_prototype`.
+#[no_mangle]
+pub extern "C" fn object_get_own_property_descriptor(
+    obj: AnyValue,
+    key: AnyValue,
+    object_prototype: AnyValue,
+) -> AnyValue {
+    let obj = match match_object(*obj) {
+        Some(obj) => obj,
+        None => return AnyEnum::Undefined.into(),
+    };
+    let heap = heap();
+    let strings = static_strings();
+    let key = property_key(key);
+    let attributes = match obj.own_property(heap, key) {
+        Some((_, attributes)) if key != strings.__proto__ => attributes,
+        _ => return AnyEnum::Undefined.into(),
+    };
+    heap.push_shadow_frame(3);
+    heap.set_in_current_shadow_frame_slot(0, Some(obj.get_ptr()));
+    heap.set_in_current_shadow_frame_slot(1, Some(key.get_ptr()));
+    let mut desc = object_empty();
+    heap.set_in_current_shadow_frame_slot(2, Some(desc.get_ptr()));
+    let mut set = |name: StringPtr, value: AnyEnum| {
+        desc.insert(heap, name, value.into(), &mut no_cache());
+    };
+    set(strings.__proto__, *object_prototype);
+    // the fields are added in the order that JavaScript shows them, and the
+    // value is read once nothing else allocates
+    if attributes.accessor {
+        set(strings.get, AnyEnum::Undefined);
+        set(strings.set, AnyEnum::Undefined);
+    } else {
+        set(strings.value, AnyEnum::Undefined);
+        set(strings.writable, AnyEnum::Bool(attributes.writable));
+    }
+    set(strings.enumerable, AnyEnum::Bool(attributes.enumerable));
+    set(strings.configurable, AnyEnum::Bool(attributes.configurable));
+    let (value, _) = obj.own_property(heap, key).unwrap();
+    if attributes.accessor {
+        let (getter, setter) = accessor_pair(value);
+        set(strings.get, getter);
+        set(strings.set, setter);
+    } else {
+        set(strings.value, value);
+    }
+    unsafe { heap.pop_shadow_frame() };
+    AnyEnum::Ptr(desc.as_any_ptr()).into()
+}
+
+/// `Object.freeze`, which produces its argument. Freezing an array freezes
+/// its elements and its other fields. Other values are left as they are.
+#[no_mangle]
+pub extern "C" fn object_freeze(v: AnyValue) -> AnyValue {
+    if let AnyEnum::Ptr(ptr) = *v {
+        if let HeapRefView::Array(mut array) = ptr.view() {
+            array.frozen = true;
+            if let Some(fields) = array.fields {
+                fields.freeze(heap());
+            }
+            return v;
+        }
+    }
+    if let Some(obj) = match_object(*v) {
+        obj.freeze(heap());
+    }
+    v
+}
+
 /// Object.create
 /// TODO(luna): presumably there should be some way to type this correctly
 /// as returning a DynObject even though it's ultimately stored in a DynObject
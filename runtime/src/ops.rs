@@ -2,7 +2,10 @@
 
 use crate::allocator::no_cache;
 use crate::any_value::{AnyValue as Any, *};
+use crate::array::{element, has_element};
+use crate::bigint::*;
 use crate::coercions::*;
+use crate::exceptions::throw_error;
 use crate::heap;
 use crate::heap_types::ObjectPtr;
 use crate::static_strings::static_strings;
@@ -18,6 +21,14 @@ use crate::util::unwrap_log;
 /// and math.
 #[no_mangle]
 pub extern "C" fn janky_primitive_plus(a: Any, b: Any) -> Any {
+    // BigInts are pointers too, but they are only concatenated with strings
+    let is_string =
+        |v: Any| matches!(*v, AnyEnum::Ptr(p) if matches!(p.view(), HeapRefView::String(_)));
+    if !is_string(a) && !is_string(b) {
+        if let Some(res) = bigint_arith(a, b, |a, b| Some(a.add(b))) {
+            return res;
+        }
+    }
     // First check: are either `a` or `b` pointers? If so, they'll be coerced
     // to strings and we'll perform string concatenation.
     match (*a, *b) {
@@ -45,8 +56,79 @@ pub extern "C" fn janky_primitive_plus(a: Any, b: Any) -> Any {
     }
 }
 
+/// `b` as an index of an array or string, if it is an integral number
+fn array_index(b: Any) -> Option<i32> {
+    match *b {
+        AnyEnum::I32(i) => Some(i),
+        AnyEnum::F64(f) if unsafe { *f } == (unsafe { *f } as i32) as f64 => {
+            Some(unsafe { *f } as i32)
+        }
+        _ => None,
+    }
+}
+
+/// `b` as the name of a field of an object, which is its string conversion
+/// unless it is already a string
+pub(crate) fn property_key(b: Any) -> StringPtr {
+    match *b {
+        AnyEnum::Ptr(key) => match key.view() {
+            HeapRefView::String(key) => key,
+            _ => any_to_string(b),
+        },
+        _ => any_to_string(b),
+    }
+}
+
+/// `a[b]` when the type of `a` is unknown. arrays and strings are indexed
+/// by numbers and objects by strings, anything else produces `undefined`
+#[no_mangle]
+pub extern "C" fn any_index(a: Any, b: Any) -> Any {
+    let index = array_index(b);
+    match (*a, index) {
+        (AnyEnum::Ptr(ptr), Some(i)) => match ptr.view() {
+            HeapRefView::Array(arr) if i >= 0 && (i as usize) < arr.len() => {
+                element(arr[i as usize])
+            }
+            HeapRefView::String(s) => string_index(s, i),
+            _ => AnyEnum::Undefined.into(),
+        },
+        (AnyEnum::Ptr(ptr), None) => match (ptr.view(), *b) {
+            (HeapRefView::ObjectPtrPtr(obj), AnyEnum::Ptr(key)) => match key.view() {
+                HeapRefView::String(key) => obj.get(heap(), key, &mut no_cache()).into(),
+                _ => AnyEnum::Undefined.into(),
+            },
+            _ => AnyEnum::Undefined.into(),
+        },
+        _ => AnyEnum::Undefined.into(),
+    }
+}
+
+/// `a[b] = v` when the type of `a` is unknown, the counterpart of
+/// `any_index`. assigning through anything but an array or object is ignored
+#[no_mangle]
+pub extern "C" fn any_set_index(a: Any, b: Any, v: Any) -> Any {
+    let index = array_index(b);
+    if let AnyEnum::Ptr(ptr) = *a {
+        match (ptr.view(), index) {
+            (HeapRefView::Array(mut arr), Some(i))
+                if i >= 0 && (i as usize) < arr.len() && !arr.frozen =>
+            {
+                arr[i as usize] = v;
+            }
+            (HeapRefView::ObjectPtrPtr(mut obj), _) => {
+                obj.insert(heap(), property_key(b), v, &mut no_cache());
+            }
+            _ => (),
+        }
+    }
+    v
+}
+
 #[no_mangle]
 pub extern "C" fn janky_minus(a: Any, b: Any) -> Any {
+    if let Some(res) = bigint_arith(a, b, |a, b| Some(a.sub(b))) {
+        return res;
+    }
     i32s_or_as_f64s_any(a, b, |a, b| a - b, |a, b| a - b).expect("unsupported for -")
 }
 #[no_mangle]
@@ -54,28 +136,53 @@ pub extern "C" fn janky_neg(a: Any) -> Any {
     match *a {
         AnyEnum::I32(i) => any_from_i32(-i),
         AnyEnum::F64(f) => f64_to_any(-unsafe { *f }),
-        _ => f64_to_any(-any_to_f64(a)),
+        _ => bigint_neg_any(a).unwrap_or_else(|| f64_to_any(-any_to_f64(a))),
     }
 }
 #[no_mangle]
 pub extern "C" fn janky_times(a: Any, b: Any) -> Any {
+    if let Some(res) = bigint_arith(a, b, |a, b| Some(a.mul(b))) {
+        return res;
+    }
     i32s_or_as_f64s_any(a, b, |a, b| a * b, |a, b| a * b).expect("unsupported for *")
 }
+/// the result is a number unless both operands are BigInts
 #[no_mangle]
-pub extern "C" fn janky_over(a: Any, b: Any) -> f64 {
-    i32s_or_as_f64s(a, b, |a, b| a / b, |a, b| a as f64 / b as f64).expect("unsupported for /")
+pub extern "C" fn janky_over(a: Any, b: Any) -> Any {
+    if let Some(res) = bigint_arith(a, b, checked_div) {
+        return res;
+    }
+    let quotient =
+        i32s_or_as_f64s(a, b, |a, b| a / b, |a, b| a as f64 / b as f64).expect("unsupported for /");
+    f64_to_any(quotient)
 }
 #[no_mangle]
 pub extern "C" fn janky_mod(a: Any, b: Any) -> Any {
+    if let Some(res) = bigint_arith(a, b, checked_rem) {
+        return res;
+    }
     i32s_or_as_f64s_any(a, b, |a, b| a % b, |a, b| a % b).expect("unsupported for %")
 }
 #[no_mangle]
 pub extern "C" fn janky_mod_f64(a: f64, b: f64) -> f64 {
     a % b
 }
+/// Numbers, strings and BigInts are compared by value, since equal values
+/// may be stored in different places, e.g., a string that `typeof` allocates
+/// and a string literal
 #[no_mangle]
 pub extern "C" fn janky_strict_equal(a: Any, b: Any) -> bool {
-    a == b
+    if let Some(res) = i32s_or_as_f64s(a, b, |a, b| a == b, |a, b| a == b) {
+        return res;
+    }
+    match (*a, *b) {
+        (AnyEnum::Ptr(x), AnyEnum::Ptr(y)) => match (x.view(), y.view()) {
+            (HeapRefView::String(x), HeapRefView::String(y)) => x == y,
+            (HeapRefView::BigInt(x), HeapRefView::BigInt(y)) => *x == *y,
+            _ => a == b,
+        },
+        _ => a == b,
+    }
 }
 #[no_mangle]
 pub extern "C" fn janky_equal(a: Any, b: Any) -> bool {
@@ -83,7 +190,7 @@ pub extern "C" fn janky_equal(a: Any, b: Any) -> bool {
 }
 #[no_mangle]
 pub extern "C" fn janky_strict_not_equal(a: Any, b: Any) -> bool {
-    a != b
+    !janky_strict_equal(a, b)
 }
 #[no_mangle]
 pub extern "C" fn janky_not_equal(a: Any, b: Any) -> bool {
@@ -98,9 +205,36 @@ pub extern "C" fn janky_not_equal(a: Any, b: Any) -> bool {
 pub extern "C" fn janky_typeof(a: Any) -> StringPtr {
     heap().alloc_str_or_gc(typeof_as_str(a))
 }
+/// `delete a[b]`. deleting an index of an array leaves a hole, which reads as
+/// `undefined` and is not an index of the array for `in` and for..in, rather
+/// than shifting the elements after it. an object moves
+/// to a class without the field, and deleting a field that it does not have
+/// does nothing. `__proto__` cannot be deleted, since it stands in for the
+/// accessor on `Object.prototype`. a field that is not configurable is kept,
+/// and produces false, and so is an element of a frozen array. anything else
+/// is ignored
 #[no_mangle]
-pub extern "C" fn janky_delete(_a: Any, _b: Any) -> bool {
-    todo!()
+pub extern "C" fn janky_delete(a: Any, b: Any) -> bool {
+    if let AnyEnum::Ptr(ptr) = *a {
+        if let HeapRefView::Array(mut arr) = ptr.view() {
+            if let Some(i) = array_index(b) {
+                if i >= 0 && (i as usize) < arr.len() {
+                    if arr.frozen {
+                        return false;
+                    }
+                    arr[i as usize] = AnyEnum::Hole.into();
+                }
+            }
+            return true;
+        }
+    }
+    if let Some(mut obj) = match_object(*a) {
+        let key = property_key(b);
+        if key != static_strings().__proto__ {
+            return obj.delete(heap(), key);
+        }
+    }
+    true
 }
 #[no_mangle]
 pub extern "C" fn janky_void(_: Any) -> Any {
@@ -118,9 +252,7 @@ pub extern "C" fn instance_of(a: Any, b: Any) -> bool {
         // this algorithm much cleaner, and avoid constant casts to Any, the
         // helper function inclusive_instance_of is used
         if let Some(obj) = match_object(*a) {
-            // two step process needed because of packed borrows
-            let constructor_env = constructor.0;
-            let constructor_obj = constructor_env.fn_obj();
+            let constructor_obj = constructor.env().fn_obj();
             let constructor_prototype =
                 constructor_obj.get(heap(), static_strings().prototype, &mut no_cache());
             let should_match = unwrap_log(match_object(constructor_prototype), "non-obj prototype");
@@ -180,9 +312,43 @@ fn inclusive_instance_of(obj: ObjectPtr, should_match: ObjectPtr) -> bool {
     }
 }
 
+/// `a in b`. an array has its indices and `length`, and an object has its own
+/// fields and those of its prototype chain. anything else throws a
+/// `TypeError`
 #[no_mangle]
 pub extern "C" fn janky_in(a: Any, b: Any) -> bool {
-    log_panic!("TODO(luna): janky_in");
+    if let AnyEnum::Ptr(ptr) = *b {
+        if let HeapRefView::Array(arr) = ptr.view() {
+            return match (array_index(a), *a) {
+                (Some(i), _) => has_element(arr, i),
+                (None, AnyEnum::Ptr(key)) => match key.view() {
+                    HeapRefView::String(key) => {
+                        &*key == "length" || key.parse().map_or(false, |i| has_element(arr, i))
+                    }
+                    _ => false,
+                },
+                (None, _) => false,
+            };
+        }
+    }
+    match match_object(*b) {
+        Some(obj) => {
+            let key = property_key(a);
+            let mut obj = Some(obj);
+            while let Some(o) = obj {
+                if o.has_own(heap(), key) {
+                    return true;
+                }
+                obj = match_object(o.get(heap(), static_strings().__proto__, &mut no_cache()));
+            }
+            false
+        }
+        None => {
+            let message = format!("Cannot use 'in' operator to search for '{}' in {}", *a, *b);
+            throw_error("TypeError", &message);
+            false
+        }
+    }
 }
 
 fn typeof_as_str(a: Any) -> &'static str {
@@ -191,7 +357,11 @@ fn typeof_as_str(a: Any) -> &'static str {
         AnyEnum::Bool(_) => "boolean",
         AnyEnum::Ptr(ptr) => match ptr.view() {
             HeapRefView::String(_) => "string",
-            HeapRefView::HT(_) | HeapRefView::Array(_) | HeapRefView::ObjectPtrPtr(_) => "object",
+            HeapRefView::BigInt(_) => "bigint",
+            HeapRefView::HT(_)
+            | HeapRefView::Array(_)
+            | HeapRefView::ObjectPtrPtr(_)
+            | HeapRefView::RegExp(_) => "object",
             HeapRefView::Any(what) => typeof_as_str(*what),
             HeapRefView::Class(_) => panic!("shouldn't be able to typeof non-value object data"),
             HeapRefView::MutF64(_) => "number",
@@ -203,7 +373,7 @@ fn typeof_as_str(a: Any) -> &'static str {
             }
         },
         AnyEnum::Closure(_) => "function",
-        AnyEnum::Undefined => "undefined",
+        AnyEnum::Undefined | AnyEnum::Hole => "undefined",
         AnyEnum::Null => "object",
     }
 }
@@ -0,0 +1,1262 @@
+//! regular expressions
+//!
+//! A `RegExp` is parsed when it is created, and matched with a backtracking
+//! matcher that follows the semantics of ECMAScript regular expressions:
+//! https://www.ecma-international.org/ecma-262/5.1/#sec-15.10
+//!
+//! We do not support lookbehind that captures groups from right to left,
+//! and positions in strings are counted in `char`s rather than UTF-16 code
+//! units.
+
+pub use super::heap_types::RegExpPtr;
+use crate::allocator::no_cache;
+use crate::any_value::any_to_f64;
+use crate::array::{alloc_strings, array_fields};
+use crate::heap_types::{ArrayPtr, ObjectPtr, StringPtr};
+use crate::object::object_empty;
+use crate::static_strings::static_strings;
+use crate::{heap, AnyEnum, AnyValue, HeapPtr, HeapRefView};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::Range;
+
+/// The start and end of each capturing group, where group 0 is the whole
+/// match
+type Captures = Vec<Option<(usize, usize)>>;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Flags {
+    pub global: bool,
+    pub ignore_case: bool,
+    pub multiline: bool,
+    pub dot_all: bool,
+    pub unicode: bool,
+    pub sticky: bool,
+}
+
+impl Flags {
+    fn parse(flags: &str) -> Result<Self, String> {
+        let mut result = Flags::default();
+        for c in flags.chars() {
+            let flag = match c {
+                'g' => &mut result.global,
+                'i' => &mut result.ignore_case,
+                'm' => &mut result.multiline,
+                's' => &mut result.dot_all,
+                'u' => &mut result.unicode,
+                'y' => &mut result.sticky,
+                _ => return Err(format!("invalid flag '{}'", c)),
+            };
+            if *flag {
+                return Err(format!("duplicate flag '{}'", c));
+            }
+            *flag = true;
+        }
+        Ok(result)
+    }
+}
+
+impl Display for Flags {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let flags = [
+            (self.global, 'g'),
+            (self.ignore_case, 'i'),
+            (self.multiline, 'm'),
+            (self.dot_all, 's'),
+            (self.unicode, 'u'),
+            (self.sticky, 'y'),
+        ];
+        for (set, c) in flags.iter() {
+            if *set {
+                write!(f, "{}", c)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The data of a heap-allocated regular expression
+#[derive(Debug)]
+pub struct RegExp {
+    pub source: String,
+    pub flags: Flags,
+    /// Where the next search of a global or sticky regular expression starts
+    pub last_index: usize,
+    node: Node,
+    /// The number of capturing groups
+    groups: usize,
+    /// The index of each named capturing group
+    names: Vec<(String, usize)>,
+    /// The object that holds the fields of this regular expression, such as
+    /// `lastIndex`, which is allocated the first time that it is needed
+    pub fields: Option<ObjectPtr>,
+}
+
+impl RegExp {
+    pub fn new(source: &str, flags: &str) -> Result<Self, String> {
+        let flags = Flags::parse(flags)?;
+        let mut parser = Parser {
+            chars: source.chars().collect(),
+            pos: 0,
+            groups: 0,
+            names: vec![],
+        };
+        let node = parser.parse()?;
+        Ok(RegExp {
+            source: source.to_string(),
+            flags,
+            last_index: 0,
+            node,
+            groups: parser.groups,
+            names: parser.names,
+            fields: None,
+        })
+    }
+
+    /// Match at exactly `start`
+    fn match_at(&self, input: &[char], start: usize) -> Option<Captures> {
+        let mut m = Matcher {
+            flags: self.flags,
+            input,
+            caps: vec![None; self.groups + 1],
+        };
+        let found = m.node(&self.node, start, &mut |m: &mut Matcher, end| {
+            m.caps[0] = Some((start, end));
+            true
+        });
+        if found {
+            Some(m.caps)
+        } else {
+            None
+        }
+    }
+
+    /// The first match that starts at or after `start`, or exactly at
+    /// `start` when the regular expression is sticky
+    fn search(&self, input: &[char], start: usize) -> Option<Captures> {
+        let last = if self.flags.sticky {
+            start
+        } else {
+            input.len()
+        };
+        (start..=last).find_map(|i| self.match_at(input, i))
+    }
+
+    /// `RegExp.prototype.exec`, which starts at and updates `lastIndex`
+    /// when the regular expression is global or sticky
+    fn exec(&mut self, input: &[char]) -> Option<Captures> {
+        let uses_last_index = self.flags.global || self.flags.sticky;
+        let start = if uses_last_index { self.last_index } else { 0 };
+        let result = if start > input.len() {
+            None
+        } else {
+            self.search(input, start)
+        };
+        if uses_last_index {
+            self.last_index = match &result {
+                Some(caps) => caps[0].unwrap().1,
+                None => 0,
+            };
+        }
+        result
+    }
+
+    fn group_named(&self, name: &str) -> Option<usize> {
+        self.names.iter().find(|(x, _)| x == name).map(|(_, n)| *n)
+    }
+}
+
+impl Display for RegExp {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        let source = if self.source.is_empty() {
+            "(?:)"
+        } else {
+            &self.source
+        };
+        write!(f, "/{}/{}", source, self.flags)
+    }
+}
+
+#[derive(Debug)]
+enum Node {
+    Char(char),
+    /// `.`
+    Dot,
+    Class(Class),
+    Seq(Vec<Node>),
+    Alt(Vec<Node>),
+    /// A capturing group and its index
+    Group(Box<Node>, usize),
+    Repeat(Box<Repeat>),
+    Backref(usize),
+    /// Replaced by `Backref` once all groups are parsed
+    NamedBackref(String),
+    LineStart,
+    LineEnd,
+    WordBoundary {
+        negated: bool,
+    },
+    Look {
+        node: Box<Node>,
+        ahead: bool,
+        negated: bool,
+    },
+}
+
+#[derive(Debug)]
+struct Repeat {
+    node: Node,
+    min: usize,
+    max: Option<usize>,
+    greedy: bool,
+    /// The groups inside `node`, which are cleared at each iteration
+    groups: Range<usize>,
+}
+
+#[derive(Debug)]
+struct Class {
+    negated: bool,
+    items: Vec<ClassItem>,
+}
+
+/// The `bool` of `\d`, `\w` and `\s` is true for `\D`, `\W` and `\S`
+#[derive(Debug, Clone, Copy)]
+enum ClassItem {
+    Range(char, char),
+    Digit(bool),
+    Word(bool),
+    Space(bool),
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    groups: usize,
+    names: Vec<(String, usize)>,
+}
+
+impl Parser {
+    fn parse(&mut self) -> Result<Node, String> {
+        let mut node = self.disjunction()?;
+        if self.pos < self.chars.len() {
+            return Err("unmatched ')'".into());
+        }
+        self.resolve_names(&mut node)?;
+        Ok(node)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn next(&mut self) -> Result<char, String> {
+        let c = self.peek().ok_or("unexpected end of pattern")?;
+        self.pos += 1;
+        Ok(c)
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn disjunction(&mut self) -> Result<Node, String> {
+        let mut alts = vec![self.alternative()?];
+        while self.eat('|') {
+            alts.push(self.alternative()?);
+        }
+        Ok(if alts.len() == 1 {
+            alts.pop().unwrap()
+        } else {
+            Node::Alt(alts)
+        })
+    }
+
+    fn alternative(&mut self) -> Result<Node, String> {
+        let mut terms = vec![];
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            terms.push(self.term()?);
+        }
+        Ok(Node::Seq(terms))
+    }
+
+    fn term(&mut self) -> Result<Node, String> {
+        let groups_before = self.groups;
+        let (node, quantifiable) = self.atom()?;
+        let (min, max) = match self.quantifier()? {
+            None => return Ok(node),
+            Some(_) if !quantifiable => return Err("nothing to repeat".into()),
+            Some(q) => q,
+        };
+        let greedy = !self.eat('?');
+        Ok(Node::Repeat(Box::new(Repeat {
+            node,
+            min,
+            max,
+            greedy,
+            groups: groups_before + 1..self.groups + 1,
+        })))
+    }
+
+    fn quantifier(&mut self) -> Result<Option<(usize, Option<usize>)>, String> {
+        let q = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                return match self.braces() {
+                    Some((min, Some(max))) if max < min => {
+                        Err("numbers out of order in {} quantifier".into())
+                    }
+                    q => Ok(q),
+                };
+            }
+            _ => return Ok(None),
+        };
+        self.pos += 1;
+        Ok(Some(q))
+    }
+
+    /// `{n}`, `{n,}` or `{n,m}`. Any other `{` is an ordinary character
+    fn braces(&mut self) -> Option<(usize, Option<usize>)> {
+        let start = self.pos;
+        let q = self.braces_inner();
+        if q.is_none() {
+            self.pos = start;
+        }
+        q
+    }
+
+    fn braces_inner(&mut self) -> Option<(usize, Option<usize>)> {
+        self.pos += 1;
+        let min = self.digits()?;
+        let max = if !self.eat(',') {
+            Some(min)
+        } else if self.peek() == Some('}') {
+            None
+        } else {
+            Some(self.digits()?)
+        };
+        if self.eat('}') {
+            Some((min, max))
+        } else {
+            None
+        }
+    }
+
+    fn digits(&mut self) -> Option<usize> {
+        let start = self.pos;
+        let mut n: usize = 0;
+        while let Some(d) = self.peek().and_then(|c| c.to_digit(10)) {
+            n = n.saturating_mul(10).saturating_add(d as usize);
+            self.pos += 1;
+        }
+        if self.pos == start {
+            None
+        } else {
+            Some(n)
+        }
+    }
+
+    /// The next atom or assertion, and whether it may be quantified
+    fn atom(&mut self) -> Result<(Node, bool), String> {
+        let node = match self.next()? {
+            '^' => return Ok((Node::LineStart, false)),
+            '$' => return Ok((Node::LineEnd, false)),
+            '(' => return self.group(),
+            '\\' => return self.atom_escape(),
+            '*' | '+' | '?' => return Err("nothing to repeat".into()),
+            '{' => {
+                self.pos -= 1;
+                if self.braces().is_some() {
+                    return Err("nothing to repeat".into());
+                }
+                self.pos += 1;
+                Node::Char('{')
+            }
+            '.' => Node::Dot,
+            '[' => Node::Class(self.class()?),
+            c => Node::Char(c),
+        };
+        Ok((node, true))
+    }
+
+    fn group(&mut self) -> Result<(Node, bool), String> {
+        let mut name = None;
+        if self.eat('?') {
+            let (ahead, negated) = match self.next()? {
+                ':' => {
+                    let node = self.disjunction()?;
+                    self.close()?;
+                    return Ok((node, true));
+                }
+                '=' => (true, false),
+                '!' => (true, true),
+                '<' if self.eat('=') => (false, false),
+                '<' if self.eat('!') => (false, true),
+                '<' => {
+                    name = Some(self.group_name()?);
+                    (true, false)
+                }
+                _ => return Err("invalid group".into()),
+            };
+            if name.is_none() {
+                let node = Box::new(self.disjunction()?);
+                self.close()?;
+                let look = Node::Look {
+                    node,
+                    ahead,
+                    negated,
+                };
+                // lookaheads may be quantified for compatibility with old
+                // engines
+                return Ok((look, ahead));
+            }
+        }
+        self.groups += 1;
+        let index = self.groups;
+        if let Some(name) = name {
+            if self.names.iter().any(|(x, _)| *x == name) {
+                return Err("duplicate capture group name".into());
+            }
+            self.names.push((name, index));
+        }
+        let node = self.disjunction()?;
+        self.close()?;
+        Ok((Node::Group(Box::new(node), index), true))
+    }
+
+    fn close(&mut self) -> Result<(), String> {
+        if self.eat(')') {
+            Ok(())
+        } else {
+            Err("unterminated group".into())
+        }
+    }
+
+    /// The name of `(?<name>` or `\k<name>`, after the `<`
+    fn group_name(&mut self) -> Result<String, String> {
+        let mut name = String::new();
+        loop {
+            match self.next() {
+                Ok('>') if !name.is_empty() => return Ok(name),
+                Ok(c) if c == '$' || c == '_' || c.is_alphanumeric() => name.push(c),
+                _ => return Err("invalid capture group name".into()),
+            }
+        }
+    }
+
+    /// An escape outside of a character class, after the `\`
+    fn atom_escape(&mut self) -> Result<(Node, bool), String> {
+        let node = match self.next()? {
+            'b' => return Ok((Node::WordBoundary { negated: false }, false)),
+            'B' => return Ok((Node::WordBoundary { negated: true }, false)),
+            '1'..='9' => {
+                self.pos -= 1;
+                Node::Backref(self.digits().unwrap())
+            }
+            'k' if self.eat('<') => Node::NamedBackref(self.group_name()?),
+            c => match self.class_escape(c)? {
+                ClassItem::Range(c, _) => Node::Char(c),
+                item => Node::Class(Class {
+                    negated: false,
+                    items: vec![item],
+                }),
+            },
+        };
+        Ok((node, true))
+    }
+
+    /// An escape that means the same thing inside and outside of a
+    /// character class, after the `\`
+    fn class_escape(&mut self, c: char) -> Result<ClassItem, String> {
+        let c = match c {
+            'd' => return Ok(ClassItem::Digit(false)),
+            'D' => return Ok(ClassItem::Digit(true)),
+            'w' => return Ok(ClassItem::Word(false)),
+            'W' => return Ok(ClassItem::Word(true)),
+            's' => return Ok(ClassItem::Space(false)),
+            'S' => return Ok(ClassItem::Space(true)),
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\u{b}',
+            'f' => '\u{c}',
+            '0' if !self.peek().map_or(false, |c| c.is_ascii_digit()) => '\0',
+            'c' => match self.peek() {
+                Some(letter) if letter.is_ascii_alphabetic() => {
+                    self.pos += 1;
+                    (letter as u8 % 32) as char
+                }
+                // `\c` without a letter is a backslash followed by `c`
+                _ => {
+                    self.pos -= 1;
+                    '\\'
+                }
+            },
+            'x' => self.hex(2).unwrap_or('x'),
+            'u' => self.hex(4).unwrap_or('u'),
+            c => c,
+        };
+        Ok(ClassItem::Range(c, c))
+    }
+
+    fn hex(&mut self, len: usize) -> Option<char> {
+        let digits: String = self.chars.get(self.pos..self.pos + len)?.iter().collect();
+        let c = std::char::from_u32(u32::from_str_radix(&digits, 16).ok()?)?;
+        self.pos += len;
+        Some(c)
+    }
+
+    /// A character class, after the `[`
+    fn class(&mut self) -> Result<Class, String> {
+        let negated = self.eat('^');
+        let mut items = vec![];
+        loop {
+            let c = self
+                .next()
+                .map_err(|_| "missing terminating ] for character class")?;
+            if c == ']' {
+                return Ok(Class { negated, items });
+            }
+            let lo = self.class_atom(c)?;
+            // a - that ends the class is an ordinary character
+            let is_range = self.peek() == Some('-')
+                && self.chars.get(self.pos + 1).map_or(false, |c| *c != ']');
+            if !is_range {
+                items.push(lo);
+                continue;
+            }
+            self.pos += 1;
+            let c = self.next()?;
+            let hi = self.class_atom(c)?;
+            match (lo, hi) {
+                (ClassItem::Range(lo, _), ClassItem::Range(hi, _)) => {
+                    if lo > hi {
+                        return Err("range out of order in character class".into());
+                    }
+                    items.push(ClassItem::Range(lo, hi));
+                }
+                // for compatibility with old engines, [\d-z] is \d, - or z
+                (lo, hi) => {
+                    items.push(lo);
+                    items.push(ClassItem::Range('-', '-'));
+                    items.push(hi);
+                }
+            }
+        }
+    }
+
+    fn class_atom(&mut self, c: char) -> Result<ClassItem, String> {
+        if c != '\\' {
+            return Ok(ClassItem::Range(c, c));
+        }
+        match self.next()? {
+            'b' => Ok(ClassItem::Range('\u{8}', '\u{8}')),
+            '-' => Ok(ClassItem::Range('-', '-')),
+            c => self.class_escape(c),
+        }
+    }
+
+    fn resolve_names(&self, node: &mut Node) -> Result<(), String> {
+        match node {
+            Node::NamedBackref(name) => {
+                let index = self.names.iter().find(|(x, _)| x == name);
+                *node = Node::Backref(index.ok_or("invalid named capture referenced")?.1);
+            }
+            Node::Seq(nodes) | Node::Alt(nodes) => {
+                for node in nodes {
+                    self.resolve_names(node)?;
+                }
+            }
+            Node::Group(node, _) | Node::Look { node, .. } => self.resolve_names(node)?,
+            Node::Repeat(r) => self.resolve_names(&mut r.node)?,
+            _ => (),
+        }
+        Ok(())
+    }
+}
+
+/// The continuation of a match: given the matcher and the position that the
+/// match has reached, does the rest of the pattern match?
+type Cont<'k, 'a> = dyn FnMut(&mut Matcher<'a>, usize) -> bool + 'k;
+
+struct Matcher<'a> {
+    flags: Flags,
+    input: &'a [char],
+    caps: Captures,
+}
+
+impl<'a> Matcher<'a> {
+    /// Match `node` at `i`, followed by `k`. Each function undoes its
+    /// changes to `caps` when it fails.
+    fn node(&mut self, node: &Node, i: usize, k: &mut Cont<'_, 'a>) -> bool {
+        match node {
+            Node::Char(_) | Node::Dot | Node::Class(_) => self.single(node, i) && k(self, i + 1),
+            Node::Seq(nodes) => self.seq(nodes, i, k),
+            Node::Alt(alts) => alts.iter().any(|alt| self.node(alt, i, k)),
+            Node::Group(node, n) => {
+                let n = *n;
+                self.node(node, i, &mut |m: &mut Matcher<'a>, j| {
+                    let saved = m.caps[n];
+                    m.caps[n] = Some((i, j));
+                    if k(m, j) {
+                        return true;
+                    }
+                    m.caps[n] = saved;
+                    false
+                })
+            }
+            Node::Repeat(r) if is_single(&r.node) => self.repeat_single(r, i, k),
+            Node::Repeat(r) => self.repeat(r, 0, i, k),
+            Node::Backref(n) => match self.caps.get(*n).copied().flatten() {
+                // a group that has not matched matches the empty string
+                None => k(self, i),
+                Some((start, end)) => {
+                    let len = end - start;
+                    let matches = i + len <= self.input.len()
+                        && (0..len).all(|d| self.eq(self.input[start + d], self.input[i + d]));
+                    matches && k(self, i + len)
+                }
+            },
+            Node::NamedBackref(_) => unreachable!("named backreference after parsing"),
+            Node::LineStart => {
+                let at_start = i == 0 || self.flags.multiline && is_line_end(self.input[i - 1]);
+                at_start && k(self, i)
+            }
+            Node::LineEnd => {
+                let at_end =
+                    i == self.input.len() || self.flags.multiline && is_line_end(self.input[i]);
+                at_end && k(self, i)
+            }
+            Node::WordBoundary { negated } => {
+                let before = i > 0 && is_word(self.input[i - 1]);
+                let after = i < self.input.len() && is_word(self.input[i]);
+                (before != after) != *negated && k(self, i)
+            }
+            Node::Look {
+                node,
+                ahead,
+                negated,
+            } => {
+                let saved = self.caps.clone();
+                // we never backtrack into a lookaround
+                let found = if *ahead {
+                    self.node(node, i, &mut |_, _| true)
+                } else {
+                    (0..=i)
+                        .rev()
+                        .any(|start| self.node(node, start, &mut |_, j| j == i))
+                };
+                if found == *negated {
+                    self.caps = saved;
+                    return false;
+                }
+                if k(self, i) {
+                    return true;
+                }
+                self.caps = saved;
+                false
+            }
+        }
+    }
+
+    fn seq(&mut self, nodes: &[Node], i: usize, k: &mut Cont<'_, 'a>) -> bool {
+        match nodes.split_first() {
+            None => k(self, i),
+            Some((first, rest)) => {
+                self.node(first, i, &mut |m: &mut Matcher<'a>, j| m.seq(rest, j, k))
+            }
+        }
+    }
+
+    /// `r` after `count` iterations
+    fn repeat(&mut self, r: &Repeat, count: usize, i: usize, k: &mut Cont<'_, 'a>) -> bool {
+        if count < r.min {
+            self.iterate(r, count, i, k)
+        } else if r.greedy {
+            self.iterate(r, count, i, k) || k(self, i)
+        } else {
+            k(self, i) || self.iterate(r, count, i, k)
+        }
+    }
+
+    /// One more iteration of `r`, followed by the rest of the repetition
+    fn iterate(&mut self, r: &Repeat, count: usize, i: usize, k: &mut Cont<'_, 'a>) -> bool {
+        if r.max.map_or(false, |max| count >= max) {
+            return false;
+        }
+        let saved = self.caps[r.groups.clone()].to_vec();
+        for cap in &mut self.caps[r.groups.clone()] {
+            *cap = None;
+        }
+        let matched = self.node(&r.node, i, &mut |m: &mut Matcher<'a>, j| {
+            // an optional iteration that matches the empty string would
+            // repeat forever
+            if j == i && count >= r.min {
+                return false;
+            }
+            m.repeat(r, count + 1, j, k)
+        });
+        if !matched {
+            self.caps[r.groups.clone()].copy_from_slice(&saved);
+        }
+        matched
+    }
+
+    /// `r` when its node matches exactly one character. We count how many
+    /// characters it can match instead of recursing, so that long inputs do
+    /// not exhaust the stack
+    fn repeat_single(&mut self, r: &Repeat, i: usize, k: &mut Cont<'_, 'a>) -> bool {
+        let max = r.max.unwrap_or(usize::MAX);
+        let mut n = 0;
+        while n < max && self.single(&r.node, i + n) {
+            n += 1;
+        }
+        if n < r.min {
+            false
+        } else if r.greedy {
+            (r.min..=n).rev().any(|count| k(self, i + count))
+        } else {
+            (r.min..=n).any(|count| k(self, i + count))
+        }
+    }
+
+    fn single(&self, node: &Node, i: usize) -> bool {
+        let c = match self.input.get(i) {
+            Some(c) => *c,
+            None => return false,
+        };
+        match node {
+            Node::Char(x) => self.eq(*x, c),
+            Node::Dot => self.flags.dot_all || !is_line_end(c),
+            Node::Class(class) => self.class_matches(class, c),
+            _ => unreachable!("not a single-character node"),
+        }
+    }
+
+    fn eq(&self, a: char, b: char) -> bool {
+        a == b || self.flags.ignore_case && canonicalize(a) == canonicalize(b)
+    }
+
+    fn class_matches(&self, class: &Class, c: char) -> bool {
+        let matches = |c| class.items.iter().any(|item| item.matches(c));
+        let found = matches(c)
+            || self.flags.ignore_case && (matches(canonicalize(c)) || matches(single_lowercase(c)));
+        found != class.negated
+    }
+}
+
+impl ClassItem {
+    fn matches(&self, c: char) -> bool {
+        match *self {
+            ClassItem::Range(lo, hi) => lo <= c && c <= hi,
+            ClassItem::Digit(negated) => c.is_ascii_digit() != negated,
+            ClassItem::Word(negated) => is_word(c) != negated,
+            ClassItem::Space(negated) => is_space(c) != negated,
+        }
+    }
+}
+
+fn is_single(node: &Node) -> bool {
+    matches!(node, Node::Char(_) | Node::Dot | Node::Class(_))
+}
+
+fn is_word(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn is_line_end(c: char) -> bool {
+    matches!(c, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
+
+fn is_space(c: char) -> bool {
+    matches!(
+        c,
+        '\t' | '\u{b}' | '\u{c}' | ' ' | '\u{a0}' | '\u{1680}' | '\u{2000}'
+            ..='\u{200a}' | '\u{202f}' | '\u{205f}' | '\u{3000}' | '\u{feff}'
+    ) || is_line_end(c)
+}
+
+/// The uppercase form of `c`, as the `i` flag compares characters. Like
+/// JavaScript, we never map a non-ASCII character to an ASCII one
+fn canonicalize(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(u), None) if c.is_ascii() || !u.is_ascii() => u,
+        _ => c,
+    }
+}
+
+fn single_lowercase(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) => l,
+        _ => c,
+    }
+}
+
+/// The `RegExp` that `val` points to, if it is one. Its `lastIndex` is
+/// whatever the program last wrote to its field
+fn regexp_of(val: AnyValue) -> Option<RegExpPtr> {
+    match *val {
+        AnyEnum::Ptr(ptr) => match ptr.view() {
+            HeapRefView::RegExp(mut re) => {
+                if let Some(fields) = re.fields {
+                    let last_index =
+                        fields.get(heap(), static_strings().last_index, &mut no_cache());
+                    let last_index = any_to_f64(last_index.into());
+                    re.last_index = if last_index >= 0. {
+                        last_index as usize
+                    } else {
+                        0
+                    };
+                }
+                Some(re)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The object that holds the fields of `re`, which is allocated the first
+/// time that it is needed. Its `lastIndex` is updated to where the next
+/// search starts
+pub(crate) fn regexp_fields(mut re: RegExpPtr) -> ObjectPtr {
+    let heap = heap();
+    let strings = static_strings();
+    let mut fields = match re.fields {
+        Some(fields) => fields,
+        None => {
+            heap.push_shadow_frame(2);
+            heap.set_in_current_shadow_frame_slot(0, Some(re.get_ptr()));
+            let mut fields = object_empty();
+            heap.set_in_current_shadow_frame_slot(1, Some(fields.get_ptr()));
+            let source = heap.alloc_str_or_gc(&re.source);
+            let source = AnyEnum::Ptr(source.as_any_ptr()).into();
+            fields.insert(heap, strings.source, source, &mut no_cache());
+            let flags = heap.alloc_str_or_gc(&re.flags.to_string());
+            let flags = AnyEnum::Ptr(flags.as_any_ptr()).into();
+            fields.insert(heap, strings.flags, flags, &mut no_cache());
+            let re_flags = re.flags;
+            for (name, flag) in &[
+                (strings.global, re_flags.global),
+                (strings.ignore_case, re_flags.ignore_case),
+                (strings.multiline, re_flags.multiline),
+                (strings.sticky, re_flags.sticky),
+            ] {
+                fields.insert(heap, *name, AnyEnum::Bool(*flag).into(), &mut no_cache());
+            }
+            unsafe { heap.pop_shadow_frame() };
+            re.fields = Some(fields);
+            fields
+        }
+    };
+    let last_index = AnyEnum::I32(re.last_index as i32).into();
+    fields.insert(heap, strings.last_index, last_index, &mut no_cache());
+    fields
+}
+
+fn to_chars(val: AnyValue) -> Vec<char> {
+    match *val {
+        AnyEnum::Ptr(ptr) => match ptr.view() {
+            HeapRefView::String(s) => s.chars().collect(),
+            _ => val.to_string().chars().collect(),
+        },
+        _ => val.to_string().chars().collect(),
+    }
+}
+
+/// The strings that each group in `caps` matched, where `None` is a group
+/// that did not match
+fn captured(input: &[char], caps: &[Option<(usize, usize)>]) -> Vec<Option<String>> {
+    caps.iter()
+        .map(|cap| cap.map(|(start, end)| input[start..end].iter().collect()))
+        .collect()
+}
+
+/// The array that `exec` produces, which is also the array that `match`
+/// produces when `re` is not global: the strings that the match and each
+/// group matched, with the `index` of the match and the `input`
+fn match_array(input: &[char], caps: Option<Captures>) -> AnyValue {
+    let caps = match caps {
+        Some(caps) => caps,
+        None => return AnyEnum::Null.into(),
+    };
+    let heap = heap();
+    let strings = static_strings();
+    let array = alloc_strings(captured(input, &caps));
+    heap.push_shadow_frame(1);
+    heap.set_in_current_shadow_frame_slot(0, Some(array.get_ptr()));
+    let mut fields = array_fields(array);
+    let index = AnyEnum::I32(caps[0].unwrap().0 as i32).into();
+    fields.insert(heap, strings.index, index, &mut no_cache());
+    let input = heap.alloc_str_or_gc(&input.iter().collect::<String>());
+    let input = AnyEnum::Ptr(input.as_any_ptr()).into();
+    fields.insert(heap, strings.input, input, &mut no_cache());
+    unsafe { heap.pop_shadow_frame() };
+    AnyEnum::Ptr(array.as_any_ptr()).into()
+}
+
+/// The array that `match` produces when `re` is global, or `null`
+fn strings_or_null(strings: Option<Vec<Option<String>>>) -> AnyValue {
+    match strings {
+        Some(strings) => AnyEnum::Ptr(alloc_strings(strings).as_any_ptr()).into(),
+        None => AnyEnum::Null.into(),
+    }
+}
+
+fn find(input: &[char], pat: &[char], from: usize) -> Option<usize> {
+    (from..=input.len()).find(|&i| input[i..].starts_with(pat))
+}
+
+/// Expand the `$` patterns of a replacement string:
+/// https://www.ecma-international.org/ecma-262/5.1/#sec-15.5.4.11
+fn substitute(
+    out: &mut String,
+    replacement: &[char],
+    input: &[char],
+    caps: &[Option<(usize, usize)>],
+    re: Option<&RegExp>,
+) {
+    let (start, end) = caps[0].unwrap();
+    let push_group = |out: &mut String, n: usize| {
+        if let Some((start, end)) = caps[n] {
+            out.extend(&input[start..end]);
+        }
+    };
+    let mut i = 0;
+    while i < replacement.len() {
+        let c = replacement[i];
+        i += 1;
+        if c != '$' || i == replacement.len() {
+            out.push(c);
+            continue;
+        }
+        match replacement[i] {
+            '$' => out.push('$'),
+            '&' => out.extend(&input[start..end]),
+            '`' => out.extend(&input[..start]),
+            '\'' => out.extend(&input[end..]),
+            d if d.is_ascii_digit() => {
+                // $nn when that is a group, and $n otherwise
+                let one = d.to_digit(10).unwrap() as usize;
+                let two = replacement
+                    .get(i + 1)
+                    .and_then(|c| c.to_digit(10))
+                    .map(|d| one * 10 + d as usize);
+                match two.filter(|n| (1..caps.len()).contains(n)) {
+                    Some(n) => {
+                        push_group(out, n);
+                        i += 1;
+                    }
+                    None if (1..caps.len()).contains(&one) => push_group(out, one),
+                    None => {
+                        out.push('$');
+                        continue;
+                    }
+                }
+            }
+            '<' => {
+                let close = replacement[i..].iter().position(|c| *c == '>');
+                match (re.filter(|re| !re.names.is_empty()), close) {
+                    (Some(re), Some(close)) => {
+                        let name: String = replacement[i + 1..i + close].iter().collect();
+                        if let Some(n) = re.group_named(&name) {
+                            push_group(out, n);
+                        }
+                        i += close;
+                    }
+                    _ => {
+                        out.push('$');
+                        continue;
+                    }
+                }
+            }
+            _ => {
+                out.push('$');
+                continue;
+            }
+        }
+        i += 1;
+    }
+}
+
+fn replace_regexp(re: &mut RegExp, input: &[char], replacement: &[char]) -> String {
+    let global = re.flags.global;
+    if global {
+        re.last_index = 0;
+    }
+    let mut result = String::new();
+    let mut next = 0;
+    while let Some(caps) = re.exec(input) {
+        let (start, end) = caps[0].unwrap();
+        result.extend(&input[next..start]);
+        substitute(&mut result, replacement, input, &caps, Some(re));
+        next = end;
+        if !global {
+            break;
+        }
+        if start == end {
+            re.last_index = end + 1;
+        }
+    }
+    result.extend(&input[next..]);
+    result
+}
+
+fn split_regexp(re: &RegExp, input: &[char]) -> Vec<Option<String>> {
+    if input.is_empty() {
+        return match re.match_at(input, 0) {
+            Some(_) => vec![],
+            None => vec![Some(String::new())],
+        };
+    }
+    let mut parts = vec![];
+    let (mut p, mut q) = (0, 0);
+    while q < input.len() {
+        match re.match_at(input, q) {
+            Some(caps) if caps[0].unwrap().1 != p => {
+                parts.push(Some(input[p..q].iter().collect()));
+                parts.extend(captured(input, &caps[1..]));
+                p = caps[0].unwrap().1;
+                q = p;
+            }
+            _ => q += 1,
+        }
+    }
+    parts.push(Some(input[p..].iter().collect()));
+    parts
+}
+
+fn split_str(input: &[char], sep: &[char]) -> Vec<Option<String>> {
+    if sep.is_empty() {
+        return input.iter().map(|c| Some(c.to_string())).collect();
+    }
+    let mut parts = vec![];
+    let mut p = 0;
+    while let Some(q) = find(input, sep, p) {
+        parts.push(Some(input[p..q].iter().collect()));
+        p = q + sep.len();
+    }
+    parts.push(Some(input[p..].iter().collect()));
+    parts
+}
+
+/// `/source/flags`
+#[no_mangle]
+pub extern "C" fn regexp_new(source: StringPtr, flags: StringPtr) -> AnyValue {
+    match RegExp::new(&source, &flags) {
+        Ok(re) => AnyEnum::Ptr(heap().alloc_or_gc(re).as_any_ptr()).into(),
+        Err(err) => log_panic!("Invalid regular expression: /{}/: {}", &*source, err),
+    }
+}
+
+fn this_regexp(this: AnyValue) -> RegExpPtr {
+    match regexp_of(this) {
+        Some(re) => re,
+        None => log_panic!("{:?} is not a regular expression", *this),
+    }
+}
+
+/// `re.test(s)`
+#[no_mangle]
+pub extern "C" fn any_test(this: AnyValue, s: AnyValue) -> AnyValue {
+    let input = to_chars(s);
+    let found = this_regexp(this).exec(&input).is_some();
+    AnyEnum::Bool(found).into()
+}
+
+/// `re.exec(s)`: the strings that the match and each group matched, with
+/// the `index` of the match and the `input`, or `null`
+#[no_mangle]
+pub extern "C" fn any_exec(this: AnyValue, s: AnyValue) -> AnyValue {
+    let input = to_chars(s);
+    let caps = this_regexp(this).exec(&input);
+    match_array(&input, caps)
+}
+
+/// `s.match(re)`, where `re` is converted to a regular expression if it
+/// isn't one
+#[no_mangle]
+pub extern "C" fn string_match(s: StringPtr, re: AnyValue) -> AnyValue {
+    let input: Vec<char> = s.chars().collect();
+    match regexp_of(re) {
+        Some(mut re) => match_regexp(&mut re, &input),
+        None => {
+            let source: String = match *re {
+                AnyEnum::Undefined => String::new(),
+                _ => to_chars(re).into_iter().collect(),
+            };
+            match RegExp::new(&source, "") {
+                Ok(mut re) => match_regexp(&mut re, &input),
+                Err(err) => log_panic!("Invalid regular expression: /{}/: {}", source, err),
+            }
+        }
+    }
+}
+
+fn match_regexp(re: &mut RegExp, input: &[char]) -> AnyValue {
+    if !re.flags.global {
+        let caps = re.exec(input);
+        return match_array(input, caps);
+    }
+    re.last_index = 0;
+    let mut matches = vec![];
+    while let Some(caps) = re.exec(input) {
+        let (start, end) = caps[0].unwrap();
+        matches.push(Some(input[start..end].iter().collect()));
+        if start == end {
+            re.last_index = end + 1;
+        }
+    }
+    strings_or_null(if matches.is_empty() {
+        None
+    } else {
+        Some(matches)
+    })
+}
+
+/// `s.replace(pattern, replacement)`, where `replacement` is a string
+#[no_mangle]
+pub extern "C" fn string_replace(
+    s: StringPtr,
+    pattern: AnyValue,
+    replacement: AnyValue,
+) -> StringPtr {
+    if let AnyEnum::Closure(_) = *replacement {
+        log_panic!("replace with a function is not supported");
+    }
+    let input: Vec<char> = s.chars().collect();
+    let replacement = to_chars(replacement);
+    let result = match regexp_of(pattern) {
+        Some(mut re) => replace_regexp(&mut re, &input, &replacement),
+        None => {
+            let pattern = to_chars(pattern);
+            match find(&input, &pattern, 0) {
+                None => input.iter().collect(),
+                Some(start) => {
+                    let end = start + pattern.len();
+                    let mut result: String = input[..start].iter().collect();
+                    substitute(
+                        &mut result,
+                        &replacement,
+                        &input,
+                        &[Some((start, end))],
+                        None,
+                    );
+                    result.extend(&input[end..]);
+                    result
+                }
+            }
+        }
+    };
+    heap().alloc_str_or_gc(&result)
+}
+
+/// `s.split(separator)`
+#[no_mangle]
+pub extern "C" fn string_split(s: StringPtr, separator: AnyValue) -> ArrayPtr {
+    let input: Vec<char> = s.chars().collect();
+    let parts = match (*separator, regexp_of(separator)) {
+        (AnyEnum::Undefined, _) => vec![Some(input.iter().collect())],
+        (_, Some(re)) => split_regexp(&re, &input),
+        (_, None) => split_str(&input, &to_chars(separator)),
+    };
+    alloc_strings(parts)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::any_value::any_to_object;
+    use crate::init;
+    use wasm_bindgen_test::*;
+
+    fn exec(source: &str, flags: &str, input: &str) -> Option<Vec<Option<String>>> {
+        let mut re = RegExp::new(source, flags).unwrap();
+        let input: Vec<char> = input.chars().collect();
+        re.exec(&input).map(|caps| captured(&input, &caps))
+    }
+
+    fn strings(strs: &[&str]) -> Option<Vec<Option<String>>> {
+        Some(strs.iter().map(|s| Some(s.to_string())).collect())
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn backtracking() {
+        assert_eq!(
+            exec("(a|ab)(c|bcd)(d*)", "", "abcd"),
+            strings(&["abcd", "a", "bcd", ""])
+        );
+        assert_eq!(exec("a.*?b", "", "aXbYb"), strings(&["aXb"]));
+        assert_eq!(
+            exec("(?=(a+))a*b\\1", "", "baaabac"),
+            strings(&["aba", "a"])
+        );
+        assert_eq!(exec("(a*)*", "", "b"), Some(vec![Some("".into()), None]));
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn flags() {
+        assert_eq!(exec("^abc$", "i", "ABC"), strings(&["ABC"]));
+        assert_eq!(exec("^b", "m", "a\nb"), strings(&["b"]));
+        assert_eq!(exec("^b", "", "a\nb"), None);
+        let mut re = RegExp::new("o", "g").unwrap();
+        let input: Vec<char> = "foo".chars().collect();
+        assert_eq!(re.exec(&input).unwrap()[0], Some((1, 2)));
+        assert_eq!(re.exec(&input).unwrap()[0], Some((2, 3)));
+        assert_eq!(re.exec(&input), None);
+        assert_eq!(re.last_index, 0);
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn syntax_errors() {
+        assert!(RegExp::new("a)", "").is_err());
+        assert!(RegExp::new("*a", "").is_err());
+        assert!(RegExp::new("[b-a]", "").is_err());
+        assert!(RegExp::new("a", "gg").is_err());
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn split_with_captures() {
+        init();
+        let s = heap().alloc_str_or_gc("x-y_z");
+        let sep = regexp_new(heap().alloc_str_or_gc("([-_])"), heap().alloc_str_or_gc(""));
+        let parts = string_split(s, sep);
+        let parts: Vec<String> = parts.iter().map(|p| format!("{}", **p)).collect();
+        assert_eq!(parts, vec!["x", "-", "y", "_", "z"]);
+    }
+
+    /// The field `name` of `val` as an object
+    fn field(val: AnyValue, name: StringPtr) -> AnyEnum {
+        match any_to_object(val).view() {
+            HeapRefView::ObjectPtrPtr(fields) => fields.get(heap(), name, &mut no_cache()),
+            _ => panic!("{:?} does not have fields", *val),
+        }
+    }
+
+    #[test]
+    #[wasm_bindgen_test]
+    fn exec_fields() {
+        init();
+        let strings = static_strings();
+        let re = regexp_new(heap().alloc_str_or_gc("b"), heap().alloc_str_or_gc("g"));
+        let input = AnyEnum::Ptr(heap().alloc_str_or_gc("abc").as_any_ptr()).into();
+        let found = any_exec(re, input);
+        assert_eq!(field(found, strings.index), AnyEnum::I32(1));
+        assert_eq!(field(re, strings.last_index), AnyEnum::I32(2));
+        assert_eq!(field(re, strings.global), AnyEnum::Bool(true));
+    }
+}
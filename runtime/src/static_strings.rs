@@ -18,6 +18,31 @@ static mut STATIC_STRINGS: Option<StaticStrings> = None;
 pub struct StaticStrings {
     pub __proto__: StringPtr,
     pub prototype: StringPtr,
+    pub name: StringPtr,
+    pub message: StringPtr,
+    /// The key of `Symbol.iterator`, see `ITERATOR_KEY` in libjankscripten
+    pub iterator: StringPtr,
+    /// The field of the strings of a tagged template that holds their raw
+    /// forms
+    pub raw: StringPtr,
+    // the fields of the array that `exec` produces
+    pub index: StringPtr,
+    pub input: StringPtr,
+    // the fields of a regular expression
+    pub last_index: StringPtr,
+    pub source: StringPtr,
+    pub flags: StringPtr,
+    pub global: StringPtr,
+    pub ignore_case: StringPtr,
+    pub multiline: StringPtr,
+    pub sticky: StringPtr,
+    // the fields of property descriptors
+    pub value: StringPtr,
+    pub get: StringPtr,
+    pub set: StringPtr,
+    pub writable: StringPtr,
+    pub enumerable: StringPtr,
+    pub configurable: StringPtr,
 }
 
 pub fn init() {
@@ -25,6 +50,25 @@ pub fn init() {
         STATIC_STRINGS = Some(StaticStrings {
             __proto__: mkstr32("__proto__"),
             prototype: mkstr32("prototype"),
+            name: mkstr32("name"),
+            message: mkstr32("message"),
+            iterator: mkstr32("@@iterator"),
+            raw: mkstr32("raw"),
+            index: mkstr32("index"),
+            input: mkstr32("input"),
+            last_index: mkstr32("lastIndex"),
+            source: mkstr32("source"),
+            flags: mkstr32("flags"),
+            global: mkstr32("global"),
+            ignore_case: mkstr32("ignoreCase"),
+            multiline: mkstr32("multiline"),
+            sticky: mkstr32("sticky"),
+            value: mkstr32("value"),
+            get: mkstr32("get"),
+            set: mkstr32("set"),
+            writable: mkstr32("writable"),
+            enumerable: mkstr32("enumerable"),
+            configurable: mkstr32("configurable"),
         });
     }
 }
@@ -1,4 +1,5 @@
 use crate::any_value::{AnyValue as Any, *};
+use crate::bigint::bigint_of;
 use crate::heap_types::EnvPtr;
 use crate::wasm32::heap;
 
@@ -9,15 +10,20 @@ pub extern "C" fn parse_int(env: EnvPtr, this: Any, a: Any, radix_any: Any) -> A
     match *a {
         AnyEnum::I32(i) => AnyEnum::I32(i).into(),
         AnyEnum::F64(f) => AnyEnum::I32(f as i32).into(),
-        AnyEnum::Bool(_) | AnyEnum::Closure(_) | AnyEnum::Undefined | AnyEnum::Null => {
-            heap().f64_to_any(f64::NAN)
-        }
+        AnyEnum::Bool(_)
+        | AnyEnum::Closure(_)
+        | AnyEnum::Undefined
+        | AnyEnum::Null
+        | AnyEnum::Hole => heap().f64_to_any(f64::NAN),
         AnyEnum::Ptr(ptr) => match ptr.view() {
             HeapRefView::String(s) => match i32::from_str_radix(&*s, radix as u32) {
                 Ok(o) => AnyEnum::I32(o).into(),
                 Err(_) => todo!("support partial parse"),
             },
-            HeapRefView::HT(_) | HeapRefView::ObjectPtrPtr(_) => heap().f64_to_any(f64::NAN),
+            HeapRefView::HT(_) | HeapRefView::ObjectPtrPtr(_) | HeapRefView::RegExp(_) => {
+                heap().f64_to_any(f64::NAN)
+            }
+            HeapRefView::BigInt(n) => AnyEnum::I32(n.to_f64() as i32).into(),
             HeapRefView::Array(a) => parse_int(env, this, a[0], radix_any),
             HeapRefView::Any(what) => parse_int(env, this, *what, radix_any),
             HeapRefView::Class(_)
@@ -30,9 +36,13 @@ pub extern "C" fn parse_int(env: EnvPtr, this: Any, a: Any, radix_any: Any) -> A
     }
 }
 
-/// we return undefined for type messiness for now
+/// we return undefined for type messiness for now. like node, we print a
+/// BigInt with an `n` after its digits
 #[no_mangle]
 pub extern "C" fn console_log(_: EnvPtr, _this: Any, a: Any) -> Any {
-    log!("{}", *a);
+    match bigint_of(*a) {
+        Some(n) => log!("{}n", *n),
+        None => log!("{}", *a),
+    }
     AnyEnum::Undefined.into()
 }
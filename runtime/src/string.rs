@@ -1,7 +1,7 @@
 //! create managed strings and manipulate them
 
 pub use super::heap_types::StringPtr;
-use crate::heap;
+use crate::{heap, AnyEnum, AnyValue, HeapPtr};
 
 #[no_mangle]
 pub extern "C" fn string_length(string: StringPtr) -> i32 {
@@ -30,6 +30,16 @@ pub extern "C" fn string_char_at(s: StringPtr, i: usize) -> StringPtr {
     heap().alloc_str_or_gc(c.to_string().as_str())
 }
 
+/// `s[i]`: the character at `i` as a string, or `undefined` when `i` is out
+/// of bounds
+#[no_mangle]
+pub extern "C" fn string_index(s: StringPtr, i: i32) -> AnyValue {
+    if i < 0 || i >= s.len() as i32 {
+        return AnyEnum::Undefined.into();
+    }
+    AnyEnum::Ptr(string_char_at(s, i as usize).as_any_ptr()).into()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -55,6 +65,14 @@ mod test {
     }
     #[test]
     #[wasm_bindgen_test]
+    fn index_in_and_out_of_bounds() {
+        init();
+        let s = heap().alloc_str_or_gc("quartz");
+        assert_eq!(format!("{}", *string_index(s, 1)), "u");
+        assert_eq!(string_index(s, 6), AnyEnum::Undefined.into());
+    }
+    #[test]
+    #[wasm_bindgen_test]
     fn string_append_hello_world() {
         init();
         let a = heap().alloc_str_or_gc("Hello");